@@ -4,6 +4,7 @@ import { TranslatorView } from './components/TranslatorView';
 import { SettingsModal } from './components/SettingsModal';
 import { OcrModal } from './components/OcrModal';
 import { QuickTranslateWindow } from './components/QuickTranslateWindow';
+import { PinnedTranslationWindow } from './components/PinnedTranslationWindow';
 import { ErrorBoundary } from './components/ErrorBoundary';
 import { useOcrDependencies } from './hooks/useOcrDependencies';
 import { useAppStore } from './store/useAppStore';
@@ -14,17 +15,19 @@ const App: React.FC = () => {
   const [showSettings, setShowSettings] = useState(false);
   const [showOCR, setShowOCR] = useState(false);
   const hasPrompted = useRef(false);
-  const { updateSettings, provider } = useAppStore();
+  const { provider } = useAppStore();
 
   // Get current provider info
   const currentProvider = PROVIDERS.find(p => p.id === provider);
 
   // Initialize directly from URL to avoid flash/race conditions
-  const [isQuickMode] = useState(() => {
+  const [windowMode] = useState(() => {
     const params = new URLSearchParams(window.location.search);
     console.log('App mounted. Search params:', window.location.search);
-    return params.get('mode') === 'quick';
+    return params.get('mode');
   });
+  // Pinned windows are detached quick windows and share their setup
+  const isQuickMode = windowMode === 'quick' || windowMode === 'pinned';
   useEffect(() => {
     const root = document.documentElement;
     root.dataset.windowMode = isQuickMode ? 'quick' : 'main';
@@ -46,31 +49,56 @@ const App: React.FC = () => {
     }
   }, []);
 
+  // The backend owns the settings: import the former localStorage copy once,
+  // then follow its changes (tray, D-Bus, other windows)
+  useEffect(() => {
+    if (!platform.isAvailable()) return;
+    const { applyBackendSettings } = useAppStore.getState();
+    let cancelled = false;
+    let label: string | null = null;
+
+    const load = async () => {
+      label = await platform.getWindowLabel();
+      const stored = localStorage.getItem('light-translator-storage');
+      const settings = windowMode === null && stored
+        ? await platform.importFrontendSettings(stored).catch((error) => {
+          console.error('Failed to import settings:', error);
+          return platform.getSettings();
+        })
+        : await platform.getSettings();
+      if (settings && !cancelled) applyBackendSettings(settings);
+    };
+    load().catch((error) => console.error('Failed to load settings:', error));
+
+    const unlisten = platform.onSettingsChanged(({ settings, origin }) => {
+      // This window already has its own changes, possibly newer ones
+      if (origin !== null && origin === label) return;
+      applyBackendSettings(settings);
+    });
+    return () => {
+      cancelled = true;
+      unlisten();
+    };
+  }, [windowMode]);
+
+  // Runs the command line action (--text, --settings, ...) once the listeners
+  // above and in TranslatorView are registered
+  useEffect(() => {
+    if (windowMode === null && platform.isAvailable()) {
+      platform.sendMainReady();
+    }
+  }, [windowMode]);
+
   // Resize main window when settings modal opens/closes
   useEffect(() => {
     if (showSettings && platform.isAvailable()) {
       platform.resizeMainWindow({ width: 820, height: 680 });
       return () => {
-        platform.resizeMainWindow({ width: 480, height: 680 });
+        platform.restoreMainWindowSize();
       };
     }
   }, [showSettings]);
 
-  // Sync auto-launch state with system on app startup
-  useEffect(() => {
-    const syncAutoLaunchState = async () => {
-      if (platform.isAvailable()) {
-        try {
-          const enabled = await platform.getAutoLaunch();
-          updateSettings({ launchAtStartup: enabled });
-        } catch (error) {
-          console.error('Failed to sync auto-launch state:', error);
-        }
-      }
-    };
-    syncAutoLaunchState();
-  }, [updateSettings]);
-
   // Prompt user to install OCR dependencies if missing (only once on first check)
   useEffect(() => {
     if (ocrStatus.checked && !ocrStatus.available && !hasPrompted.current && !isQuickMode) {
@@ -83,6 +111,14 @@ const App: React.FC = () => {
     }
   }, [ocrStatus.checked, ocrStatus.available, isQuickMode, promptAndInstall]);
 
+  if (windowMode === 'pinned') {
+    return (
+      <ErrorBoundary>
+        <PinnedTranslationWindow />
+      </ErrorBoundary>
+    );
+  }
+
   if (isQuickMode) {
     return (
       <ErrorBoundary>
@@ -30,7 +30,8 @@ export const OcrModal: React.FC<OcrModalProps> = ({ onClose }) => {
     openaiApiKey,
     openaiBaseUrl,
     openaiModel,
-    deeplApiKey
+    deeplApiKey,
+    formality
   } = useAppStore();
 
   const handleFileChange = (e: React.ChangeEvent<HTMLInputElement>) => {
@@ -104,7 +105,8 @@ export const OcrModal: React.FC<OcrModalProps> = ({ onClose }) => {
         openaiApiKey,
         openaiBaseUrl,
         openaiModel,
-        deeplApiKey
+        deeplApiKey,
+        formality: formality || undefined
       });
 
       setTranslatedText(translatedResult);
@@ -0,0 +1,98 @@
+import React, { useEffect, useState } from 'react';
+import { X, Copy, Check } from 'lucide-react';
+import { useAppStore } from '../store/useAppStore';
+import { PROVIDERS, LANGUAGES } from '../constants';
+import { platform, PinnedContent } from '../src/lib/platform';
+
+export const PinnedTranslationWindow: React.FC = () => {
+  useEffect(() => {
+    document.body.style.background = 'transparent';
+    return () => { document.body.style.background = ''; };
+  }, []);
+
+  const [content, setContent] = useState<PinnedContent | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
+  const [showSource, setShowSource] = useState(false);
+
+  const { quickWindowOpacity, quickWindowBorderOpacity } = useAppStore();
+
+  useEffect(() => {
+    platform.getPinnedContent()
+      .then(setContent)
+      .catch((err: any) => setError(typeof err === 'string' ? err : err.message || 'Failed to load translation'));
+  }, []);
+
+  const handleCopy = () => {
+    if (!content) return;
+    navigator.clipboard.writeText(content.translation);
+    setCopied(true);
+    setTimeout(() => setCopied(false), 1500);
+  };
+
+  const providerName = PROVIDERS.find(p => p.id === content?.provider)?.name || content?.provider;
+  const targetName = LANGUAGES.find((l) => l.code === content?.targetLang)?.name || content?.targetLang;
+
+  return (
+    <div
+      className="h-screen w-screen backdrop-blur-3xl rounded-3xl flex flex-col overflow-hidden relative"
+      style={{
+        backgroundColor: `rgba(255, 255, 255, ${quickWindowOpacity})`,
+        border: `1px solid rgba(150, 150, 150, ${quickWindowBorderOpacity * 2})`
+      }}
+    >
+      {/* Header / Drag Area */}
+      <div
+        className="h-8 bg-gray-100/80 flex items-center justify-between px-3 -webkit-app-region-drag border-b border-gray-200/50"
+        data-tauri-drag-region
+      >
+        <span className="text-xs font-medium text-gray-600 pointer-events-none select-none truncate">
+          {content ? `${providerName} · ${targetName}` : 'Pinned Translation'}
+        </span>
+        <div className="flex items-center gap-1">
+          <button
+            onClick={handleCopy}
+            disabled={!content}
+            title="Copy"
+            className="p-1 hover:bg-gray-200 rounded-full -webkit-app-region-no-drag transition-colors"
+          >
+            {copied ? <Check size={13} className="text-green-500" /> : <Copy size={13} className="text-gray-500" />}
+          </button>
+          <button
+            onClick={() => platform.closePinnedWindow()}
+            title="Close"
+            className="p-1 hover:bg-gray-200 rounded-full -webkit-app-region-no-drag transition-colors"
+          >
+            <X size={14} className="text-gray-500" />
+          </button>
+        </div>
+      </div>
+
+      {/* Content */}
+      <div className="flex-1 overflow-y-auto p-4 space-y-2">
+        {error ? (
+          <div className="text-sm text-red-500 bg-red-50 p-2 rounded border border-red-100">
+            {error}
+          </div>
+        ) : content && (
+          <>
+            <div className="text-sm text-gray-900 font-medium leading-relaxed break-words whitespace-pre-wrap select-text">
+              {content.translation}
+            </div>
+            <button
+              onClick={() => setShowSource((prev) => !prev)}
+              className="text-[11px] text-gray-400 hover:text-gray-600 transition-colors"
+            >
+              {showSource ? 'Hide original' : 'Show original'}
+            </button>
+            {showSource && (
+              <div className="text-xs text-gray-500 leading-relaxed break-words whitespace-pre-wrap select-text">
+                {content.text}
+              </div>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+};
@@ -1,11 +1,11 @@
 import React, { useEffect, useState, useRef, useCallback } from 'react';
-import { X, Loader2, ChevronDown } from 'lucide-react';
+import { X, Loader2, ChevronDown, Pin } from 'lucide-react';
 import { useAppStore } from '../store/useAppStore';
 import { translateText } from '../services/geminiService';
 import { cleanTextLineBreaks } from '../utils/textUtils';
 import { PROVIDERS, LANGUAGES } from '../constants';
-import { platform } from '../src/lib/platform';
-import { LanguageCode } from '../types';
+import { platform, QuickTranslateRequest } from '../src/lib/platform';
+import { LanguageCode, TranslationProviderId } from '../types';
 
 // Window size constraints
 const MIN_WIDTH = 100;
@@ -29,8 +29,20 @@ export const QuickTranslateWindow: React.FC = () => {
   const [sourceText, setSourceText] = useState('');
   const [translated, setTranslated] = useState('');
   const [loading, setLoading] = useState(false);
+  // Streamed output of superseded requests is dropped
+  const latestRequestRef = useRef(0);
   const [error, setError] = useState<string | null>(null);
   const [langDropdownOpen, setLangDropdownOpen] = useState(false);
+  // Replace-selection preview: the translation waits for confirmation
+  const [pendingReplace, setPendingReplace] = useState(false);
+  const pendingReplaceRef = useRef(false);
+  // Overrides from the application rule that matched when the text was captured
+  const overridesRef = useRef<Omit<QuickTranslateRequest, 'text'>>({});
+  const [overrides, setOverridesState] = useState<Omit<QuickTranslateRequest, 'text'>>({});
+  const setOverrides = (next: Omit<QuickTranslateRequest, 'text'>) => {
+    overridesRef.current = next;
+    setOverridesState(next);
+  };
   const contentRef = useRef<HTMLDivElement>(null);
   const dropdownRef = useRef<HTMLDivElement>(null);
 
@@ -42,14 +54,6 @@ export const QuickTranslateWindow: React.FC = () => {
     setQuickTargetLang,
   } = useAppStore();
 
-  const refreshSettings = useCallback(async () => {
-    try {
-      await useAppStore.persist.rehydrate();
-    } catch (err) {
-      console.warn('Failed to refresh settings from storage:', err);
-    }
-  }, []);
-
   // Resize window to fit content
   const resizeToFitContent = useCallback(() => {
     if (!contentRef.current || !platform.isAvailable()) return;
@@ -75,14 +79,27 @@ export const QuickTranslateWindow: React.FC = () => {
     if (platform.isAvailable()) {
       platform.sendQuickReady();
 
-      const unlisten = platform.onQuickTranslate((receivedText: string) => {
+      const unlisten = platform.onQuickTranslate(({ text: receivedText, ...ruleOverrides }) => {
         console.log('Received text:', receivedText);
+        setOverrides(ruleOverrides);
         const cleanedText = cleanTextLineBreaks(receivedText);
+        setReplacePending(false);
         setSourceText(cleanedText);
         handleTranslate(cleanedText);
       });
 
-      return unlisten;
+      const unlistenPreview = platform.onReplacePreview(({ text, translation }) => {
+        setReplacePending(true);
+        setSourceText(text);
+        setError(null);
+        setLoading(false);
+        setTranslated(translation);
+      });
+
+      return () => {
+        unlisten();
+        unlistenPreview();
+      };
     }
   }, []);
 
@@ -90,7 +107,7 @@ export const QuickTranslateWindow: React.FC = () => {
   useEffect(() => {
     if (platform.isAvailable()) {
       const unlisten = platform.onWindowBlur(() => {
-        platform.closeQuickWindow();
+        handleClose();
       });
       return unlisten;
     }
@@ -115,9 +132,10 @@ export const QuickTranslateWindow: React.FC = () => {
     setTranslated('');
     setLoading(true);
     setError(null);
+    const requestId = ++latestRequestRef.current;
+    let partial = '';
 
     try {
-      await refreshSettings();
       const {
         quickTargetLang,
         provider,
@@ -129,38 +147,83 @@ export const QuickTranslateWindow: React.FC = () => {
         openaiModel,
         openrouterApiKey,
         openrouterModel,
+        anthropicModel,
+        ollamaModel,
         deeplApiKey,
+        formality,
         microsoftSubscriptionKey,
         microsoftRegion
       } = useAppStore.getState();
-      const result = await translateText(inputText, 'auto', quickTargetLang, {
-        provider,
+      const active = overridesRef.current;
+      const result = await translateText(inputText, active.sourceLang || 'auto', active.targetLang || quickTargetLang, {
+        provider: (active.provider as TranslationProviderId) || provider,
         modelId,
-        customSystemInstruction,
+        customSystemInstruction: active.systemPrompt || customSystemInstruction,
         geminiApiKey,
         openaiApiKey,
         openaiBaseUrl,
         openaiModel,
         openrouterApiKey,
         openrouterModel,
+        anthropicModel,
+        ollamaModel,
         deeplApiKey,
+        formality: active.formality || formality || undefined,
         microsoftSubscriptionKey,
-        microsoftRegion
+        microsoftRegion,
+        onPartial: (delta) => {
+          if (requestId === latestRequestRef.current) setTranslated(partial += delta);
+        }
       });
+      if (requestId !== latestRequestRef.current) return;
       setTranslated(result);
+      platform.addRecentTranslation(inputText, result).catch(() => {});
     } catch (err: any) {
-      setError(err.message || 'Translation failed');
+      if (requestId === latestRequestRef.current) setError(err.message || 'Translation failed');
     } finally {
-      setLoading(false);
+      if (requestId === latestRequestRef.current) setLoading(false);
     }
   };
 
+  const setReplacePending = (pending: boolean) => {
+    pendingReplaceRef.current = pending;
+    setPendingReplace(pending);
+  };
+
   const handleClose = () => {
     if (platform.isAvailable()) {
+      if (pendingReplaceRef.current) {
+        // Gives the user's clipboard back
+        setReplacePending(false);
+        platform.cancelReplace();
+      }
       platform.closeQuickWindow();
     }
   };
 
+  const handleConfirmReplace = async () => {
+    setReplacePending(false);
+    try {
+      await platform.confirmReplace(translated);
+    } catch (err: any) {
+      setError(typeof err === 'string' ? err : err.message || 'Replace failed');
+    }
+  };
+
+  // Keeps the translation on screen in its own window, freeing this one
+  const handlePin = async () => {
+    try {
+      await platform.pinQuickWindow({
+        text: sourceText,
+        translation: translated,
+        provider: overridesRef.current.provider || provider,
+        targetLang: overridesRef.current.targetLang || quickTargetLang,
+      });
+    } catch (err: any) {
+      setError(typeof err === 'string' ? err : err.message || 'Pin failed');
+    }
+  };
+
   const toggleDropdown = useCallback(() => {
     setLangDropdownOpen((prev) => {
       const next = !prev;
@@ -176,11 +239,13 @@ export const QuickTranslateWindow: React.FC = () => {
   }, [resizeToFitContent]);
 
   const handleSelectLang = (code: LanguageCode) => {
-    if (code === quickTargetLang) {
+    if (code === (overridesRef.current.targetLang || quickTargetLang)) {
       setLangDropdownOpen(false);
       return;
     }
     setQuickTargetLang(code);
+    // An explicit choice wins over the application rule
+    setOverrides({ ...overridesRef.current, targetLang: undefined });
     setLangDropdownOpen(false);
     setTimeout(resizeToFitContent, 50);
     // Re-translate with the new target language
@@ -189,7 +254,9 @@ export const QuickTranslateWindow: React.FC = () => {
     }
   };
 
-  const currentTargetName = TARGET_LANGUAGES.find((l) => l.code === quickTargetLang)?.name || quickTargetLang;
+  const effectiveTargetLang = overrides.targetLang || quickTargetLang;
+  const effectiveProvider = overrides.provider || provider;
+  const currentTargetName = TARGET_LANGUAGES.find((l) => l.code === effectiveTargetLang)?.name || effectiveTargetLang;
 
   return (
     <div
@@ -205,14 +272,24 @@ export const QuickTranslateWindow: React.FC = () => {
         data-tauri-drag-region
       >
         <span className="text-xs font-medium text-gray-600 pointer-events-none select-none">
-          Powered by {PROVIDERS.find(p => p.id === provider)?.name || 'Unknown'}
+          Powered by {PROVIDERS.find(p => p.id === effectiveProvider)?.name || effectiveProvider || 'Unknown'}
         </span>
-        <button
-          onClick={handleClose}
-          className="p-1 hover:bg-gray-200 rounded-full -webkit-app-region-no-drag transition-colors"
-        >
-          <X size={14} className="text-gray-500" />
-        </button>
+        <div className="flex items-center gap-1">
+          <button
+            onClick={handlePin}
+            disabled={!translated || loading || pendingReplace}
+            title="Pin"
+            className="p-1 hover:bg-gray-200 rounded-full -webkit-app-region-no-drag transition-colors disabled:opacity-40 disabled:hover:bg-transparent"
+          >
+            <Pin size={13} className="text-gray-500" />
+          </button>
+          <button
+            onClick={handleClose}
+            className="p-1 hover:bg-gray-200 rounded-full -webkit-app-region-no-drag transition-colors"
+          >
+            <X size={14} className="text-gray-500" />
+          </button>
+        </div>
       </div>
 
       {/* Language Bar */}
@@ -234,7 +311,7 @@ export const QuickTranslateWindow: React.FC = () => {
                 key={lang.code}
                 onClick={() => handleSelectLang(lang.code)}
                 className={`w-full text-left px-3 py-1.5 text-[11px] transition-colors ${
-                  lang.code === quickTargetLang
+                  lang.code === effectiveTargetLang
                     ? 'bg-blue-50 text-blue-600 font-medium'
                     : 'text-gray-700 hover:bg-gray-50'
                 }`}
@@ -255,6 +332,29 @@ export const QuickTranslateWindow: React.FC = () => {
             <div className="text-sm text-red-500 bg-red-50 p-2 rounded border border-red-100">
               {error}
             </div>
+          ) : pendingReplace ? (
+            <div className="space-y-2">
+              <textarea
+                value={translated}
+                onChange={(e) => setTranslated(e.target.value)}
+                rows={Math.min(8, translated.split('\n').length + 1)}
+                className="w-full text-sm text-gray-900 font-medium leading-relaxed bg-white/70 border border-gray-200 rounded-lg p-2 outline-none focus:border-blue-400 resize-none"
+              />
+              <div className="flex justify-end gap-2">
+                <button
+                  onClick={handleClose}
+                  className="px-3 py-1 text-xs font-medium text-gray-700 bg-gray-200 rounded-lg hover:bg-gray-300 transition-colors"
+                >
+                  Cancel
+                </button>
+                <button
+                  onClick={handleConfirmReplace}
+                  className="px-3 py-1 text-xs font-medium text-white bg-macos-active rounded-lg hover:bg-macos-active/90 transition-colors"
+                >
+                  Replace
+                </button>
+              </div>
+            </div>
           ) : (
             <div className="text-sm text-gray-900 font-medium leading-relaxed break-words">
               {translated || <span className="text-gray-300 italic">Translating...</span>}
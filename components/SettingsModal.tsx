@@ -1,8 +1,9 @@
 import React, { useState, useEffect } from 'react';
-import { X, Save, Bot, Terminal, Zap, Globe, Cloud, Layout, Cpu, Image, Network, Keyboard, Power, MessageSquare, MousePointer2, Languages } from 'lucide-react';
+import { X, Save, Bot, Terminal, FileCode, Puzzle, Zap, Globe, Cloud, Layout, Cpu, Image, Network, Keyboard, Power, MessageSquare, MousePointer2, Languages, Clipboard, AppWindow, Plus, Trash2, Bell, Users, HardDrive } from 'lucide-react';
 import { useAppStore } from '../store/useAppStore';
 import { PROVIDERS, DEFAULT_SYSTEM_PROMPT, LANGUAGES } from '../constants';
-import { platform } from '../src/lib/platform';
+import { AppSettings, OutputMode, QuickPlacement, ClipboardWatchSettings, GgufTemplate } from '../types';
+import { platform, AppRule, ProfileList, Policy, ProviderModel, OllamaPullProgress, ProviderLanguage, CustomProviders, PluginList } from '../src/lib/platform';
 
 interface SettingsModalProps {
   onClose: () => void;
@@ -21,21 +22,76 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
     openaiModel,
     openrouterApiKey,
     openrouterModel,
+    anthropicApiKey,
+    anthropicBaseUrl,
+    anthropicModel,
+    azureOpenaiEndpoint,
+    azureOpenaiRegion,
+    azureOpenaiDeployment,
+    azureOpenaiApiVersion,
+    azureOpenaiAuth,
+    azureOpenaiApiKey,
+    azureOpenaiTenantId,
+    azureOpenaiClientId,
+    azureOpenaiClientSecret,
+    ollamaBaseUrl,
+    ollamaModel,
+    ollamaKeepAlive,
+    ollamaNumCtx,
+    ggufModelPath,
+    ggufTemplate,
+    ggufCustomTemplate,
+    ggufThreads,
+    ggufContextLength,
     deeplApiKey,
+    formality,
     microsoftSubscriptionKey,
     microsoftRegion,
+    libretranslateBaseUrl,
+    libretranslateApiKey,
+    libretranslateFormat,
+    libretranslateAlternatives,
+    baiduAppId,
+    baiduSecretKey,
+    youdaoAppKey,
+    youdaoAppSecret,
+    tencentSecretId,
+    tencentSecretKey,
+    tencentRegion,
+    awsAccessKeyId,
+    awsSecretAccessKey,
+    awsProfile,
+    awsRegion,
+    awsTerminology,
+    awsEndpoint,
+    googleCloudCredentialsPath,
+    googleCloudProjectId,
+    googleCloudLocation,
+    googleCloudGlossary,
+    googleCloudEndpoint,
+    offlineModelsDir,
+    offlineThreads,
+    offlineMemoryLimitMb,
     proxyEnabled,
     proxyProtocol,
     proxyHost,
     proxyPort,
     proxyUsername,
     proxyPassword,
+    proxyBypass,
     selectionShortcut,
+    quickTranslateOutput,
+    ocrOutput,
+    replaceShortcut,
+    writingLang,
+    replacePreview,
     launchAtStartup,
     quickWindowOpacity,
+    quickWindowPlacement,
     quickWindowBorderOpacity,
     quickSourceLang,
     quickTargetLang,
+    clipboardWatch,
     updateSettings
   } = useAppStore();
 
@@ -43,6 +99,192 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
   const [isRecordingShortcut, setIsRecordingShortcut] = useState(false);
   const [tempShortcut, setTempShortcut] = useState('');
 
+  const [appRules, setAppRules] = useState<AppRule[]>([]);
+  const [appRulesStatus, setAppRulesStatus] = useState<string | null>(null);
+
+  useEffect(() => {
+    platform.getAppRules().then(setAppRules);
+  }, []);
+
+  // Settings an administrator locked or restricted are shown read-only
+  const [policy, setPolicy] = useState<Policy | null>(null);
+
+  useEffect(() => {
+    platform.getPolicy().then(setPolicy);
+  }, []);
+
+  const isLocked = (field: keyof AppSettings) => !!policy && field in policy.locked;
+  const isProviderAllowed = (id: string) => !policy?.allowedProviders || policy.allowedProviders.includes(id);
+  const canSelectProvider = (id: string) => !isLocked('provider') && isProviderAllowed(id);
+  const isManaged = !!policy && (
+    Object.keys(policy.locked).length > 0 || !!policy.allowedProviders || policy.disableHistory
+  );
+
+  const [anthropicModels, setAnthropicModels] = useState<ProviderModel[]>([]);
+  const [anthropicModelsStatus, setAnthropicModelsStatus] = useState<string | null>(null);
+
+  // Listed by the backend, which holds the key and goes through the proxy
+  const loadAnthropicModels = async () => {
+    setAnthropicModelsStatus('Loading...');
+    try {
+      const models = await platform.listProviderModels('anthropic');
+      setAnthropicModels(models);
+      setAnthropicModelsStatus(`${models.length} models available`);
+    } catch (error) {
+      setAnthropicModelsStatus(String(error));
+    }
+  };
+
+  const [ollamaModels, setOllamaModels] = useState<ProviderModel[]>([]);
+  const [ollamaStatus, setOllamaStatus] = useState<string | null>(null);
+  const [ollamaPullName, setOllamaPullName] = useState('');
+  const [ollamaPull, setOllamaPull] = useState<OllamaPullProgress | null>(null);
+
+  // Also tells whether the daemon is running
+  const loadOllamaModels = async () => {
+    try {
+      const models = await platform.listProviderModels('ollama');
+      setOllamaModels(models);
+      setOllamaStatus(models.length ? null : 'No models pulled yet.');
+    } catch (error) {
+      setOllamaModels([]);
+      setOllamaStatus(String(error));
+    }
+  };
+
+  useEffect(() => {
+    if (provider === 'ollama') loadOllamaModels();
+  }, [provider, ollamaBaseUrl]);
+
+  useEffect(() => platform.onOllamaPullProgress(setOllamaPull), []);
+
+  const pullOllamaModel = async () => {
+    const model = ollamaPullName.trim();
+    if (!model) return;
+    setOllamaStatus(null);
+    try {
+      await platform.pullOllamaModel(model);
+      setOllamaPullName('');
+      await loadOllamaModels();
+      updateSettings({ ollamaModel: model });
+    } catch (error) {
+      setOllamaStatus(String(error));
+    } finally {
+      setOllamaPull(null);
+    }
+  };
+
+  const [libreLanguages, setLibreLanguages] = useState<ProviderLanguage[]>([]);
+  const [libreStatus, setLibreStatus] = useState<string | null>(null);
+
+  const loadLibreLanguages = async () => {
+    setLibreStatus('Loading...');
+    try {
+      const languages = await platform.listProviderLanguages('libretranslate');
+      setLibreLanguages(languages);
+      setLibreStatus(languages.length ? null : 'The server reported no languages.');
+    } catch (error) {
+      setLibreLanguages([]);
+      setLibreStatus(String(error));
+    }
+  };
+
+  const [offlineModels, setOfflineModels] = useState<ProviderModel[]>([]);
+  const [offlineStatus, setOfflineStatus] = useState<string | null>(null);
+
+  const loadOfflineModels = async () => {
+    try {
+      const models = await platform.listProviderModels('offline');
+      setOfflineModels(models);
+      setOfflineStatus(models.length ? null : 'No language pairs found in the model directory.');
+    } catch (error) {
+      setOfflineModels([]);
+      setOfflineStatus(String(error));
+    }
+  };
+
+  useEffect(() => {
+    if (provider === 'offline') loadOfflineModels();
+  }, [provider, offlineModelsDir]);
+
+  const [customProviders, setCustomProviders] = useState<CustomProviders>({ dir: '', providers: [], errors: [] });
+  const [customStatus, setCustomStatus] = useState<string | null>(null);
+
+  const loadCustomProviders = async () => {
+    try {
+      setCustomProviders(await platform.listCustomProviders());
+      setCustomStatus(null);
+    } catch (error) {
+      setCustomStatus(String(error));
+    }
+  };
+
+  useEffect(() => {
+    loadCustomProviders();
+  }, []);
+
+  const [pluginList, setPluginList] = useState<PluginList>({ dir: '', plugins: [] });
+  const [pluginStatus, setPluginStatus] = useState<string | null>(null);
+
+  const runPluginAction = async (action: () => Promise<PluginList>) => {
+    setPluginStatus(null);
+    try {
+      setPluginList(await action());
+    } catch (error) {
+      setPluginStatus(String(error));
+    }
+  };
+
+  useEffect(() => {
+    runPluginAction(() => platform.listPlugins());
+  }, []);
+
+  // Custom providers and enabled translator plugins, selectable like cloud providers
+  const extraProviders = [
+    ...customProviders.providers.map(p => ({ id: p.id, name: p.name, description: p.host, badge: 'Custom' })),
+    ...pluginList.plugins
+      .filter(p => p.enabled && p.kinds.includes('translator'))
+      .map(p => ({ id: p.id, name: p.name, description: p.allowedHosts.join(', ') || 'No network access', badge: 'Plugin' })),
+  ];
+
+  const [profileList, setProfileList] = useState<ProfileList>({ active: null, profiles: [] });
+  const [profileName, setProfileName] = useState('');
+  const [profileIncognito, setProfileIncognito] = useState(false);
+  const [profileStatus, setProfileStatus] = useState<string | null>(null);
+
+  // Profiles can also be switched from the tray and the command line
+  useEffect(() => {
+    platform.listProfiles().then(setProfileList);
+    return platform.onProfilesChanged(setProfileList);
+  }, []);
+
+  const runProfileAction = async (action: () => Promise<string | null>) => {
+    try {
+      setProfileStatus(await action());
+    } catch (error) {
+      setProfileStatus(String(error));
+    }
+  };
+
+  const updateAppRule = (index: number, changes: Partial<AppRule>) => {
+    setAppRules(appRules.map((rule, i) => (i === index ? { ...rule, ...changes } : rule)));
+    setAppRulesStatus(null);
+  };
+
+  const saveAppRules = async () => {
+    try {
+      await platform.setAppRules(appRules);
+      setAppRules(await platform.getAppRules());
+      setAppRulesStatus('Saved');
+    } catch (error) {
+      setAppRulesStatus(String(error));
+    }
+  };
+
+  const updateClipboardWatch = (changes: Partial<ClipboardWatchSettings>) => {
+    updateSettings({ clipboardWatch: { ...clipboardWatch, ...changes } });
+  };
+
   const selectedProvider = PROVIDERS.find(p => p.id === provider);
   const isLlmProvider = selectedProvider?.category === 'llm';
 
@@ -110,10 +352,6 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
   const saveShortcut = () => {
     if (tempShortcut) {
       updateSettings({ selectionShortcut: tempShortcut });
-      // Notify platform to update the shortcut
-      if (platform.isAvailable()) {
-        platform.updateShortcut(tempShortcut);
-      }
     }
     setIsRecordingShortcut(false);
     setTempShortcut('');
@@ -185,6 +423,11 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
 
           {/* Scrollable Content */}
           <div className="flex-1 overflow-y-auto p-8 space-y-6">
+            {isManaged && (
+              <div className="text-xs text-amber-700 bg-amber-50 border border-amber-200 rounded-lg px-4 py-2">
+                Some settings are managed by your organization and can't be changed.
+              </div>
+            )}
 
             {/* === LLM TAB === */}
             {activeTab === 'llm' && (
@@ -197,7 +440,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                       <label key={p.id} className="flex items-center justify-between group cursor-pointer">
                         <div className="flex items-center gap-3">
                           <div className={`w-8 h-8 rounded-full flex items-center justify-center ${provider === p.id ? 'bg-macos-active text-white' : 'bg-gray-100 text-gray-500'}`}>
-                            {p.id === 'gemini' ? <Bot size={16} /> : p.id === 'openrouter' ? <Globe size={16} /> : <Terminal size={16} />}
+                            {p.id === 'gemini' || p.id === 'anthropic' ? <Bot size={16} /> : p.id === 'openrouter' ? <Globe size={16} /> : p.id === 'ollama' ? <Cpu size={16} /> : p.id === 'gguf' ? <HardDrive size={16} /> : <Terminal size={16} />}
                           </div>
                           <div>
                             <div className="text-sm font-medium text-macos-text group-hover:text-black">{p.name}</div>
@@ -212,6 +455,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                             name="provider-llm"
                             checked={provider === p.id}
                             onChange={() => updateSettings({ provider: p.id })}
+                            disabled={!canSelectProvider(p.id)}
                             className="sr-only toggle-checkbox"
                           />
                           <div className="toggle-label"></div>
@@ -333,6 +577,366 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                   </div>
                 )}
 
+                {provider === 'anthropic' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Bot size={16} className="text-amber-600" />
+                      Anthropic Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">API Key</label>
+                        <input
+                          type="password"
+                          value={anthropicApiKey}
+                          onChange={(e) => updateSettings({ anthropicApiKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="sk-ant-..."
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Get your API key from the <a href="https://console.anthropic.com/settings/keys" target="_blank" rel="noopener noreferrer" className="text-blue-500 hover:underline">Anthropic Console</a></p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Base URL</label>
+                        <input
+                          type="text"
+                          value={anthropicBaseUrl}
+                          onChange={(e) => updateSettings({ anthropicBaseUrl: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="https://api.anthropic.com"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Model</label>
+                        <div className="flex gap-2">
+                          <input
+                            type="text"
+                            list="anthropic-models"
+                            value={anthropicModel}
+                            onChange={(e) => updateSettings({ anthropicModel: e.target.value })}
+                            className="flex-1 bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="claude-haiku-4-5"
+                          />
+                          <datalist id="anthropic-models">
+                            {anthropicModels.map((m) => (
+                              <option key={m.id} value={m.id}>{m.name}</option>
+                            ))}
+                          </datalist>
+                          <button
+                            onClick={loadAnthropicModels}
+                            disabled={!anthropicApiKey}
+                            className="px-3 py-2 text-xs font-medium bg-white border border-gray-200 rounded-lg shadow-sm hover:bg-gray-50 disabled:opacity-50"
+                          >
+                            Load Models
+                          </button>
+                        </div>
+                        <p className="text-xs text-macos-muted mt-2">{anthropicModelsStatus || 'Translations stream in as they are generated.'}</p>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {provider === 'azure_openai' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Bot size={16} className="text-sky-600" />
+                      Azure OpenAI Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Endpoint</label>
+                        <input
+                          type="text"
+                          value={azureOpenaiEndpoint}
+                          onChange={(e) => updateSettings({ azureOpenaiEndpoint: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="https://my-resource.openai.azure.com"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Leave empty to use the regional endpoint. Entra ID requires the resource endpoint.</p>
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Region</label>
+                          <input
+                            type="text"
+                            value={azureOpenaiRegion}
+                            onChange={(e) => updateSettings({ azureOpenaiRegion: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="eastus"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">API Version</label>
+                          <input
+                            type="text"
+                            value={azureOpenaiApiVersion}
+                            onChange={(e) => updateSettings({ azureOpenaiApiVersion: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="2024-10-21"
+                          />
+                        </div>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Deployment</label>
+                        <input
+                          type="text"
+                          value={azureOpenaiDeployment}
+                          onChange={(e) => updateSettings({ azureOpenaiDeployment: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="gpt-4o-mini"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">The deployment name from Azure AI Foundry, not the model name.</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Authentication</label>
+                        <select
+                          value={azureOpenaiAuth}
+                          onChange={(e) => updateSettings({ azureOpenaiAuth: e.target.value as 'key' | 'entra' })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                        >
+                          <option value="key">API key</option>
+                          <option value="entra">Microsoft Entra ID</option>
+                        </select>
+                      </div>
+                      {azureOpenaiAuth === 'key' ? (
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">API Key</label>
+                          <input
+                            type="password"
+                            value={azureOpenaiApiKey}
+                            onChange={(e) => updateSettings({ azureOpenaiApiKey: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="Azure OpenAI key"
+                          />
+                        </div>
+                      ) : (
+                        <>
+                          <div>
+                            <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Tenant ID</label>
+                            <input
+                              type="text"
+                              value={azureOpenaiTenantId}
+                              onChange={(e) => updateSettings({ azureOpenaiTenantId: e.target.value })}
+                              className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                              placeholder="Directory (tenant) ID"
+                            />
+                          </div>
+                          <div>
+                            <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Client ID</label>
+                            <input
+                              type="text"
+                              value={azureOpenaiClientId}
+                              onChange={(e) => updateSettings({ azureOpenaiClientId: e.target.value })}
+                              className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                              placeholder="Application (client) ID"
+                            />
+                          </div>
+                          <div>
+                            <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Client Secret</label>
+                            <input
+                              type="password"
+                              value={azureOpenaiClientSecret}
+                              onChange={(e) => updateSettings({ azureOpenaiClientSecret: e.target.value })}
+                              className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                              placeholder="Client secret"
+                            />
+                          </div>
+                          <p className="text-xs text-macos-muted">Without a client ID, the account signed in to the Azure CLI (<code>az login</code>) is used. It needs the Cognitive Services OpenAI User role.</p>
+                        </>
+                      )}
+                    </div>
+                  </div>
+                )}
+
+                {provider === 'ollama' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cpu size={16} className="text-gray-700" />
+                      Ollama Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Server URL</label>
+                        <input
+                          type="text"
+                          value={ollamaBaseUrl}
+                          onChange={(e) => updateSettings({ ollamaBaseUrl: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="http://localhost:11434"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Local servers are reached directly, bypassing the proxy.</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Model</label>
+                        <div className="flex gap-2">
+                          <select
+                            value={ollamaModel}
+                            onChange={(e) => updateSettings({ ollamaModel: e.target.value })}
+                            className="flex-1 bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active outline-none shadow-sm"
+                          >
+                            {!ollamaModels.some((m) => m.id === ollamaModel) && (
+                              <option value={ollamaModel}>{ollamaModel}</option>
+                            )}
+                            {ollamaModels.map((m) => (
+                              <option key={m.id} value={m.id}>{m.name}</option>
+                            ))}
+                          </select>
+                          <button
+                            onClick={loadOllamaModels}
+                            className="px-3 py-2 text-xs font-medium bg-white border border-gray-200 rounded-lg shadow-sm hover:bg-gray-50"
+                          >
+                            Refresh
+                          </button>
+                        </div>
+                        {ollamaStatus && <p className="text-xs text-amber-600 mt-2">{ollamaStatus}</p>}
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Pull Model</label>
+                        <div className="flex gap-2">
+                          <input
+                            type="text"
+                            value={ollamaPullName}
+                            onChange={(e) => setOllamaPullName(e.target.value)}
+                            disabled={!!ollamaPull}
+                            className="flex-1 bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="qwen2.5:7b"
+                          />
+                          <button
+                            onClick={pullOllamaModel}
+                            disabled={!ollamaPullName.trim() || !!ollamaPull}
+                            className="px-3 py-2 text-xs font-medium bg-white border border-gray-200 rounded-lg shadow-sm hover:bg-gray-50 disabled:opacity-50"
+                          >
+                            Pull
+                          </button>
+                        </div>
+                        {ollamaPull && (
+                          <div className="mt-2 space-y-1">
+                            <p className="text-xs text-macos-muted">{ollamaPull.model}: {ollamaPull.status}</p>
+                            {ollamaPull.total ? (
+                              <div className="h-1.5 bg-gray-200 rounded-full overflow-hidden">
+                                <div
+                                  className="h-full bg-macos-active transition-all"
+                                  style={{ width: `${Math.round(((ollamaPull.completed || 0) / ollamaPull.total) * 100)}%` }}
+                                />
+                              </div>
+                            ) : null}
+                          </div>
+                        )}
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Keep Alive</label>
+                          <input
+                            type="text"
+                            value={ollamaKeepAlive}
+                            onChange={(e) => updateSettings({ ollamaKeepAlive: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="5m"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Context Size</label>
+                          <input
+                            type="number"
+                            min={0}
+                            step={1024}
+                            value={ollamaNumCtx}
+                            onChange={(e) => updateSettings({ ollamaNumCtx: Math.max(0, parseInt(e.target.value) || 0) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                      </div>
+                      <p className="text-xs text-macos-muted">Keep alive: how long the model stays loaded, e.g. 10m, or -1 for always. Context size 0 uses the model's default.</p>
+                    </div>
+                  </div>
+                )}
+
+                {provider === 'gguf' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <HardDrive size={16} className="text-gray-700" />
+                      Local GGUF Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Model File</label>
+                        <div className="flex gap-2">
+                          <input
+                            type="text"
+                            value={ggufModelPath}
+                            onChange={(e) => updateSettings({ ggufModelPath: e.target.value })}
+                            className="flex-1 bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="/path/to/model.gguf"
+                          />
+                          <button
+                            onClick={async () => {
+                              const path = await platform.pickGgufModel();
+                              if (path) updateSettings({ ggufModelPath: path });
+                            }}
+                            className="px-3 py-2 text-xs font-medium bg-white border border-gray-200 rounded-lg shadow-sm hover:bg-gray-50"
+                          >
+                            Browse...
+                          </button>
+                        </div>
+                        <p className="text-xs text-macos-muted mt-2">Text never leaves this computer. Runs on the CPU with llama.cpp.</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Prompt Template</label>
+                        <select
+                          value={ggufTemplate}
+                          onChange={(e) => updateSettings({ ggufTemplate: e.target.value as GgufTemplate })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active outline-none shadow-sm"
+                        >
+                          <option value="chatml">ChatML (Qwen, many fine-tunes)</option>
+                          <option value="llama3">Llama 3</option>
+                          <option value="gemma">Gemma</option>
+                          <option value="mistral">Mistral</option>
+                          <option value="custom">Custom</option>
+                        </select>
+                        {ggufTemplate === 'custom' && (
+                          <>
+                            <textarea
+                              value={ggufCustomTemplate}
+                              onChange={(e) => updateSettings({ ggufCustomTemplate: e.target.value })}
+                              rows={4}
+                              className="w-full mt-2 bg-white border border-gray-200 rounded-lg px-3 py-2 text-xs font-mono focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none resize-none shadow-sm"
+                              placeholder={'{system}\n\nTranslate from {source} to {target}:\n{text}\n'}
+                            />
+                            <p className="text-xs text-macos-muted mt-2">Placeholders: {'{system}'}, {'{text}'} (exactly once), {'{source}'}, {'{target}'}.</p>
+                          </>
+                        )}
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">CPU Threads</label>
+                          <input
+                            type="number"
+                            min={0}
+                            value={ggufThreads}
+                            onChange={(e) => updateSettings({ ggufThreads: Math.max(0, parseInt(e.target.value) || 0) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Context Length</label>
+                          <input
+                            type="number"
+                            min={512}
+                            step={512}
+                            value={ggufContextLength}
+                            onChange={(e) => updateSettings({ ggufContextLength: Math.max(512, parseInt(e.target.value) || 0) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                      </div>
+                      <p className="text-xs text-macos-muted">0 threads uses all cores.</p>
+                    </div>
+                  </div>
+                )}
+
                 {/* System Prompt Card - Only for LLM providers */}
                 <div className={`bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 transition-opacity ${!isLlmProvider ? 'opacity-50' : ''}`}>
                   <div className="flex items-center justify-between">
@@ -396,7 +1000,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                       <label key={p.id} className={`flex items-center justify-between group cursor-pointer ${!p.enabled && !p.requiresKey ? '' : (!p.enabled ? 'opacity-50 grayscale' : '')}`}>
                         <div className="flex items-center gap-3">
                           <div className={`w-8 h-8 rounded-full flex items-center justify-center ${provider === p.id ? 'bg-macos-active text-white' : 'bg-gray-100 text-gray-500'}`}>
-                            {p.id === 'google' ? <Globe size={16} /> : <Cloud size={16} />}
+                            {p.id === 'google' ? <Globe size={16} /> : p.id === 'offline' ? <HardDrive size={16} /> : <Cloud size={16} />}
                           </div>
                           <div>
                             <div className="flex items-center gap-2">
@@ -413,7 +1017,35 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                             name="provider-cloud"
                             checked={provider === p.id}
                             onChange={() => p.enabled && updateSettings({ provider: p.id })}
-                            disabled={!p.enabled}
+                            disabled={!p.enabled || !canSelectProvider(p.id)}
+                            className="sr-only toggle-checkbox"
+                          />
+                          <div className="toggle-label"></div>
+                        </div>
+                      </label>
+                    ))}
+                    {extraProviders.map(p => (
+                      <label key={p.id} className="flex items-center justify-between group cursor-pointer">
+                        <div className="flex items-center gap-3">
+                          <div className={`w-8 h-8 rounded-full flex items-center justify-center ${provider === p.id ? 'bg-macos-active text-white' : 'bg-gray-100 text-gray-500'}`}>
+                            {p.badge === 'Plugin' ? <Puzzle size={16} /> : <FileCode size={16} />}
+                          </div>
+                          <div>
+                            <div className="flex items-center gap-2">
+                              <div className="text-sm font-medium text-macos-text">{p.name}</div>
+                              <span className="text-[10px] bg-gray-200 px-1.5 rounded text-gray-500">{p.badge}</span>
+                            </div>
+                            <div className="text-xs text-macos-muted">{p.description}</div>
+                          </div>
+                        </div>
+
+                        <div className="relative">
+                          <input
+                            type="radio"
+                            name="provider-cloud"
+                            checked={provider === p.id}
+                            onChange={() => updateSettings({ provider: p.id })}
+                            disabled={!canSelectProvider(p.id)}
                             className="sr-only toggle-checkbox"
                           />
                           <div className="toggle-label"></div>
@@ -455,6 +1087,18 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                       />
                       <p className="text-xs text-macos-muted mt-2">Supports both Free and Pro API keys.</p>
                     </div>
+                    <div>
+                      <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Formality</label>
+                      <select
+                        value={formality}
+                        onChange={(e) => updateSettings({ formality: e.target.value as '' | 'formal' | 'informal' })}
+                        className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                      >
+                        <option value="">Default</option>
+                        <option value="formal">Formal</option>
+                        <option value="informal">Informal</option>
+                      </select>
+                    </div>
                   </div>
                 )}
 
@@ -490,25 +1134,496 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                     </div>
                   </div>
                 )}
-              </>
-            )}
 
-            {/* === SELECTION TAB === */}
-            {activeTab === 'selection' && (
-              <>
-                {/* Keyboard Shortcut Card */}
-                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
-                  <div className="flex items-center gap-2 text-sm font-medium">
-                    <Keyboard size={16} className="text-gray-500" />
-                    <span>Activation Shortcut</span>
-                  </div>
+                {selectedProvider?.id === 'libretranslate' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-sky-600" />
+                      LibreTranslate Settings
+                    </div>
 
-                  <div className="space-y-3">
-                    <div className="flex items-center gap-3">
-                      <div
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Server URL</label>
+                        <input
+                          type="text"
+                          value={libretranslateBaseUrl}
+                          onChange={(e) => updateSettings({ libretranslateBaseUrl: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="http://localhost:5000"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">API Key (optional)</label>
+                        <input
+                          type="password"
+                          value={libretranslateApiKey}
+                          onChange={(e) => updateSettings({ libretranslateApiKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Only if the server requires keys"
+                        />
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Format</label>
+                          <select
+                            value={libretranslateFormat}
+                            onChange={(e) => updateSettings({ libretranslateFormat: e.target.value as 'text' | 'html' })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          >
+                            <option value="text">Plain text</option>
+                            <option value="html">HTML (keep markup)</option>
+                          </select>
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Alternatives</label>
+                          <input
+                            type="number"
+                            min={0}
+                            max={5}
+                            value={libretranslateAlternatives}
+                            onChange={(e) => updateSettings({ libretranslateAlternatives: Math.min(5, Math.max(0, parseInt(e.target.value) || 0)) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                      </div>
+                      <div>
+                        <div className="flex items-center justify-between mb-1.5">
+                          <label className="text-xs font-medium text-macos-muted ml-1">Server Languages</label>
+                          <button onClick={loadLibreLanguages} className="text-xs text-blue-500 hover:underline">Load Languages</button>
+                        </div>
+                        {libreLanguages.length > 0 && (
+                          <ul className="text-xs text-macos-text bg-white border border-gray-200 rounded-lg divide-y divide-gray-100 max-h-40 overflow-y-auto">
+                            {libreLanguages.map((l) => (
+                              <li key={l.code} className="px-3 py-1.5">{l.name} ({l.code}) → {l.targets.length} targets</li>
+                            ))}
+                          </ul>
+                        )}
+                        {libreStatus && <p className="text-xs text-amber-600 mt-2">{libreStatus}</p>}
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'baidu' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-blue-600" />
+                      Baidu Translate Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">App ID</label>
+                        <input
+                          type="text"
+                          value={baiduAppId}
+                          onChange={(e) => updateSettings({ baiduAppId: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="APP ID"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Secret Key</label>
+                        <input
+                          type="password"
+                          value={baiduSecretKey}
+                          onChange={(e) => updateSettings({ baiduSecretKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Secret Key"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">From the Baidu Fanyi open platform console (api.fanyi.baidu.com).</p>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'youdao' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-red-500" />
+                      Youdao Translate Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">App Key</label>
+                        <input
+                          type="text"
+                          value={youdaoAppKey}
+                          onChange={(e) => updateSettings({ youdaoAppKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Application ID"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">App Secret</label>
+                        <input
+                          type="password"
+                          value={youdaoAppSecret}
+                          onChange={(e) => updateSettings({ youdaoAppSecret: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Application Secret"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">From the Youdao AI open platform (ai.youdao.com).</p>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'tencent' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-sky-500" />
+                      Tencent Cloud TMT Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">SecretId</label>
+                        <input
+                          type="text"
+                          value={tencentSecretId}
+                          onChange={(e) => updateSettings({ tencentSecretId: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="AKID..."
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">SecretKey</label>
+                        <input
+                          type="password"
+                          value={tencentSecretKey}
+                          onChange={(e) => updateSettings({ tencentSecretKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="SecretKey"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Region</label>
+                        <input
+                          type="text"
+                          value={tencentRegion}
+                          onChange={(e) => updateSettings({ tencentRegion: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="ap-guangzhou"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Tencent Cloud region (e.g., ap-guangzhou, ap-shanghai, ap-beijing)</p>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'amazon' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-orange-500" />
+                      Amazon Translate Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Access Key ID</label>
+                        <input
+                          type="text"
+                          value={awsAccessKeyId}
+                          onChange={(e) => updateSettings({ awsAccessKeyId: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Leave empty to use ~/.aws/credentials"
+                        />
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Secret Access Key</label>
+                        <input
+                          type="password"
+                          value={awsSecretAccessKey}
+                          onChange={(e) => updateSettings({ awsSecretAccessKey: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Secret Access Key"
+                        />
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Profile</label>
+                          <input
+                            type="text"
+                            value={awsProfile}
+                            onChange={(e) => updateSettings({ awsProfile: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="default"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Region</label>
+                          <input
+                            type="text"
+                            value={awsRegion}
+                            onChange={(e) => updateSettings({ awsRegion: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="From ~/.aws/config, or us-east-1"
+                          />
+                        </div>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Custom Terminologies</label>
+                        <input
+                          type="text"
+                          value={awsTerminology}
+                          onChange={(e) => updateSettings({ awsTerminology: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Comma-separated terminology names"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Terminologies created in the Amazon Translate console for the region.</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Endpoint (optional)</label>
+                        <input
+                          type="text"
+                          value={awsEndpoint}
+                          onChange={(e) => updateSettings({ awsEndpoint: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="https://translate.<region>.amazonaws.com"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">For VPC endpoints or a local mock server.</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Formality</label>
+                        <select
+                          value={formality}
+                          onChange={(e) => updateSettings({ formality: e.target.value as '' | 'formal' | 'informal' })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                        >
+                          <option value="">Default</option>
+                          <option value="formal">Formal</option>
+                          <option value="informal">Informal</option>
+                        </select>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'google_cloud' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Cloud size={16} className="text-blue-500" />
+                      Google Cloud Translation Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Service Account Key</label>
+                        <div className="flex gap-2">
+                          <input
+                            type="text"
+                            value={googleCloudCredentialsPath}
+                            onChange={(e) => updateSettings({ googleCloudCredentialsPath: e.target.value })}
+                            className="flex-1 bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="/path/to/service-account.json"
+                          />
+                          <button
+                            onClick={async () => {
+                              const path = await platform.pickServiceAccountKey();
+                              if (path) updateSettings({ googleCloudCredentialsPath: path });
+                            }}
+                            className="px-3 py-2 text-xs font-medium bg-white border border-gray-200 rounded-lg shadow-sm hover:bg-gray-50"
+                          >
+                            Browse...
+                          </button>
+                        </div>
+                        <p className="text-xs text-macos-muted mt-2">The account needs the Cloud Translation API User role.</p>
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Project ID</label>
+                          <input
+                            type="text"
+                            value={googleCloudProjectId}
+                            onChange={(e) => updateSettings({ googleCloudProjectId: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="From the key file"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Location</label>
+                          <input
+                            type="text"
+                            value={googleCloudLocation}
+                            onChange={(e) => updateSettings({ googleCloudLocation: e.target.value })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                            placeholder="global"
+                          />
+                        </div>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Glossary (optional)</label>
+                        <input
+                          type="text"
+                          value={googleCloudGlossary}
+                          onChange={(e) => updateSettings({ googleCloudGlossary: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="Glossary ID"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">Glossaries live in a region: set the location to it (e.g. us-central1).</p>
+                      </div>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Endpoint (optional)</label>
+                        <input
+                          type="text"
+                          value={googleCloudEndpoint}
+                          onChange={(e) => updateSettings({ googleCloudEndpoint: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="https://translation.googleapis.com"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">For private endpoints or a local mock server.</p>
+                      </div>
+                    </div>
+                  </div>
+                )}
+
+                {selectedProvider?.id === 'offline' && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4 animate-in fade-in slide-in-from-bottom-2">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <HardDrive size={16} className="text-gray-700" />
+                      Offline Translation Settings
+                    </div>
+
+                    <div className="space-y-4">
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Model Directory</label>
+                        <input
+                          type="text"
+                          value={offlineModelsDir}
+                          onChange={(e) => updateSettings({ offlineModelsDir: e.target.value })}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="~/.local/share/lighttranslator/models"
+                        />
+                        <p className="text-xs text-macos-muted mt-2">One folder per language pair (e.g. enzh, opus-mt-de-en) with a Marian model converted to CTranslate2.</p>
+                      </div>
+                      <div>
+                        <div className="flex items-center justify-between mb-1.5">
+                          <label className="text-xs font-medium text-macos-muted ml-1">Installed Language Pairs</label>
+                          <button onClick={loadOfflineModels} className="text-xs text-blue-500 hover:underline">Refresh</button>
+                        </div>
+                        {offlineModels.length > 0 && (
+                          <ul className="text-xs text-macos-text bg-white border border-gray-200 rounded-lg divide-y divide-gray-100">
+                            {offlineModels.map((m) => (
+                              <li key={m.id} className="px-3 py-1.5">{m.name}</li>
+                            ))}
+                          </ul>
+                        )}
+                        {offlineStatus && <p className="text-xs text-amber-600 mt-2">{offlineStatus}</p>}
+                      </div>
+                      <div className="grid grid-cols-2 gap-3">
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">CPU Threads</label>
+                          <input
+                            type="number"
+                            min={0}
+                            value={offlineThreads}
+                            onChange={(e) => updateSettings({ offlineThreads: Math.max(0, parseInt(e.target.value) || 0) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                        <div>
+                          <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Memory Limit (MB)</label>
+                          <input
+                            type="number"
+                            min={64}
+                            step={256}
+                            value={offlineMemoryLimitMb}
+                            onChange={(e) => updateSettings({ offlineMemoryLimitMb: Math.max(64, parseInt(e.target.value) || 0) })}
+                            className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          />
+                        </div>
+                      </div>
+                      <p className="text-xs text-macos-muted">Models load on first use and unload after 10 minutes idle or to stay under the memory limit. 0 threads uses all cores.</p>
+                    </div>
+                  </div>
+                )}
+
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-3">
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <FileCode size={16} className="text-gray-700" />
+                      Custom Providers
+                    </div>
+                    <button onClick={loadCustomProviders} className="text-xs text-blue-500 hover:underline">Reload</button>
+                  </div>
+                  <p className="text-xs text-macos-muted">
+                    Add a TOML or JSON file per HTTP API to {customProviders.dir ? <code className="bg-gray-100 px-1 rounded">{customProviders.dir}</code> : 'the providers config directory'}. See the README for the format.
+                  </p>
+                  {customProviders.errors.length > 0 && (
+                    <ul className="text-xs text-amber-600 space-y-1">
+                      {customProviders.errors.map((error) => (
+                        <li key={error} className="whitespace-pre-wrap break-words">{error}</li>
+                      ))}
+                    </ul>
+                  )}
+                  {customStatus && <p className="text-xs text-amber-600">{customStatus}</p>}
+                </div>
+
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-3">
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-2 text-macos-text text-sm font-semibold">
+                      <Puzzle size={16} className="text-gray-700" />
+                      Plugins
+                    </div>
+                    <button onClick={() => runPluginAction(() => platform.reloadPlugins())} className="text-xs text-blue-500 hover:underline">Reload</button>
+                  </div>
+                  <p className="text-xs text-macos-muted">
+                    WebAssembly components in {pluginList.dir ? <code className="bg-gray-100 px-1 rounded">{pluginList.dir}</code> : 'the plugins config directory'} that translate or post-process translations. They can only reach the hosts listed in their manifest.
+                  </p>
+                  {pluginList.plugins.length > 0 && (
+                    <ul className="bg-white border border-gray-200 rounded-lg divide-y divide-gray-100">
+                      {pluginList.plugins.map((plugin) => (
+                        <li key={plugin.id} className="px-3 py-2 space-y-1">
+                          <div className="flex items-center justify-between gap-3">
+                            <div className="min-w-0">
+                              <div className="text-sm text-macos-text truncate">{plugin.name}</div>
+                              <div className="text-xs text-macos-muted">
+                                {plugin.kinds.length ? plugin.kinds.join(', ') : 'Not loaded'}
+                                {' · '}
+                                {plugin.allowedHosts.length ? plugin.allowedHosts.join(', ') : 'No network access'}
+                              </div>
+                            </div>
+                            <label className="relative inline-flex items-center cursor-pointer shrink-0">
+                              <input
+                                type="checkbox"
+                                checked={plugin.enabled}
+                                onChange={(e) => runPluginAction(() => platform.setPluginEnabled(plugin.id, e.target.checked))}
+                                disabled={!plugin.enabled && plugin.kinds.length === 0}
+                                className="sr-only peer"
+                              />
+                              <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-green-500 peer-disabled:opacity-50"></div>
+                            </label>
+                          </div>
+                          {plugin.error && <p className="text-xs text-amber-600 whitespace-pre-wrap break-words">{plugin.error}</p>}
+                        </li>
+                      ))}
+                    </ul>
+                  )}
+                  {pluginStatus && <p className="text-xs text-amber-600">{pluginStatus}</p>}
+                </div>
+              </>
+            )}
+
+            {/* === SELECTION TAB === */}
+            {activeTab === 'selection' && (
+              <>
+                {/* Keyboard Shortcut Card */}
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                  <div className="flex items-center gap-2 text-sm font-medium">
+                    <Keyboard size={16} className="text-gray-500" />
+                    <span>Activation Shortcut</span>
+                  </div>
+
+                  <div className="space-y-3">
+                    <div className="flex items-center gap-3">
+                      <div
                         tabIndex={0}
                         onKeyDown={handleKeyDown}
                         onFocus={() => {
+                          if (isLocked('selectionShortcut')) return;
                           setIsRecordingShortcut(true);
                           setTempShortcut('');
                         }}
@@ -557,6 +1672,86 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                   </div>
                 </div>
 
+                {/* Result Output Card */}
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                  <div className="flex items-center gap-2 text-sm font-medium">
+                    <Bell size={16} className="text-purple-500" />
+                    <span>Show Results In</span>
+                  </div>
+
+                  <div className="grid grid-cols-2 gap-4">
+                    <div>
+                      <label className="block text-xs font-medium text-macos-muted mb-1">Selection Shortcut</label>
+                      <select
+                        value={quickTranslateOutput}
+                        onChange={(e) => updateSettings({ quickTranslateOutput: e.target.value as OutputMode })}
+                        className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                      >
+                        <option value="window">Pop-up Window</option>
+                        <option value="notification">Notification</option>
+                      </select>
+                    </div>
+                    <div>
+                      <label className="block text-xs font-medium text-macos-muted mb-1">OCR Screenshot</label>
+                      <select
+                        value={ocrOutput}
+                        onChange={(e) => updateSettings({ ocrOutput: e.target.value as OutputMode })}
+                        className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                      >
+                        <option value="window">Main Window</option>
+                        <option value="notification">Notification</option>
+                      </select>
+                    </div>
+                  </div>
+
+                  <p className="text-xs text-macos-muted">Notifications don't take focus. They offer Copy, Open in Window and Speak actions; long translations are shortened and can be opened in full. Speak requires speech-dispatcher or espeak-ng.</p>
+                </div>
+
+                {/* Replace Selection Card */}
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                  <div className="flex items-center gap-2 text-sm font-medium">
+                    <MousePointer2 size={16} className="text-orange-500" />
+                    <span>Replace Selection</span>
+                  </div>
+
+                  <div className="grid grid-cols-2 gap-4">
+                    <div>
+                      <label className="block text-xs font-medium text-macos-muted mb-1">Shortcut</label>
+                      <input
+                        type="text"
+                        key={replaceShortcut}
+                        defaultValue={replaceShortcut}
+                        onBlur={(e) => updateSettings({ replaceShortcut: e.target.value.trim() })}
+                        placeholder="Disabled"
+                        className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400 font-mono"
+                      />
+                    </div>
+                    <div>
+                      <label className="block text-xs font-medium text-macos-muted mb-1">Writing Language</label>
+                      <select
+                        value={writingLang}
+                        onChange={(e) => updateSettings({ writingLang: e.target.value as any })}
+                        className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                      >
+                        {LANGUAGES.filter((lang) => lang.code !== 'auto').map((lang) => (
+                          <option key={lang.code} value={lang.code}>{lang.name}</option>
+                        ))}
+                      </select>
+                    </div>
+                  </div>
+
+                  <label className="flex items-center gap-2 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={replacePreview}
+                      onChange={(e) => updateSettings({ replacePreview: e.target.checked })}
+                    />
+                    Preview the translation before replacing
+                  </label>
+
+                  <p className="text-xs text-macos-muted">Translates the selected text into the writing language and pastes it in place, e.g. <span className="font-mono">CommandOrControl+Alt+X</span>. Leave the shortcut empty to disable. Requires xdotool (X11) or wtype/ydotool (Wayland).</p>
+                </div>
+
                 {/* Quick Translate Language Card */}
                 <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-5">
                   <div className="flex items-center gap-2 text-sm font-medium">
@@ -594,6 +1789,190 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                   <p className="text-xs text-macos-muted">These language settings are independent from the main panel. You can also change them directly in the pop-up window.</p>
                 </div>
 
+                {/* Clipboard Watch Card */}
+                {clipboardWatch && (
+                  <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                    <div className="flex items-center justify-between">
+                      <div className="flex items-center gap-2 text-sm font-medium">
+                        <Clipboard size={16} className="text-green-500" />
+                        <span>Clipboard Watch</span>
+                      </div>
+                      <label className="relative inline-flex items-center cursor-pointer">
+                        <input
+                          type="checkbox"
+                          checked={clipboardWatch.enabled}
+                          onChange={(e) => updateClipboardWatch({ enabled: e.target.checked })}
+                          className="sr-only peer"
+                        />
+                        <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-green-500"></div>
+                      </label>
+                    </div>
+
+                    <div className="grid grid-cols-2 gap-4">
+                      <div>
+                        <label className="block text-xs font-medium text-macos-muted mb-1">Show Result In</label>
+                        <select
+                          value={clipboardWatch.output}
+                          onChange={(e) => updateClipboardWatch({ output: e.target.value as ClipboardWatchSettings['output'] })}
+                          className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                        >
+                          <option value="quick">Pop-up Window</option>
+                          <option value="notification">Notification</option>
+                        </select>
+                      </div>
+                      <div>
+                        <label className="block text-xs font-medium text-macos-muted mb-1">Minimum Length</label>
+                        <input
+                          type="number"
+                          min={1}
+                          value={clipboardWatch.minLength}
+                          onChange={(e) => updateClipboardWatch({ minLength: Math.max(1, parseInt(e.target.value) || 1) })}
+                          className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                        />
+                      </div>
+                    </div>
+
+                    <div className="space-y-2">
+                      <label className="flex items-center gap-2 text-sm">
+                        <input
+                          type="checkbox"
+                          checked={clipboardWatch.watchPrimary}
+                          onChange={(e) => updateClipboardWatch({ watchPrimary: e.target.checked })}
+                        />
+                        Also translate selected text (primary selection)
+                      </label>
+                      <label className="flex items-center gap-2 text-sm">
+                        <input
+                          type="checkbox"
+                          checked={clipboardWatch.skipTargetLanguage}
+                          onChange={(e) => updateClipboardWatch({ skipTargetLanguage: e.target.checked })}
+                        />
+                        Skip text already in the target language
+                      </label>
+                    </div>
+
+                    <div>
+                      <label className="block text-xs font-medium text-macos-muted mb-1">Ignored Applications</label>
+                      <input
+                        type="text"
+                        key={clipboardWatch.denylist.join(',')}
+                        defaultValue={clipboardWatch.denylist.join(', ')}
+                        onBlur={(e) => updateClipboardWatch({ denylist: e.target.value.split(',').map((name) => name.trim()).filter(Boolean) })}
+                        className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400 font-mono"
+                      />
+                      <p className="text-xs text-macos-muted mt-1">Window classes whose copies are never read, e.g. password managers.</p>
+                    </div>
+                  </div>
+                )}
+
+                {/* Application Rules Card */}
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-2 text-sm font-medium">
+                      <AppWindow size={16} className="text-purple-500" />
+                      <span>Application Rules</span>
+                    </div>
+                    <button
+                      onClick={() => setAppRules([...appRules, { app: '', neverCapture: false }])}
+                      className="flex items-center gap-1 text-xs font-medium text-macos-active hover:underline"
+                    >
+                      <Plus size={12} /> Add Rule
+                    </button>
+                  </div>
+
+                  {appRules.map((rule, index) => (
+                    <div key={index} className="space-y-2 border-t border-gray-200/60 pt-3">
+                      <div className="flex items-center gap-2">
+                        <input
+                          type="text"
+                          value={rule.app}
+                          onChange={(e) => updateAppRule(index, { app: e.target.value })}
+                          placeholder="Window class, e.g. kitty"
+                          className="flex-1 text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400 font-mono"
+                        />
+                        <label className="flex items-center gap-1 text-xs whitespace-nowrap">
+                          <input
+                            type="checkbox"
+                            checked={rule.neverCapture}
+                            onChange={(e) => updateAppRule(index, { neverCapture: e.target.checked })}
+                          />
+                          Never capture
+                        </label>
+                        <button
+                          onClick={() => setAppRules(appRules.filter((_, i) => i !== index))}
+                          className="p-1.5 text-gray-400 hover:text-red-500 transition-colors"
+                        >
+                          <Trash2 size={14} />
+                        </button>
+                      </div>
+
+                      {!rule.neverCapture && (
+                        <>
+                          <div className="grid grid-cols-4 gap-2">
+                            <select
+                              value={rule.provider || ''}
+                              onChange={(e) => updateAppRule(index, { provider: e.target.value || undefined })}
+                              className="text-sm bg-white border border-gray-200 rounded-lg px-2 py-1.5 outline-none focus:border-blue-400"
+                            >
+                              <option value="">Default provider</option>
+                              {PROVIDERS.map((p) => (
+                                <option key={p.id} value={p.id} disabled={!isProviderAllowed(p.id)}>{p.name}</option>
+                              ))}
+                            </select>
+                            <select
+                              value={rule.sourceLang || ''}
+                              onChange={(e) => updateAppRule(index, { sourceLang: e.target.value || undefined })}
+                              className="text-sm bg-white border border-gray-200 rounded-lg px-2 py-1.5 outline-none focus:border-blue-400"
+                            >
+                              <option value="">Default source</option>
+                              {LANGUAGES.map((lang) => (
+                                <option key={lang.code} value={lang.code}>{lang.name}</option>
+                              ))}
+                            </select>
+                            <select
+                              value={rule.targetLang || ''}
+                              onChange={(e) => updateAppRule(index, { targetLang: e.target.value || undefined })}
+                              className="text-sm bg-white border border-gray-200 rounded-lg px-2 py-1.5 outline-none focus:border-blue-400"
+                            >
+                              <option value="">Default target</option>
+                              {LANGUAGES.filter((lang) => lang.code !== 'auto').map((lang) => (
+                                <option key={lang.code} value={lang.code}>{lang.name}</option>
+                              ))}
+                            </select>
+                            <select
+                              value={rule.formality || ''}
+                              onChange={(e) => updateAppRule(index, { formality: (e.target.value || undefined) as AppRule['formality'] })}
+                              className="text-sm bg-white border border-gray-200 rounded-lg px-2 py-1.5 outline-none focus:border-blue-400"
+                            >
+                              <option value="">Default formality</option>
+                              <option value="formal">Formal</option>
+                              <option value="informal">Informal</option>
+                            </select>
+                          </div>
+                          <textarea
+                            value={rule.systemPrompt || ''}
+                            onChange={(e) => updateAppRule(index, { systemPrompt: e.target.value || undefined })}
+                            placeholder="System prompt for LLM providers (optional)"
+                            rows={2}
+                            className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400 resize-none"
+                          />
+                        </>
+                      )}
+                    </div>
+                  ))}
+
+                  <div className="flex items-center gap-3">
+                    <button
+                      onClick={saveAppRules}
+                      className="px-4 py-2 bg-macos-active text-white font-medium rounded-lg hover:bg-macos-active/90 transition-colors text-sm"
+                    >
+                      Save Rules
+                    </button>
+                    {appRulesStatus && <span className="text-xs text-macos-muted">{appRulesStatus}</span>}
+                  </div>
+                  <p className="text-xs text-macos-muted">The first rule matching the focused application (X11 window class or Wayland app id) applies to the shortcut, clipboard watch and replace selection.</p>
+                </div>
+
                 {/* Quick Translate Appearance Card */}
                 <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-5">
                   <div className="flex items-center gap-2 text-sm font-medium">
@@ -601,6 +1980,25 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                     <span>Appearance</span>
                   </div>
 
+                  {/* Placement */}
+                  <div>
+                    <label className="block text-xs font-medium text-macos-muted mb-1">Position</label>
+                    <select
+                      value={quickWindowPlacement}
+                      onChange={(e) => updateSettings({ quickWindowPlacement: e.target.value as QuickPlacement })}
+                      className="w-full text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                    >
+                      <option value="cursor">At the cursor</option>
+                      <option value="selection">Below the selection</option>
+                      <option value="topLeft">Top-left corner</option>
+                      <option value="topRight">Top-right corner</option>
+                      <option value="bottomLeft">Bottom-left corner</option>
+                      <option value="bottomRight">Bottom-right corner</option>
+                      <option value="center">Centered</option>
+                    </select>
+                    <p className="text-xs text-macos-muted mt-1">Where the popup opens, always kept fully on the monitor under the cursor.</p>
+                  </div>
+
                   {/* Background Opacity Slider */}
                   <div>
                     <div className="flex items-center justify-between mb-2">
@@ -662,6 +2060,99 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
 
                 </div>
 
+                {/* Profiles Card */}
+                <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-2 text-sm font-medium">
+                      <Users size={16} className="text-purple-500" />
+                      <span>Profiles</span>
+                    </div>
+                    <div className="flex items-center gap-3">
+                      <button
+                        onClick={() => runProfileAction(async () => {
+                          const count = await platform.importProfiles();
+                          return count > 0 ? `Imported ${count} profile(s)` : null;
+                        })}
+                        className="text-xs font-medium text-macos-active hover:underline"
+                      >
+                        Import
+                      </button>
+                      <button
+                        onClick={() => runProfileAction(async () => (await platform.exportProfiles()) ? 'Exported without keys' : null)}
+                        disabled={profileList.profiles.length === 0}
+                        className="text-xs font-medium text-macos-active hover:underline disabled:opacity-40 disabled:no-underline"
+                      >
+                        Export
+                      </button>
+                    </div>
+                  </div>
+
+                  {profileList.profiles.map((profile) => (
+                    <div key={profile.name} className="flex items-center gap-2 border-t border-gray-200/60 pt-3">
+                      <div className="flex-1 min-w-0">
+                        <div className="text-sm font-medium truncate">
+                          {profile.name}
+                          {profileList.active === profile.name && <span className="ml-2 text-xs text-green-600">Active</span>}
+                        </div>
+                        <div className="text-xs text-macos-muted truncate">
+                          {PROVIDERS.find((p) => p.id === profile.settings.provider)?.name || profile.settings.provider}
+                          {profile.settings.proxyEnabled ? ` · Proxy ${profile.settings.proxyHost}` : ' · No proxy'}
+                          {profile.incognito && ' · Incognito'}
+                        </div>
+                      </div>
+                      <button
+                        onClick={() => runProfileAction(async () => {
+                          await platform.switchProfile(profile.name);
+                          return null;
+                        })}
+                        className="px-3 py-1.5 text-xs font-medium bg-white border border-gray-200 rounded-lg hover:bg-gray-50 transition-colors"
+                      >
+                        Switch
+                      </button>
+                      <button
+                        onClick={() => runProfileAction(async () => {
+                          await platform.deleteProfile(profile.name);
+                          return null;
+                        })}
+                        className="p-1.5 text-gray-400 hover:text-red-500 transition-colors"
+                      >
+                        <Trash2 size={14} />
+                      </button>
+                    </div>
+                  ))}
+
+                  <div className="flex items-center gap-2">
+                    <input
+                      type="text"
+                      value={profileName}
+                      onChange={(e) => setProfileName(e.target.value)}
+                      placeholder={profileList.active || 'Profile name, e.g. work'}
+                      className="flex-1 text-sm bg-white border border-gray-200 rounded-lg px-3 py-2 outline-none focus:border-blue-400"
+                    />
+                    <label className="flex items-center gap-1 text-xs whitespace-nowrap">
+                      <input
+                        type="checkbox"
+                        checked={profileIncognito}
+                        onChange={(e) => setProfileIncognito(e.target.checked)}
+                      />
+                      No history
+                    </label>
+                    <button
+                      onClick={() => runProfileAction(async () => {
+                        const name = profileName.trim() || profileList.active || '';
+                        await platform.saveProfile(name, profileIncognito);
+                        setProfileName('');
+                        return `Saved "${name}"`;
+                      })}
+                      className="px-4 py-2 bg-macos-active text-white font-medium rounded-lg hover:bg-macos-active/90 transition-colors text-sm whitespace-nowrap"
+                    >
+                      Save Current
+                    </button>
+                  </div>
+                  {profileStatus && <p className="text-xs text-macos-muted">{profileStatus}</p>}
+                  <p className="text-xs text-macos-muted">A profile stores the provider, models, proxy, shortcuts and languages. API keys go to the system keyring and are never exported.</p>
+                </div>
+
                 {/* Proxy Settings Card */}
                 <div className="bg-white/60 border border-white/50 shadow-macos-card rounded-xl p-5 space-y-4">
                   <div className="flex items-center justify-between">
@@ -674,20 +2165,8 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                       <input
                         type="checkbox"
                         checked={proxyEnabled}
-                        onChange={(e) => {
-                          updateSettings({ proxyEnabled: e.target.checked });
-                          // Notify platform to apply proxy
-                          if (platform.isAvailable()) {
-                            platform.setProxy({
-                              enabled: e.target.checked,
-                              protocol: proxyProtocol,
-                              host: proxyHost,
-                              port: proxyPort,
-                              username: proxyUsername,
-                              password: proxyPassword
-                            });
-                          }
-                        }}
+                        onChange={(e) => updateSettings({ proxyEnabled: e.target.checked })}
+                        disabled={isLocked('proxyEnabled')}
                         className="sr-only peer"
                       />
                       <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-green-500"></div>
@@ -702,6 +2181,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                           <select
                             value={proxyProtocol}
                             onChange={(e) => updateSettings({ proxyProtocol: e.target.value as 'http' | 'https' | 'socks5' })}
+                            disabled={isLocked('proxyProtocol')}
                             className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
                           >
                             <option value="http">HTTP</option>
@@ -715,6 +2195,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                             type="number"
                             value={proxyPort}
                             onChange={(e) => updateSettings({ proxyPort: parseInt(e.target.value) || 8080 })}
+                            disabled={isLocked('proxyPort')}
                             className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
                             placeholder="8080"
                           />
@@ -726,6 +2207,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                           type="text"
                           value={proxyHost}
                           onChange={(e) => updateSettings({ proxyHost: e.target.value })}
+                          disabled={isLocked('proxyHost')}
                           className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
                           placeholder="127.0.0.1 or proxy.example.com"
                         />
@@ -737,6 +2219,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                             type="text"
                             value={proxyUsername}
                             onChange={(e) => updateSettings({ proxyUsername: e.target.value })}
+                            disabled={isLocked('proxyUsername')}
                             className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
                             placeholder="Optional"
                           />
@@ -747,29 +2230,24 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                             type="password"
                             value={proxyPassword}
                             onChange={(e) => updateSettings({ proxyPassword: e.target.value })}
+                            disabled={isLocked('proxyPassword')}
                             className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
                             placeholder="Optional"
                           />
                         </div>
                       </div>
-                      <button
-                        onClick={() => {
-                          if (platform.isAvailable()) {
-                            platform.setProxy({
-                              enabled: proxyEnabled,
-                              protocol: proxyProtocol,
-                              host: proxyHost,
-                              port: proxyPort,
-                              username: proxyUsername,
-                              password: proxyPassword
-                            });
-                          }
-                        }}
-                        className="w-full py-2 bg-macos-active text-white font-medium rounded-lg hover:bg-macos-active/90 transition-colors text-sm"
-                      >
-                        Apply Proxy Settings
-                      </button>
-                      <p className="text-xs text-macos-muted">Configure a proxy server for all translation API requests.</p>
+                      <div>
+                        <label className="text-xs font-medium text-macos-muted ml-1 mb-1.5 block">Bypass Proxy For</label>
+                        <input
+                          type="text"
+                          value={proxyBypass}
+                          onChange={(e) => updateSettings({ proxyBypass: e.target.value })}
+                          disabled={isLocked('proxyBypass')}
+                          className="w-full bg-white border border-gray-200 rounded-lg px-3 py-2 text-sm focus:border-macos-active focus:ring-2 focus:ring-macos-active/20 outline-none shadow-sm"
+                          placeholder="translate.internal, .corp.example.com, 10.0.0.0/8"
+                        />
+                      </div>
+                      <p className="text-xs text-macos-muted">Configure a proxy server for all translation API requests. Local servers (localhost) are always reached directly.</p>
                     </div>
                   )}
                 </div>
@@ -790,21 +2268,8 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({ onClose }) => {
                       <input
                         type="checkbox"
                         checked={launchAtStartup}
-                        onChange={async (e) => {
-                          const enabled = e.target.checked;
-                          // Notify platform to set auto-launch
-                          if (platform.isAvailable()) {
-                            try {
-                              await platform.setAutoLaunch(enabled);
-                              updateSettings({ launchAtStartup: enabled });
-                            } catch (error) {
-                              console.error('Failed to set auto-launch:', error);
-                            }
-                          } else {
-                            // Not in native environment, just update local state
-                            updateSettings({ launchAtStartup: enabled });
-                          }
-                        }}
+                        onChange={(e) => updateSettings({ launchAtStartup: e.target.checked })}
+                        disabled={isLocked('launchAtStartup')}
                         className="sr-only peer"
                       />
                       <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-green-500"></div>
@@ -30,7 +30,15 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
     openaiModel,
     openrouterApiKey,
     openrouterModel,
+    anthropicApiKey,
+    anthropicModel,
+    azureOpenaiDeployment,
+    azureOpenaiAuth,
+    azureOpenaiApiKey,
+    ollamaModel,
+    ggufModelPath,
     deeplApiKey,
+    formality,
     microsoftSubscriptionKey,
     microsoftRegion,
     ocrStatus,
@@ -45,23 +53,30 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
 
   const [copied, setCopied] = React.useState(false);
   const [isProcessingImage, setIsProcessingImage] = useState(false);
+  // Other candidates for the current translation (LibreTranslate)
+  const [alternatives, setAlternatives] = useState<string[]>([]);
   const debounceTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
   const textareaRef = useRef<HTMLTextAreaElement>(null);
 
   // We use a ref to track the latest request to prevent race conditions
   const latestRequestText = useRef<string>('');
+  // Input of a translation shown as is, which auto-translate skips once
+  const shownTranslationText = useRef<string | null>(null);
 
   const performTranslation = useCallback(async (text: string) => {
     // Immediate clear if empty, providing instant feedback
     if (!text.trim()) {
       setTranslatedText("");
+      setAlternatives([]);
       setIsTranslating(false);
       return;
     }
 
     setIsTranslating(true);
     setErrorMessage(null);
+    setAlternatives([]);
     latestRequestText.current = text;
+    let partial = '';
 
     try {
       const result = await translateText(text, sourceLang, targetLang, {
@@ -75,9 +90,23 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
         openaiModel,
         openrouterApiKey,
         openrouterModel,
+        anthropicModel,
+        ollamaModel,
         deeplApiKey,
+        formality: formality || undefined,
         microsoftSubscriptionKey,
-        microsoftRegion
+        microsoftRegion,
+        // Show streamed output while this is still the latest request
+        onPartial: (delta) => {
+          if (text === latestRequestText.current && text === useAppStore.getState().inputText) {
+            setTranslatedText(partial += delta);
+          }
+        },
+        onAlternatives: (candidates) => {
+          if (text === latestRequestText.current && text === useAppStore.getState().inputText) {
+            setAlternatives(candidates);
+          }
+        }
       });
 
       // Race Condition Check:
@@ -87,6 +116,7 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
 
       if (text === currentInput && text === latestRequestText.current) {
         setTranslatedText(result);
+        platform.addRecentTranslation(text, result).catch(() => {});
       }
     } catch (err: any) {
       const currentInput = useAppStore.getState().inputText;
@@ -100,11 +130,15 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
         setIsTranslating(false);
       }
     }
-  }, [sourceLang, targetLang, provider, modelId, customSystemInstruction, systemPromptEnabled, geminiApiKey, openaiApiKey, openaiBaseUrl, openaiModel, openrouterApiKey, openrouterModel, deeplApiKey, microsoftSubscriptionKey, microsoftRegion, setIsTranslating, setErrorMessage, setTranslatedText]);
+  }, [sourceLang, targetLang, provider, modelId, customSystemInstruction, systemPromptEnabled, geminiApiKey, openaiApiKey, openaiBaseUrl, openaiModel, openrouterApiKey, openrouterModel, anthropicModel, ollamaModel, deeplApiKey, formality, microsoftSubscriptionKey, microsoftRegion, setIsTranslating, setErrorMessage, setTranslatedText]);
 
   useEffect(() => {
     if (!autoTranslate) return;
     if (debounceTimer.current) clearTimeout(debounceTimer.current);
+    if (inputText === shownTranslationText.current) {
+      shownTranslationText.current = null;
+      return;
+    }
 
     if (inputText.trim()) {
       debounceTimer.current = setTimeout(() => performTranslation(inputText), debounceMs);
@@ -128,17 +162,36 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
     }
   }, [setInputText, performTranslation]);
 
+  // Listen for a finished translation to show (notification "Open")
+  useEffect(() => {
+    if (platform.isAvailable()) {
+      const unlisten = platform.onShowTranslation(({ text, translation }) => {
+        // Unchanged input doesn't run the auto-translate effect at all
+        if (text !== useAppStore.getState().inputText) {
+          shownTranslationText.current = text;
+        }
+        latestRequestText.current = text;
+        setInputText(text);
+        setTranslatedText(translation);
+        setAlternatives([]);
+        setErrorMessage(null);
+        setIsTranslating(false);
+      });
+      return unlisten;
+    }
+  }, [setInputText, setTranslatedText, setErrorMessage, setIsTranslating]);
+
   // Get provider info
   const currentProvider = PROVIDERS.find(p => p.id === provider);
   const isLlmProvider = currentProvider?.category === 'llm';
 
   // Get the configured model name for display
-  const configuredModelName = provider === 'gemini' ? modelId : provider === 'openrouter' ? openrouterModel : openaiModel;
+  const configuredModelName = provider === 'gemini' ? modelId : provider === 'openrouter' ? openrouterModel : provider === 'anthropic' ? anthropicModel : provider === 'azure_openai' ? azureOpenaiDeployment : provider === 'ollama' ? ollamaModel : provider === 'gguf' ? (ggufModelPath.split('/').pop() || '') : openaiModel;
 
   // Clear verification when provider or model changes (don't auto-verify to save quota)
   useEffect(() => {
     clearModelVerification();
-  }, [provider, modelId, openaiModel, openrouterModel, clearModelVerification]);
+  }, [provider, modelId, openaiModel, openrouterModel, anthropicModel, azureOpenaiDeployment, ollamaModel, ggufModelPath, clearModelVerification]);
 
   // Manual verification handler - only runs when user clicks verify button
   const handleVerify = useCallback(async () => {
@@ -148,7 +201,11 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
     const hasCredentials =
       (provider === 'gemini' && geminiApiKey) ||
       (provider === 'openai' && openaiApiKey && openaiBaseUrl) ||
-      (provider === 'openrouter' && openrouterApiKey);
+      (provider === 'openrouter' && openrouterApiKey) ||
+      (provider === 'anthropic' && anthropicApiKey) ||
+      (provider === 'azure_openai' && azureOpenaiDeployment && (azureOpenaiAuth === 'entra' || azureOpenaiApiKey)) ||
+      provider === 'ollama' ||
+      (provider === 'gguf' && ggufModelPath);
 
     if (!hasCredentials) {
       setModelVerification({
@@ -170,7 +227,11 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
         openaiApiKey,
         openaiModel,
         openrouterApiKey,
-        openrouterModel
+        openrouterModel,
+        anthropicModel,
+        azureOpenaiDeployment,
+        ollamaModel,
+        ggufModelPath
       });
 
       setModelVerification({
@@ -186,7 +247,7 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
         error: error.message || 'Verification failed'
       });
     }
-  }, [provider, geminiApiKey, modelId, openaiApiKey, openaiBaseUrl, openaiModel, openrouterApiKey, openrouterModel, isLlmProvider, setModelVerification]);
+  }, [provider, geminiApiKey, modelId, openaiApiKey, openaiBaseUrl, openaiModel, openrouterApiKey, openrouterModel, anthropicApiKey, anthropicModel, azureOpenaiDeployment, azureOpenaiAuth, azureOpenaiApiKey, ollamaModel, ggufModelPath, isLlmProvider, setModelVerification]);
 
   const handleCopy = () => {
     if (translatedText) {
@@ -312,6 +373,25 @@ export const TranslatorView: React.FC<TranslatorViewProps> = ({ onOpenOCR }) =>
           </div>
         )}
 
+        {/* Alternatives: clicking one swaps it with the shown translation */}
+        {!errorMessage && !isTranslating && alternatives.length > 0 && (
+          <div className="flex flex-wrap gap-1.5 pt-3 mt-2 border-t border-black/5">
+            {alternatives.map((alternative, index) => (
+              <button
+                key={index}
+                onClick={() => {
+                  setAlternatives(alternatives.map((a, i) => (i === index ? translatedText : a)));
+                  setTranslatedText(alternative);
+                }}
+                className="px-2 py-1 text-xs text-macos-text bg-white/70 hover:bg-macos-active hover:text-white rounded-md border border-black/5 shadow-sm transition-colors"
+                title="Use this translation"
+              >
+                {alternative}
+              </button>
+            ))}
+          </div>
+        )}
+
         {isTranslating && (
           <div className="absolute top-4 right-4 bg-white/80 backdrop-blur px-2 py-1 rounded-md shadow-sm border border-black/5">
             <Loader2 size={16} className="text-macos-active animate-spin" />
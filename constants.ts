@@ -41,6 +41,38 @@ export const PROVIDERS: TranslationProvider[] = [
     requiresKey: true,
     description: 'GPT-4, Claude, Llama, etc.'
   },
+  {
+    id: 'anthropic',
+    name: 'Anthropic Claude',
+    category: 'llm',
+    enabled: true,
+    requiresKey: true,
+    description: 'Claude via the Messages API'
+  },
+  {
+    id: 'azure_openai',
+    name: 'Azure OpenAI',
+    category: 'llm',
+    enabled: true,
+    requiresKey: true,
+    description: 'GPT deployments on Azure'
+  },
+  {
+    id: 'ollama',
+    name: 'Ollama',
+    category: 'llm',
+    enabled: true,
+    requiresKey: false,
+    description: 'Local models, offline'
+  },
+  {
+    id: 'gguf',
+    name: 'Local GGUF (llama.cpp)',
+    category: 'llm',
+    enabled: true,
+    requiresKey: false,
+    description: 'Private, runs inside the app'
+  },
   // Cloud Providers
   {
     id: 'deepl',
@@ -66,6 +98,62 @@ export const PROVIDERS: TranslationProvider[] = [
     requiresKey: true,
     description: 'Azure Cognitive Services'
   },
+  {
+    id: 'libretranslate',
+    name: 'LibreTranslate',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: false,
+    description: 'Self-hosted, open source'
+  },
+  {
+    id: 'baidu',
+    name: 'Baidu Translate',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: true,
+    description: 'Reachable from mainland China'
+  },
+  {
+    id: 'youdao',
+    name: 'Youdao Translate',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: true,
+    description: 'Reachable from mainland China'
+  },
+  {
+    id: 'tencent',
+    name: 'Tencent Cloud TMT',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: true,
+    description: 'Reachable from mainland China'
+  },
+  {
+    id: 'amazon',
+    name: 'Amazon Translate',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: true,
+    description: 'AWS, with custom terminology'
+  },
+  {
+    id: 'google_cloud',
+    name: 'Google Cloud Translation',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: true,
+    description: 'Service account, with glossaries'
+  },
+  {
+    id: 'offline',
+    name: 'Offline (Marian)',
+    category: 'cloud',
+    enabled: true,
+    requiresKey: false,
+    description: 'Local models, no network'
+  },
 ];
 
 export const DEFAULT_SETTINGS = {
@@ -91,13 +179,82 @@ export const DEFAULT_SETTINGS = {
   openrouterApiKey: '',
   openrouterModel: 'openai/gpt-3.5-turbo',
 
+  // Anthropic Defaults
+  anthropicApiKey: '',
+  anthropicBaseUrl: 'https://api.anthropic.com',
+  anthropicModel: 'claude-haiku-4-5',
+
+  // Azure OpenAI Defaults
+  azureOpenaiEndpoint: '',
+  azureOpenaiRegion: 'eastus',
+  azureOpenaiDeployment: '',
+  azureOpenaiApiVersion: '2024-10-21',
+  azureOpenaiAuth: 'key' as const,
+  azureOpenaiApiKey: '',
+  azureOpenaiTenantId: '',
+  azureOpenaiClientId: '',
+  azureOpenaiClientSecret: '',
+
+  // Ollama Defaults
+  ollamaBaseUrl: 'http://localhost:11434',
+  ollamaModel: 'llama3.2',
+  ollamaKeepAlive: '5m',
+  ollamaNumCtx: 0,
+
+  // Local GGUF Defaults
+  ggufModelPath: '',
+  ggufTemplate: 'chatml' as const,
+  ggufCustomTemplate: '',
+  ggufThreads: 0,
+  ggufContextLength: 4096,
+
   // DeepL Defaults
   deeplApiKey: '',
+  formality: '',
 
   // Microsoft Translator Defaults
   microsoftSubscriptionKey: '',
   microsoftRegion: 'eastus',
 
+  // LibreTranslate Defaults
+  libretranslateBaseUrl: 'http://localhost:5000',
+  libretranslateApiKey: '',
+  libretranslateFormat: 'text' as const,
+  libretranslateAlternatives: 0,
+
+  // Baidu Translate Defaults
+  baiduAppId: '',
+  baiduSecretKey: '',
+
+  // Youdao Translate Defaults
+  youdaoAppKey: '',
+  youdaoAppSecret: '',
+
+  // Tencent Cloud TMT Defaults
+  tencentSecretId: '',
+  tencentSecretKey: '',
+  tencentRegion: 'ap-guangzhou',
+
+  // Amazon Translate Defaults
+  awsAccessKeyId: '',
+  awsSecretAccessKey: '',
+  awsProfile: '',
+  awsRegion: '',
+  awsTerminology: '',
+  awsEndpoint: '',
+
+  // Google Cloud Translation Defaults
+  googleCloudCredentialsPath: '',
+  googleCloudProjectId: '',
+  googleCloudLocation: 'global',
+  googleCloudGlossary: '',
+  googleCloudEndpoint: '',
+
+  // Offline Defaults
+  offlineModelsDir: '',
+  offlineThreads: 0,
+  offlineMemoryLimitMb: 1024,
+
   // Proxy Defaults
   proxyEnabled: false,
   proxyProtocol: 'http' as const,
@@ -105,18 +262,48 @@ export const DEFAULT_SETTINGS = {
   proxyPort: 8080,
   proxyUsername: '',
   proxyPassword: '',
+  proxyBypass: '',
 
   // Shortcut Defaults
   selectionShortcut: 'CommandOrControl+Shift+X',
 
+  // Result Output Defaults
+  quickTranslateOutput: 'window' as const,
+  ocrOutput: 'window' as const,
+
+  // Replace-Selection Defaults
+  replaceShortcut: 'CommandOrControl+Alt+X',
+  writingLang: 'en' as const,
+  replacePreview: false,
+
   // Startup Defaults
   launchAtStartup: false,
 
   // Quick Window Appearance Defaults
   quickWindowOpacity: 0.95,
   quickWindowBorderOpacity: 0.05,
+  quickWindowPlacement: 'cursor' as const,
 
   // Quick Window Language Defaults
   quickSourceLang: 'auto' as const,
   quickTargetLang: 'zh-CN' as const,
+
+  // Clipboard Watch Defaults
+  clipboardWatch: {
+    enabled: false,
+    watchPrimary: false,
+    minLength: 2,
+    output: 'quick' as const,
+    skipTargetLanguage: true,
+    denylist: [
+      'keepassxc',
+      '1password',
+      'bitwarden',
+      'enpass',
+      'seahorse',
+      'org.gnome.seahorse.Application',
+      'kwalletmanager5',
+      'secrets',
+    ],
+  },
 };
\ No newline at end of file
@@ -17,8 +17,20 @@ interface TranslateOptions {
   // OpenRouter Options
   openrouterApiKey?: string;
   openrouterModel?: string;
+  // Anthropic Options (sent by the backend, which holds the key)
+  anthropicModel?: string;
+  // Ollama Options
+  ollamaModel?: string;
+  // Local GGUF Options
+  ggufModelPath?: string;
+  // Receives partial output of streaming providers
+  onPartial?: (text: string) => void;
+  // Receives other candidate translations (LibreTranslate)
+  onAlternatives?: (alternatives: string[]) => void;
   // DeepL Options
   deeplApiKey?: string;
+  // DeepL and Amazon Translate; the provider's default if unset
+  formality?: 'formal' | 'informal';
   // Microsoft Options
   microsoftSubscriptionKey?: string;
   microsoftRegion?: string;
@@ -33,8 +45,18 @@ interface VerifyModelOptions {
   openaiModel?: string;
   openrouterApiKey?: string;
   openrouterModel?: string;
+  anthropicModel?: string;
+  azureOpenaiDeployment?: string;
+  ollamaModel?: string;
+  ggufModelPath?: string;
 }
 
+/** Providers implemented in the backend, besides custom and plugin ones */
+const BACKEND_PROVIDERS: ReadonlySet<TranslationProviderId> = new Set<TranslationProviderId>([
+  'anthropic', 'azure_openai', 'ollama', 'gguf', 'offline', 'libretranslate',
+  'baidu', 'youdao', 'tencent', 'amazon', 'google_cloud',
+]);
+
 // Helper to get Gemini client with the provided API key
 const getGeminiClient = (apiKey?: string) => {
   const key = apiKey || process.env.API_KEY || '';
@@ -58,6 +80,25 @@ export const translateText = async (
 
   const provider = options.provider || 'gemini';
 
+  // The backend runs the post-processor plugins itself
+  if (BACKEND_PROVIDERS.has(provider) || provider.startsWith('custom:') || provider.startsWith('plugin:')) {
+    return translateWithBackend(text, sourceLang, targetLang, options);
+  }
+
+  const translation = await translateInWebview(text, sourceLang, targetLang, provider, options);
+  return platform.postprocessTranslation(translation, sourceLang, targetLang, provider);
+};
+
+/**
+ * Translates with the providers called from the webview.
+ */
+const translateInWebview = async (
+  text: string,
+  sourceLang: string,
+  targetLang: string,
+  provider: TranslationProviderId,
+  options: TranslateOptions
+): Promise<string> => {
   if (provider === 'openai') {
     return translateWithOpenAI(text, sourceLang, targetLang, options);
   }
@@ -211,11 +252,79 @@ export const verifyModelIdentity = async (options: VerifyModelOptions): Promise<
     }
   }
 
-  throw new Error("Model verification is only available for LLM providers (Gemini, OpenAI, OpenRouter).");
+  if (provider === 'anthropic') {
+    // Lists the models with the key held by the backend
+    const requestedModel = options.anthropicModel || 'claude-haiku-4-5';
+    try {
+      const models = await platform.listProviderModels('anthropic');
+      const model = models.find((m) => m.id === requestedModel);
+      if (!model) {
+        throw new Error(`Model ${requestedModel} not found`);
+      }
+      return model.name;
+    } catch (error: any) {
+      console.error("Anthropic Verification Error:", error);
+      throw new Error(`Anthropic Verification Error: ${error.message || error}`);
+    }
+  }
+
+  if (provider === 'azure_openai') {
+    // The backend asks the deployment which model it serves
+    try {
+      const [deployment] = await platform.listProviderModels('azure_openai');
+      return deployment.name;
+    } catch (error: any) {
+      console.error("Azure OpenAI Verification Error:", error);
+      throw new Error(`Azure OpenAI Verification Error: ${error.message || error}`);
+    }
+  }
+
+  if (provider === 'ollama') {
+    // Checks that the model has been pulled into the local daemon
+    const requestedModel = options.ollamaModel || 'llama3.2';
+    try {
+      const models = await platform.listProviderModels('ollama');
+      const model = models.find((m) => m.id === requestedModel || m.id === `${requestedModel}:latest`);
+      if (!model) {
+        throw new Error(`Model ${requestedModel} is not pulled`);
+      }
+      return model.name;
+    } catch (error: any) {
+      console.error("Ollama Verification Error:", error);
+      throw new Error(`Ollama Verification Error: ${error.message || error}`);
+    }
+  }
+
+  if (provider === 'gguf') {
+    // The model runs in the app, so the configured file is the model in use
+    if (!options.ggufModelPath) {
+      throw new Error("Choose a GGUF model file in Settings.");
+    }
+    return options.ggufModelPath.split('/').pop() || options.ggufModelPath;
+  }
+
+  throw new Error("Model verification is only available for LLM providers (Gemini, OpenAI, OpenRouter, Anthropic, Azure OpenAI, Ollama, Local GGUF).");
 };
 
 // --- Internal Providers ---
 
+/**
+ * Providers implemented only in the backend, which also streams their
+ * output back through `onPartial`.
+ */
+const translateWithBackend = async (text: string, source: string, target: string, options: TranslateOptions) => {
+  const systemPrompt = options.systemPromptEnabled !== false ? options.customSystemInstruction : undefined;
+  try {
+    return await platform.translateText(text, source, target, {
+      provider: options.provider,
+      systemPrompt: systemPrompt || undefined,
+      formality: options.formality,
+    }, options.onPartial, options.onAlternatives);
+  } catch (error: any) {
+    throw new Error(typeof error === 'string' ? error : error.message || 'Translation failed');
+  }
+};
+
 const translateWithGemini = async (text: string, source: string, target: string, options: TranslateOptions) => {
   const apiKey = options.geminiApiKey || process.env.API_KEY || '';
   if (!apiKey) {
@@ -513,6 +622,10 @@ const translateWithDeepL = async (text: string, source: string, target: string,
   if (source !== 'auto') {
     params.append('source_lang', source.toUpperCase().split('-')[0]); // DeepL source is usually 2 chars (EN, ZH, JA)
   }
+  if (options.formality) {
+    // The prefer_ variants fall back to the default for languages without formality
+    params.append('formality', options.formality === 'formal' ? 'prefer_more' : 'prefer_less');
+  }
 
   const url = `${baseUrl}?${params.toString()}`;
 
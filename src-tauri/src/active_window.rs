@@ -0,0 +1,123 @@
+//! Identifies the application owning the focused window.
+//!
+//! Asks the compositor over its IPC on Hyprland, Sway and niri, and falls
+//! back to EWMH (`_NET_ACTIVE_WINDOW` and its `WM_CLASS`) everywhere else.
+//! Other Wayland compositors only expose XWayland clients that way.
+
+use std::process::Command;
+
+use serde_json::Value;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, Window};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FocusedWindow {
+    /// First part of `WM_CLASS` (e.g. `keepassxc`), or the Wayland app id
+    pub instance: String,
+    /// Second part of `WM_CLASS` (e.g. `KeePassXC`), or the Wayland app id
+    pub class: String,
+}
+
+impl FocusedWindow {
+    /// Case-insensitive match of `name` against either `WM_CLASS` part.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim();
+        !name.is_empty()
+            && (self.instance.eq_ignore_ascii_case(name) || self.class.eq_ignore_ascii_case(name))
+    }
+}
+
+/// X11 id of the focused window.
+pub fn focused_window_id() -> Option<Window> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    active_window(&conn, screen_num)
+}
+
+pub fn focused_window() -> Option<FocusedWindow> {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        // {"class": "kitty", "initialClass": "kitty", ...}
+        let window = command_json("hyprctl", &["activewindow", "-j"])?;
+        return from_app_ids(&window["initialClass"], &window["class"]);
+    }
+    if std::env::var_os("SWAYSOCK").is_some() {
+        let tree = command_json("swaymsg", &["-t", "get_tree"])?;
+        let node = focused_sway_node(&tree)?;
+        return from_app_ids(&node["app_id"], &node["window_properties"]["class"]);
+    }
+    if std::env::var_os("NIRI_SOCKET").is_some() {
+        let window = command_json("niri", &["msg", "--json", "focused-window"])?;
+        return from_app_ids(&window["app_id"], &window["app_id"]);
+    }
+    focused_x11_window()
+}
+
+fn command_json(program: &str, args: &[&str]) -> Option<Value> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+/// Builds a window from compositor fields, either of which may be missing
+/// (XWayland windows only carry a class).
+fn from_app_ids(instance: &Value, class: &Value) -> Option<FocusedWindow> {
+    let instance = instance.as_str().filter(|s| !s.is_empty());
+    let class = class.as_str().filter(|s| !s.is_empty());
+    let first = instance.or(class)?;
+    Some(FocusedWindow {
+        instance: first.to_string(),
+        class: class.unwrap_or(first).to_string(),
+    })
+}
+
+fn focused_sway_node(node: &Value) -> Option<&Value> {
+    if node["focused"].as_bool() == Some(true) {
+        return Some(node);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[*key].as_array())
+        .flatten()
+        .find_map(focused_sway_node)
+}
+
+fn focused_x11_window() -> Option<FocusedWindow> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let window = active_window(&conn, screen_num)?;
+
+    let wm_class = conn
+        .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+        .ok()?
+        .reply()
+        .ok()?
+        .value;
+
+    // "instance\0class\0"
+    let mut parts = wm_class
+        .split(|byte| *byte == 0)
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+    Some(FocusedWindow {
+        instance: parts.next().unwrap_or_default(),
+        class: parts.next().unwrap_or_default(),
+    })
+}
+
+fn active_window(conn: &impl Connection, screen_num: usize) -> Option<Window> {
+    let root = conn.setup().roots.get(screen_num)?.root;
+
+    let active_atom = conn
+        .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    let window: Window = conn
+        .get_property(false, root, active_atom, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .next()?;
+    (window != 0).then_some(window)
+}
@@ -0,0 +1,199 @@
+//! Per-application rules, matched against the focused window whenever text
+//! is captured: e.g. translate terminals English→Chinese with a programming
+//! prompt, use DeepL for the mail client, never read from password managers.
+//!
+//! Rules are part of the settings (`appRules`), so they are saved, exported
+//! and switched with profiles like everything else. They are evaluated here,
+//! so the quick window only receives the resulting overrides.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::active_window::{self, FocusedWindow};
+use crate::translate::TranslationSettings;
+use crate::AppState;
+
+/// Where rules were kept before they moved into the settings.
+const LEGACY_RULES_FILE: &str = "app-rules.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppRule {
+    /// `WM_CLASS` part or Wayland app id, matched case-insensitively
+    pub app: String,
+    /// Ignore the shortcut, clipboard watch and replace mode in this app
+    pub never_capture: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_lang: Option<String>,
+    /// Replaces the system prompt of LLM providers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// `formal` or `informal`, for providers that support it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formality: Option<String>,
+}
+
+/// What a matching rule changes about a translation. Sent to the quick
+/// window along with the captured text, which passes it back to
+/// `translate_text`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuleOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formality: Option<String>,
+}
+
+impl RuleOverrides {
+    /// Applies the overrides to a copy of the current translation settings.
+    pub fn apply(&self, settings: &mut TranslationSettings) {
+        if let Some(provider) = &self.provider {
+            settings.provider = provider.clone();
+        }
+        if let Some(source_lang) = &self.source_lang {
+            settings.quick_source_lang = source_lang.clone();
+        }
+        if let Some(target_lang) = &self.target_lang {
+            settings.quick_target_lang = target_lang.clone();
+        }
+        if let Some(system_prompt) = &self.system_prompt {
+            settings.custom_system_instruction = system_prompt.clone();
+            settings.system_prompt_enabled = true;
+        }
+        if let Some(formality) = &self.formality {
+            settings.formality = formality.clone();
+        }
+    }
+}
+
+/// Outcome of evaluating the rules for the focused window.
+#[derive(Debug, Clone, Default)]
+pub enum Capture {
+    #[default]
+    Allowed,
+    AllowedWith(RuleOverrides),
+    Denied,
+}
+
+impl Capture {
+    pub fn overrides(self) -> RuleOverrides {
+        match self {
+            Capture::AllowedWith(overrides) => overrides,
+            _ => RuleOverrides::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AppRules {
+    rules: Mutex<Vec<AppRule>>,
+}
+
+impl AppRules {
+    /// Makes `rules` current, see [`crate::settings`].
+    pub fn set(&self, rules: Vec<AppRule>) {
+        *self.rules.lock().unwrap() = rules;
+    }
+
+    /// First rule matching `window`, in list order.
+    pub fn evaluate(&self, window: &FocusedWindow) -> Capture {
+        let rules = self.rules.lock().unwrap();
+        let Some(rule) = rules.iter().find(|rule| window.matches(&rule.app)) else {
+            return Capture::Allowed;
+        };
+        if rule.never_capture {
+            return Capture::Denied;
+        }
+        Capture::AllowedWith(RuleOverrides {
+            provider: rule.provider.clone(),
+            source_lang: rule.source_lang.clone(),
+            target_lang: rule.target_lang.clone(),
+            system_prompt: rule.system_prompt.clone(),
+            formality: rule.formality.clone(),
+        })
+    }
+}
+
+/// Evaluates the rules against the currently focused application. Call it
+/// before capturing, while the source application still has focus.
+pub fn for_focused_window(app: &AppHandle) -> Capture {
+    match active_window::focused_window() {
+        Some(window) => app.state::<AppState>().app_rules.evaluate(&window),
+        None => Capture::Allowed,
+    }
+}
+
+/// Checks what the types alone don't.
+pub fn validate(rules: &[AppRule]) -> Result<(), String> {
+    for rule in rules {
+        if rule.app.trim().is_empty() {
+            return Err("Application rules need an application".to_string());
+        }
+        if let Some(formality) = &rule.formality {
+            if !["formal", "informal"].contains(&formality.as_str()) {
+                return Err(format!("{}: invalid formality '{}'", rule.app, formality));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Takes the rules of the former `app-rules.json`, if it still exists, so
+/// they can be moved into the settings. The file is renamed rather than
+/// deleted.
+pub fn take_legacy(app: &AppHandle) -> Option<Vec<AppRule>> {
+    let path = app.path().app_config_dir().ok()?.join(LEGACY_RULES_FILE);
+    let contents = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::rename(&path, path.with_extension("json.imported"));
+    match serde_json::from_str::<Vec<AppRule>>(&contents) {
+        Ok(rules) => Some(rules),
+        Err(e) => {
+            log::error!("Ignoring invalid {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn get_app_rules(app: AppHandle) -> Result<Vec<AppRule>, String> {
+    Ok(crate::settings::current(&app).app_rules)
+}
+
+#[tauri::command]
+pub async fn set_app_rules(app: AppHandle, rules: Vec<AppRule>) -> Result<(), String> {
+    let rules: Vec<AppRule> = rules
+        .into_iter()
+        .filter(|rule| !rule.app.trim().is_empty())
+        .map(|rule| AppRule {
+            app: rule.app.trim().to_string(),
+            // Empty fields from the settings form mean "don't override"
+            provider: rule.provider.filter(|s| !s.is_empty()),
+            source_lang: rule.source_lang.filter(|s| !s.is_empty()),
+            target_lang: rule.target_lang.filter(|s| !s.is_empty() && s != "auto"),
+            system_prompt: rule.system_prompt.filter(|s| !s.trim().is_empty()),
+            formality: rule.formality.filter(|s| !s.is_empty()),
+            ..rule
+        })
+        .collect();
+
+    crate::settings::update(&app, None, |settings| {
+        settings.app_rules = rules;
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,212 @@
+//! Command line actions.
+//!
+//! The same arguments work for the first launch and for later launches, which
+//! the single-instance plugin forwards (with their working directory) to the
+//! running instance before exiting.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliAction {
+    /// No arguments: bring up the main window
+    Show,
+    /// `--hidden` / `--autostart`: stay in the tray
+    Hidden,
+    /// `--settings`
+    Settings,
+    /// `--ocr`: same as the tray's "OCR Screenshot" item
+    Ocr,
+    /// `--translate-selection`: same as the global shortcut
+    TranslateSelection,
+    /// `--text <text>` or `--file <path>`: translate in the main window
+    Translate(String),
+    /// `--profile <name>`: switch to a configuration profile
+    Profile(String),
+    /// `--provider <id>`: switch the translation provider
+    Provider(String),
+}
+
+/// The first launch's action, held until the main window listens for the
+/// events it sends.
+enum Startup {
+    Waiting(Option<CliAction>),
+    Ready,
+}
+
+static STARTUP: Mutex<Startup> = Mutex::new(Startup::Waiting(None));
+
+/// Parses `args` (without the program name). Relative `--file` paths are
+/// resolved against `cwd`, the launching process' working directory.
+pub fn parse_args(args: &[String], cwd: &Path) -> Result<CliAction, String> {
+    let mut args = args.iter();
+    let mut action = CliAction::Show;
+
+    while let Some(arg) = args.next() {
+        action = match arg.as_str() {
+            "--hidden" | "--autostart" => CliAction::Hidden,
+            "--settings" => CliAction::Settings,
+            "--ocr" => CliAction::Ocr,
+            "--translate-selection" => CliAction::TranslateSelection,
+            "--text" => {
+                let text = args.next().ok_or("--text requires a value")?;
+                CliAction::Translate(text.clone())
+            }
+            "--profile" => {
+                let name = args.next().ok_or("--profile requires a name")?;
+                CliAction::Profile(name.clone())
+            }
+            "--provider" => {
+                let id = args.next().ok_or("--provider requires a provider id")?;
+                CliAction::Provider(id.clone())
+            }
+            "--file" => {
+                let path = cwd.join(args.next().ok_or("--file requires a path")?);
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                CliAction::Translate(text)
+            }
+            // Unknown flags (e.g. from desktop launchers) are ignored
+            _ => continue,
+        };
+    }
+
+    Ok(action)
+}
+
+pub fn perform(app: &AppHandle, action: CliAction) {
+    match action {
+        CliAction::Hidden => {}
+        CliAction::Show => crate::show_main_window(app),
+        CliAction::Settings => crate::open_settings(app),
+        CliAction::Ocr => crate::spawn_ocr_capture(app),
+        CliAction::TranslateSelection => {
+            let app = app.clone();
+            std::thread::spawn(move || crate::trigger_quick_translate(&app));
+        }
+        CliAction::Translate(text) => crate::open_text_in_main_window(app, &text),
+        CliAction::Profile(name) => {
+            if let Err(e) = crate::profiles::switch(app, &name) {
+                crate::tray::report_error(app, "Switching profile failed", &e);
+            }
+        }
+        CliAction::Provider(id) => {
+            let result = crate::settings::update(app, None, |settings| {
+                settings.translation.provider = id;
+                Ok(())
+            });
+            if let Err(e) = result {
+                crate::tray::report_error(app, "Switching provider failed", &e);
+            }
+        }
+    }
+}
+
+/// Runs the first launch's `action`, off the calling thread. Actions that
+/// reach the main window wait for [`main_window_ready`].
+pub fn perform_at_startup(app: &AppHandle, action: CliAction) {
+    let needs_window = !matches!(action, CliAction::Profile(_) | CliAction::Provider(_));
+    if needs_window {
+        let mut startup = STARTUP.lock().unwrap();
+        if let Startup::Waiting(pending) = &mut *startup {
+            *pending = Some(action);
+            return;
+        }
+    }
+    let app = app.clone();
+    std::thread::spawn(move || perform(&app, action));
+}
+
+/// Called once the main window has registered its event listeners; runs the
+/// action [`perform_at_startup`] held back.
+pub fn main_window_ready(app: &AppHandle) {
+    let startup = std::mem::replace(&mut *STARTUP.lock().unwrap(), Startup::Ready);
+    if let Startup::Waiting(Some(action)) = startup {
+        perform(app, action);
+    }
+}
+
+/// Callback for the single-instance plugin: `argv` and `cwd` come from the
+/// second launch, which exits right after handing them over.
+pub fn handle_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let args = argv.get(1..).unwrap_or_default();
+    match parse_args(args, Path::new(&cwd)) {
+        // A racing autostart parses as Hidden and leaves the window alone
+        Ok(action) => perform(app, action),
+        Err(e) => log::error!("Ignoring forwarded arguments: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliAction, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args, Path::new("/nonexistent"))
+    }
+
+    #[test]
+    fn parses_flags() {
+        assert_eq!(parse(&[]), Ok(CliAction::Show));
+        assert_eq!(parse(&["--hidden"]), Ok(CliAction::Hidden));
+        assert_eq!(parse(&["--autostart"]), Ok(CliAction::Hidden));
+        assert_eq!(parse(&["--settings"]), Ok(CliAction::Settings));
+        assert_eq!(parse(&["--ocr"]), Ok(CliAction::Ocr));
+        assert_eq!(
+            parse(&["--translate-selection"]),
+            Ok(CliAction::TranslateSelection)
+        );
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(
+            parse(&["--text", "--hidden"]),
+            Ok(CliAction::Translate("--hidden".to_string()))
+        );
+        assert_eq!(
+            parse(&["--profile", "work"]),
+            Ok(CliAction::Profile("work".to_string()))
+        );
+        assert_eq!(
+            parse(&["--provider", "deepl"]),
+            Ok(CliAction::Provider("deepl".to_string()))
+        );
+        assert!(parse(&["--text"]).is_err());
+        assert!(parse(&["--profile"]).is_err());
+        assert!(parse(&["--provider"]).is_err());
+        assert!(parse(&["--file"]).is_err());
+    }
+
+    #[test]
+    fn last_action_wins_and_unknown_flags_are_ignored() {
+        assert_eq!(
+            parse(&["--hidden", "--settings", "--gapplication-service"]),
+            Ok(CliAction::Settings)
+        );
+        assert_eq!(parse(&["--text", "hello", "--ocr"]), Ok(CliAction::Ocr));
+        assert_eq!(parse(&["%U", "-x"]), Ok(CliAction::Show));
+    }
+
+    #[test]
+    fn reads_files_relative_to_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("note.txt"), "Hallo Welt\n").unwrap();
+        let args = ["--file".to_string(), "note.txt".to_string()];
+
+        assert_eq!(
+            parse_args(&args, dir.path()),
+            Ok(CliAction::Translate("Hallo Welt\n".to_string()))
+        );
+        let absolute = dir.path().join("note.txt").display().to_string();
+        assert_eq!(
+            parse_args(&["--file".to_string(), absolute], Path::new("/elsewhere")),
+            Ok(CliAction::Translate("Hallo Welt\n".to_string()))
+        );
+        let error = parse_args(&args, Path::new("/nonexistent")).unwrap_err();
+        assert!(error.starts_with("Failed to read /nonexistent/note.txt"));
+    }
+}
@@ -0,0 +1,406 @@
+//! Clipboard watch mode: translates whatever lands on the clipboard (and
+//! optionally the primary selection) without pressing the shortcut.
+//!
+//! A background thread polls the selections and skips short snippets, text
+//! the app copied itself, text already in the target language and copies made
+//! while a denylisted application (password managers by default) is focused.
+//!
+//! The settings are part of [`crate::settings`]; the frontend reads and
+//! changes them with `get_settings` and `update_settings`, which replaced the
+//! dedicated `get_clipboard_watch` and `set_clipboard_watch` commands.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use arboard::{Clipboard, GetExtLinux, LinuxClipboardKind};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::app_rules::Capture;
+use crate::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How many of the app's own clipboard writes are remembered.
+const OWN_TEXTS_CAPACITY: usize = 8;
+/// Longer texts are almost certainly not meant to be translated in a popup.
+const MAX_LENGTH: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchOutput {
+    /// Show the text in the quick window near the cursor
+    Quick,
+    /// Translate in the background and show a desktop notification
+    Notification,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardWatchSettings {
+    pub enabled: bool,
+    /// Also react to the X11/Wayland primary selection (select to translate)
+    pub watch_primary: bool,
+    /// Minimum number of non-whitespace characters
+    pub min_length: usize,
+    pub output: WatchOutput,
+    pub skip_target_language: bool,
+    /// `WM_CLASS` names of applications whose copies are never read
+    pub denylist: Vec<String>,
+}
+
+impl Default for ClipboardWatchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            watch_primary: false,
+            min_length: 2,
+            output: WatchOutput::Quick,
+            skip_target_language: true,
+            denylist: [
+                "keepassxc",
+                "1password",
+                "bitwarden",
+                "enpass",
+                "seahorse",
+                "org.gnome.seahorse.Application",
+                "kwalletmanager5",
+                "secrets",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ClipboardWatcher {
+    settings: Mutex<ClipboardWatchSettings>,
+    own_texts: Mutex<VecDeque<String>>,
+}
+
+impl ClipboardWatcher {
+    pub fn settings(&self) -> ClipboardWatchSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Remembers text the app puts on the clipboard so it isn't translated
+    /// right back.
+    pub fn note_own_text(&self, text: &str) {
+        let mut own_texts = self.own_texts.lock().unwrap();
+        if own_texts.len() == OWN_TEXTS_CAPACITY {
+            own_texts.pop_front();
+        }
+        own_texts.push_back(text.trim().to_string());
+    }
+
+    fn is_own_text(&self, text: &str) -> bool {
+        let text = text.trim();
+        self.own_texts.lock().unwrap().iter().any(|own| own == text)
+    }
+}
+
+/// Hands the watcher thread new settings, see [`crate::settings`].
+pub fn apply_settings(app: &AppHandle, settings: ClipboardWatchSettings) {
+    *app.state::<AppState>()
+        .clipboard_watch
+        .settings
+        .lock()
+        .unwrap() = settings;
+}
+
+pub fn toggle(app: &AppHandle) {
+    let result = crate::settings::update(app, None, |settings| {
+        settings.clipboard_watch.enabled = !settings.clipboard_watch.enabled;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::error!("Failed to toggle clipboard watch: {}", e);
+    }
+}
+
+// --- Watcher Thread ---
+
+/// Tracks one selection. A change is reported once the content stayed the
+/// same for two polls, so dragging out a primary selection fires once.
+#[derive(Default)]
+struct Selection {
+    last_seen: Option<String>,
+    last_reported: Option<String>,
+}
+
+impl Selection {
+    fn update(&mut self, current: Option<String>) -> Option<String> {
+        let stable = current.is_some() && current == self.last_seen;
+        self.last_seen = current;
+        if stable && self.last_seen != self.last_reported {
+            self.last_reported = self.last_seen.clone();
+            return self.last_reported.clone();
+        }
+        None
+    }
+
+    /// Treats the current content as already handled.
+    fn reset(&mut self, current: Option<String>) {
+        self.last_seen = current.clone();
+        self.last_reported = current;
+    }
+}
+
+fn read(clipboard: &mut Clipboard, kind: LinuxClipboardKind) -> Option<String> {
+    clipboard.get().clipboard(kind).text().ok()
+}
+
+pub fn spawn(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut clipboard: Option<Clipboard> = None;
+        let mut regular = Selection::default();
+        let mut primary = Selection::default();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let state = app.state::<AppState>();
+            let settings = state.clipboard_watch.settings();
+            if !settings.enabled || state.tray.is_paused() {
+                // Release the clipboard connection while idle
+                clipboard = None;
+                continue;
+            }
+
+            let cb = match clipboard.as_mut() {
+                Some(cb) => cb,
+                None => match Clipboard::new() {
+                    Ok(cb) => {
+                        // Don't translate whatever was copied before enabling
+                        let cb = clipboard.insert(cb);
+                        regular.reset(read(cb, LinuxClipboardKind::Clipboard));
+                        primary.reset(read(cb, LinuxClipboardKind::Primary));
+                        continue;
+                    }
+                    Err(e) => {
+                        log::error!("Clipboard watch unavailable: {}", e);
+                        std::thread::sleep(POLL_INTERVAL * 10);
+                        continue;
+                    }
+                },
+            };
+
+            let mut changed = regular.update(read(cb, LinuxClipboardKind::Clipboard));
+            let current_primary = read(cb, LinuxClipboardKind::Primary);
+            if settings.watch_primary {
+                changed = changed.or(primary.update(current_primary));
+            } else {
+                primary.reset(current_primary);
+            }
+
+            if let Some(text) = changed {
+                handle_change(&app, text, &settings);
+            }
+        }
+    });
+}
+
+fn handle_change(app: &AppHandle, text: String, settings: &ClipboardWatchSettings) {
+    let state = app.state::<AppState>();
+
+    let length = text.chars().filter(|c| !c.is_whitespace()).count();
+    if length < settings.min_length.max(1) || length > MAX_LENGTH {
+        return;
+    }
+    // Copies from our own windows or written by the app itself
+    if state.clipboard_watch.is_own_text(&text)
+        || app
+            .webview_windows()
+            .values()
+            .any(|window| window.is_focused().unwrap_or(false))
+    {
+        return;
+    }
+    let capture = match crate::active_window::focused_window() {
+        Some(window) if settings.denylist.iter().any(|name| window.matches(name)) => return,
+        Some(window) => state.app_rules.evaluate(&window),
+        None => Capture::Allowed,
+    };
+    if matches!(capture, Capture::Denied) {
+        return;
+    }
+    let overrides = capture.overrides();
+
+    if settings.skip_target_language {
+        let target = overrides.target_lang.clone().unwrap_or_else(|| {
+            state
+                .translation_settings
+                .lock()
+                .unwrap()
+                .quick_target_lang
+                .clone()
+        });
+        if is_in_language(&text, &target) {
+            return;
+        }
+    }
+
+    match settings.output {
+        WatchOutput::Quick => crate::show_quick_window_with(app, text, overrides),
+        WatchOutput::Notification => crate::notifications::translate_to_notification(
+            app,
+            text,
+            overrides,
+            "Clipboard translation failed",
+        ),
+    }
+}
+
+// --- Language Heuristic ---
+
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "with", "for", "this",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "mit", "ein", "eine", "ich", "zu", "auf",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "des", "une", "un", "pas", "que", "pour", "dans",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "que", "una", "por", "para", "con", "del",
+        ],
+    ),
+];
+
+/// Cheap guess whether `text` is already written in `lang`, by script for
+/// CJK/Cyrillic targets and by common words for Latin-script ones.
+fn is_in_language(text: &str, lang: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return false;
+    }
+    let ratio = |pred: fn(&char) -> bool| {
+        letters.iter().filter(|c| pred(c)).count() as f32 / letters.len() as f32
+    };
+    let is_kana = |c: &char| matches!(*c, '\u{3040}'..='\u{30FF}');
+    let is_han = |c: &char| matches!(*c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}');
+    let is_hangul = |c: &char| matches!(*c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}');
+    let is_cyrillic = |c: &char| matches!(*c, '\u{0400}'..='\u{04FF}');
+
+    match lang {
+        "zh-CN" | "zh-TW" => ratio(is_han) > 0.5 && ratio(is_kana) == 0.0,
+        "ja" => ratio(is_kana) > 0.1,
+        "ko" => ratio(is_hangul) > 0.5,
+        "ru" => ratio(is_cyrillic) > 0.5,
+        _ => {
+            if ratio(|c| c.is_ascii_alphabetic()) < 0.7 {
+                return false;
+            }
+            let words: Vec<String> = text
+                .split(|c: char| !c.is_alphabetic())
+                .filter(|w| !w.is_empty())
+                .map(str::to_lowercase)
+                .collect();
+            let score = |stopwords: &[&str]| {
+                words
+                    .iter()
+                    .filter(|w| stopwords.contains(&w.as_str()))
+                    .count()
+            };
+            let best = STOPWORDS
+                .iter()
+                .map(|(code, stopwords)| (*code, score(stopwords)))
+                .max_by_key(|(_, score)| *score);
+            matches!(best, Some((code, score)) if code == lang && score > 0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_language_by_script() {
+        let cases = [
+            ("你好，世界", "zh-CN", true),
+            ("你好，世界", "ja", false),
+            ("こんにちは世界", "ja", true),
+            ("こんにちは世界", "zh-CN", false),
+            ("안녕하세요 세계", "ko", true),
+            ("Привет, как дела?", "ru", true),
+            ("Привет, как дела?", "en", false),
+            ("Hello world", "zh-CN", false),
+            ("Hello world", "ru", false),
+        ];
+        for (text, lang, expected) in cases {
+            assert_eq!(is_in_language(text, lang), expected, "{} in {}", text, lang);
+        }
+    }
+
+    #[test]
+    fn detects_latin_languages_by_common_words() {
+        let cases = [
+            ("This is the text to translate", "en", true),
+            ("This is the text to translate", "de", false),
+            ("Das ist nicht der richtige Weg", "de", true),
+            ("Das ist nicht der richtige Weg", "en", false),
+            ("Le chat est dans la maison", "fr", true),
+            ("El perro y los gatos", "es", true),
+            // No common words, or a language without a list: translate
+            ("Quick brown fox", "en", false),
+            ("Questo è il testo", "it", false),
+        ];
+        for (text, lang, expected) in cases {
+            assert_eq!(is_in_language(text, lang), expected, "{} in {}", text, lang);
+        }
+    }
+
+    #[test]
+    fn detects_mixed_text_by_majority() {
+        let cases = [
+            ("This is the 中文 word", "en", true),
+            ("Use 中文 here", "zh-CN", false),
+            ("这是一个测试 test", "zh-CN", true),
+            ("Hello мир, как дела", "ru", true),
+            ("Hello мир, как дела", "en", false),
+            ("日本語の text", "ja", true),
+        ];
+        for (text, lang, expected) in cases {
+            assert_eq!(is_in_language(text, lang), expected, "{} in {}", text, lang);
+        }
+    }
+
+    #[test]
+    fn handles_short_strings() {
+        let cases = [
+            ("", "en", false),
+            ("123 + 456", "en", false),
+            ("!?", "zh-CN", false),
+            ("OK", "en", false),
+            ("the", "en", true),
+            ("und", "de", true),
+            ("中", "zh-CN", true),
+            ("я", "ru", true),
+        ];
+        for (text, lang, expected) in cases {
+            assert_eq!(
+                is_in_language(text, lang),
+                expected,
+                "{:?} in {}",
+                text,
+                lang
+            );
+        }
+    }
+}
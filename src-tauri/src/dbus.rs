@@ -0,0 +1,133 @@
+//! Session bus service so GNOME extensions, KDE widgets and shell scripts can
+//! drive the running app, e.g.
+//!
+//! ```sh
+//! gdbus call --session --dest io.github.LightTranslator \
+//!     --object-path /io/github/LightTranslator \
+//!     --method io.github.LightTranslator.Translate "bonjour" "auto" "en"
+//! ```
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager};
+use zbus::{fdo, interface, object_server::SignalEmitter, proxy};
+
+use crate::search_provider::{self, GnomeSearchProvider, KRunnerPlugin, SearchEngine};
+
+pub const BUS_NAME: &str = "io.github.LightTranslator";
+pub const OBJECT_PATH: &str = "/io/github/LightTranslator";
+
+/// Keeps the bus connection (and therefore the exported object) alive.
+pub struct DbusConnection(#[allow(dead_code)] zbus::Connection);
+
+struct LightTranslatorService {
+    app: AppHandle,
+}
+
+#[interface(name = "io.github.LightTranslator")]
+impl LightTranslatorService {
+    /// Translates `text` with the configured provider. Empty `src`/`dst` fall
+    /// back to the quick window languages.
+    async fn translate(
+        &self,
+        text: String,
+        src: String,
+        dst: String,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> fdo::Result<String> {
+        let translation = crate::translate_with_settings(&self.app, &text, &src, &dst)
+            .await
+            .map_err(fdo::Error::Failed)?;
+
+        Self::translation_finished(&emitter, &text, &translation).await?;
+
+        Ok(translation)
+    }
+
+    /// Same as pressing the global shortcut.
+    async fn translate_selection(&self) {
+        let app = self.app.clone();
+        // trigger_quick_translate sleeps while the clipboard settles
+        let _ = tauri::async_runtime::spawn_blocking(move || crate::trigger_quick_translate(&app))
+            .await;
+    }
+
+    /// Shows `text` in the quick window, placed like the shortcut would.
+    async fn show_quick_translate(&self, text: String) {
+        crate::show_quick_window(&self.app, text);
+    }
+
+    /// Same as the tray's "OCR Screenshot" item.
+    async fn ocr_region(&self) -> fdo::Result<()> {
+        crate::ocr_capture(&self.app)
+            .await
+            .map(|_| ())
+            .map_err(fdo::Error::Failed)
+    }
+
+    async fn show_window(&self) {
+        crate::show_main_window(&self.app);
+    }
+
+    /// Sets the source/target languages of both the main panel and the quick
+    /// window.
+    async fn set_languages(&self, src: String, dst: String) -> fdo::Result<()> {
+        crate::set_quick_languages(&self.app, &src, &dst).map_err(fdo::Error::InvalidArgs)
+    }
+
+    #[zbus(signal)]
+    async fn translation_finished(
+        emitter: &SignalEmitter<'_>,
+        text: &str,
+        translation: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Client side of the service, used by processes that relay requests to the
+/// running app (e.g. the native messaging host).
+#[proxy(
+    interface = "io.github.LightTranslator",
+    default_service = "io.github.LightTranslator",
+    default_path = "/io/github/LightTranslator",
+    gen_blocking = false
+)]
+pub trait LightTranslator {
+    fn translate(&self, text: &str, src: &str, dst: &str) -> zbus::Result<String>;
+
+    fn show_quick_translate(&self, text: &str) -> zbus::Result<()>;
+}
+
+/// Claims the bus name and exports the service along with the launcher search
+/// providers. Failures are logged only, the app is fully usable without a
+/// session bus.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let service = LightTranslatorService { app: app.clone() };
+        let engine = Arc::new(SearchEngine::new(app.clone()));
+        let connection = zbus::connection::Builder::session()
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, service))
+            .and_then(|builder| {
+                builder.serve_at(
+                    search_provider::GNOME_OBJECT_PATH,
+                    GnomeSearchProvider::new(engine.clone()),
+                )
+            })
+            .and_then(|builder| {
+                builder.serve_at(
+                    search_provider::KRUNNER_OBJECT_PATH,
+                    KRunnerPlugin::new(engine),
+                )
+            });
+
+        match connection {
+            Ok(builder) => match builder.build().await {
+                Ok(connection) => {
+                    app.manage(DbusConnection(connection));
+                }
+                Err(e) => log::error!("Failed to register D-Bus service: {}", e),
+            },
+            Err(e) => log::error!("Failed to setup D-Bus service: {}", e),
+        }
+    });
+}
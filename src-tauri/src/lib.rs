@@ -1,13 +1,30 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 use std::sync::Mutex;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager, State,
-};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
+mod active_window;
+mod app_rules;
+mod cli;
+mod clipboard_watch;
+mod dbus;
+mod native_messaging;
+mod notifications;
+mod pinned;
+mod placement;
+mod policy;
+mod profiles;
+mod replace;
+mod search_provider;
+mod secrets;
+mod settings;
+mod translate;
+mod tray;
+
+use notifications::OutputMode;
+use translate::TranslationSettings;
+
 // --- Types ---
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,6 +43,21 @@ pub struct ProxyResponse {
     pub error: Option<String>,
 }
 
+/// Partial output of the `translate_text` call with the same `id`.
+#[derive(Debug, Clone, Serialize)]
+struct TranslationDelta {
+    id: u64,
+    text: String,
+}
+
+/// Other candidate translations of the `translate_text` call with the same
+/// `id`.
+#[derive(Debug, Clone, Serialize)]
+struct TranslationAlternatives {
+    id: u64,
+    alternatives: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OcrResult {
     pub success: bool,
@@ -44,6 +76,21 @@ pub struct OcrDependencyStatus {
     pub gnome_screenshot_installed: bool,
 }
 
+/// Payload of `quick-translate-text`.
+#[derive(Debug, Clone, Serialize)]
+struct QuickTranslateText {
+    text: String,
+    #[serde(flatten)]
+    overrides: app_rules::RuleOverrides,
+}
+
+/// Payload of `show-translation`.
+#[derive(Debug, Clone, Serialize)]
+struct ShownTranslation {
+    text: String,
+    translation: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WindowDimensions {
     pub width: f64,
@@ -58,6 +105,8 @@ pub struct ProxySettings {
     pub port: u16,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Hosts reached directly, comma-separated (`NO_PROXY` syntax)
+    pub bypass: String,
 }
 
 // --- State ---
@@ -65,6 +114,16 @@ pub struct ProxySettings {
 struct AppState {
     current_shortcut: Mutex<String>,
     proxy_settings: Mutex<Option<ProxySettings>>,
+    translation_settings: Mutex<TranslationSettings>,
+    clipboard_watch: clipboard_watch::ClipboardWatcher,
+    replace: replace::Replacer,
+    app_rules: app_rules::AppRules,
+    tray: tray::TrayState,
+    outputs: notifications::Outputs,
+    pinned: pinned::PinnedWindows,
+    placement: placement::Placement,
+    settings: settings::SettingsStore,
+    profiles: profiles::Profiles,
 }
 
 impl Default for AppState {
@@ -72,38 +131,69 @@ impl Default for AppState {
         Self {
             current_shortcut: Mutex::new("CommandOrControl+Shift+X".to_string()),
             proxy_settings: Mutex::new(None),
+            translation_settings: Mutex::new(TranslationSettings::default()),
+            clipboard_watch: clipboard_watch::ClipboardWatcher::default(),
+            replace: replace::Replacer::default(),
+            app_rules: app_rules::AppRules::default(),
+            tray: tray::TrayState::default(),
+            outputs: notifications::Outputs::default(),
+            pinned: pinned::PinnedWindows::default(),
+            placement: placement::Placement::default(),
+            settings: settings::SettingsStore::default(),
+            profiles: profiles::Profiles::default(),
         }
     }
 }
 
-// --- Commands ---
+impl AppState {
+    /// HTTP client honouring the proxy from the settings.
+    fn http_client(&self) -> Result<reqwest::Client, String> {
+        self.http_client_builder()?
+            .build()
+            .map_err(|e| e.to_string())
+    }
 
-#[tauri::command]
-async fn proxy_request(
-    url: String,
-    options: Option<ProxyRequestOptions>,
-    state: State<'_, AppState>,
-) -> Result<ProxyResponse, String> {
-    let client = {
-        let proxy_settings = state.proxy_settings.lock().unwrap();
+    /// [`AppState::http_client`] that doesn't follow redirects, for plugins,
+    /// which must not be led past their allowed hosts.
+    fn plugin_client(&self) -> Result<reqwest::Client, String> {
+        self.http_client_builder()?
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| e.to_string())
+    }
+
+    fn http_client_builder(&self) -> Result<reqwest::ClientBuilder, String> {
+        let proxy_settings = self.proxy_settings.lock().unwrap();
         if let Some(ref settings) = *proxy_settings {
             if settings.enabled {
                 let proxy_url = format!(
                     "{}://{}:{}",
                     settings.protocol, settings.host, settings.port
                 );
-                let proxy = reqwest::Proxy::all(&proxy_url).map_err(|e| e.to_string())?;
-                reqwest::Client::builder()
-                    .proxy(proxy)
-                    .build()
+                // Local daemons (Ollama, ...) and the configured hosts are
+                // reached directly
+                let no_proxy = format!("localhost,127.0.0.0/8,::1,{}", settings.bypass);
+                let proxy = reqwest::Proxy::all(&proxy_url)
                     .map_err(|e| e.to_string())?
-            } else {
-                reqwest::Client::new()
+                    .no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+                return Ok(reqwest::Client::builder()
+                    .redirect(policy::redirects())
+                    .proxy(proxy));
             }
-        } else {
-            reqwest::Client::new()
         }
-    };
+        Ok(reqwest::Client::builder().redirect(policy::redirects()))
+    }
+}
+
+// --- Commands ---
+
+#[tauri::command]
+async fn proxy_request(
+    url: String,
+    options: Option<ProxyRequestOptions>,
+    state: State<'_, AppState>,
+) -> Result<ProxyResponse, String> {
+    let client = state.http_client()?;
 
     let opts = options.unwrap_or(ProxyRequestOptions {
         method: None,
@@ -131,17 +221,11 @@ async fn proxy_request(
         request = request.body(body);
     }
 
-    match request.send().await {
+    match policy::send(request).await {
         Ok(response) => {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-
-            // Try to parse as JSON, otherwise return as string
-            let data = if body.starts_with('{') || body.starts_with('[') {
-                body
-            } else {
-                body
-            };
+            // JSON bodies are returned as strings too, the frontend parses them
+            let data = response.text().await.unwrap_or_default();
 
             Ok(ProxyResponse {
                 ok: status.is_success(),
@@ -169,7 +253,10 @@ async fn capture_screen() -> Result<Option<String>, String> {
     let output = Command::new("gnome-screenshot")
         .args(["-a", "-f", &temp_path])
         .output()
-        .map_err(|e| format!("Failed to run gnome-screenshot: {}", e))?;
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "gnome-screenshot is not installed".to_string(),
+            _ => format!("Failed to run gnome-screenshot: {}", e),
+        })?;
 
     if !output.status.success() {
         // User might have cancelled
@@ -213,7 +300,10 @@ async fn ocr_image(base64_image: String) -> Result<OcrResult, String> {
     let output = Command::new("tesseract")
         .args([&temp_path, "stdout", "-l", "chi_sim+chi_tra+eng+jpn+kor"])
         .output()
-        .map_err(|e| format!("Failed to run tesseract: {}", e))?;
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "tesseract is not installed".to_string(),
+            _ => format!("Failed to run tesseract: {}", e),
+        })?;
 
     // Clean up
     let _ = std::fs::remove_file(&temp_path);
@@ -237,11 +327,34 @@ async fn ocr_image(base64_image: String) -> Result<OcrResult, String> {
         Ok(OcrResult {
             success: false,
             text: None,
-            error: Some(error),
+            error: Some(describe_tesseract_error(&error)),
         })
     }
 }
 
+/// Turns tesseract's stderr into a short reason, e.g. "tesseract language
+/// pack jpn missing" for `Failed loading language 'jpn'`.
+fn describe_tesseract_error(stderr: &str) -> String {
+    let missing: Vec<&str> = stderr
+        .lines()
+        .filter_map(|line| line.split("Failed loading language '").nth(1))
+        .filter_map(|rest| rest.split('\'').next())
+        .collect();
+
+    if missing.is_empty() {
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            "tesseract failed".to_string()
+        } else {
+            stderr.to_string()
+        }
+    } else {
+        let packs = missing.join(", ");
+        let noun = if missing.len() == 1 { "pack" } else { "packs" };
+        format!("tesseract language {} {} missing", noun, packs)
+    }
+}
+
 #[tauri::command]
 async fn check_ocr_dependencies() -> Result<OcrDependencyStatus, String> {
     // Check tesseract
@@ -311,102 +424,335 @@ async fn show_ocr_install_prompt(app: AppHandle, message: String) -> Result<bool
 }
 
 #[tauri::command]
-async fn update_shortcut(
+async fn resize_quick_window(app: AppHandle, dimensions: WindowDimensions) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("quick") {
+        let size = tauri::LogicalSize::new(dimensions.width, dimensions.height);
+        window.set_size(size).map_err(|e| e.to_string())?;
+        placement::keep_on_screen(&window);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn resize_main_window(app: AppHandle, dimensions: WindowDimensions) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        let size = tauri::LogicalSize::new(dimensions.width, dimensions.height);
+        placement::resize_main_window(&window, size)?;
+    }
+    Ok(())
+}
+
+/// Sent by the main window once it listens for the backend's events.
+#[tauri::command]
+async fn main_window_ready(app: AppHandle) -> Result<(), String> {
+    cli::main_window_ready(&app);
+    Ok(())
+}
+
+#[tauri::command]
+async fn quick_window_ready(app: AppHandle) -> Result<(), String> {
+    // Get clipboard text
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    if let Ok(text) = app.clipboard().read_text() {
+        if !text.is_empty() {
+            // Emit to quick window
+            let payload = QuickTranslateText {
+                text,
+                overrides: app_rules::RuleOverrides::default(),
+            };
+            app.emit_to("quick", "quick-translate-text", payload)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Translates for the frontend with the current settings, emitting
+/// `translation-delta` events with partial output to the calling window as
+/// streaming providers produce it, and `translation-alternatives` with other
+/// candidates from providers that offer them. Returns the whole translation.
+#[tauri::command]
+async fn translate_text(
     app: AppHandle,
-    shortcut: String,
+    window: tauri::WebviewWindow,
+    request_id: u64,
+    text: String,
+    source: String,
+    target: String,
+    overrides: Option<app_rules::RuleOverrides>,
+) -> Result<String, String> {
+    let overrides = overrides.unwrap_or_default();
+    let on_delta = |delta: &str| {
+        let _ = window.emit_to(
+            window.label(),
+            "translation-delta",
+            TranslationDelta {
+                id: request_id,
+                text: delta.to_string(),
+            },
+        );
+    };
+    let on_alternatives = |alternatives: &[String]| {
+        let _ = window.emit_to(
+            window.label(),
+            "translation-alternatives",
+            TranslationAlternatives {
+                id: request_id,
+                alternatives: alternatives.to_vec(),
+            },
+        );
+    };
+    translate_streaming(
+        &app,
+        &text,
+        &source,
+        &target,
+        &overrides,
+        &on_delta,
+        &on_alternatives,
+    )
+    .await
+}
+
+/// Models `provider` offers for the current settings.
+#[tauri::command]
+async fn list_provider_models(
+    provider: String,
     state: State<'_, AppState>,
-) -> Result<bool, String> {
-    // Unregister old shortcut
-    {
-        let old_shortcut = state.current_shortcut.lock().unwrap();
-        if let Ok(old_sc) = old_shortcut.parse::<Shortcut>() {
-            let _ = app.global_shortcut().unregister(old_sc);
-        }
+) -> Result<Vec<translate::Model>, String> {
+    let client = state.http_client()?;
+    let settings = state.translation_settings.lock().unwrap().clone();
+    translate::list_models(&client, &provider, &settings).await
+}
+
+/// Custom providers found in the `providers` config directory.
+#[derive(Debug, Clone, Serialize)]
+struct CustomProviders {
+    dir: String,
+    providers: Vec<translate::CustomProvider>,
+    /// Definitions that were skipped, with the reason
+    errors: Vec<String>,
+}
+
+/// (Re)loads the custom provider definitions of the config directory.
+fn load_custom_providers(app: &AppHandle) -> Result<CustomProviders, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?
+        .join("providers");
+    let (providers, errors) =
+        translate::load_custom_providers(&dir, |reference| secrets::resolve(app, reference));
+    for error in &errors {
+        log::error!("Ignoring custom provider {}", error);
     }
+    Ok(CustomProviders {
+        dir: dir.to_string_lossy().into_owned(),
+        providers,
+        errors,
+    })
+}
 
-    // Parse and register new shortcut
-    let new_shortcut: Shortcut = shortcut.parse().map_err(|e| format!("{:?}", e))?;
+/// Reloads and lists the custom providers.
+#[tauri::command]
+async fn list_custom_providers(app: AppHandle) -> Result<CustomProviders, String> {
+    let providers = load_custom_providers(&app)?;
+    tray::refresh(&app);
+    Ok(providers)
+}
 
-    app.global_shortcut()
-        .on_shortcut(new_shortcut.clone(), move |app, _shortcut, event| {
-            if event.state == ShortcutState::Pressed {
-                trigger_quick_translate(app);
-            }
-        })
-        .map_err(|e| e.to_string())?;
+/// Enabled plugins, by ID
+const PLUGINS_FILE: &str = "plugins.json";
+
+/// Plugins found in the `plugins` config directory.
+#[derive(Debug, Clone, Serialize)]
+struct Plugins {
+    dir: String,
+    plugins: Vec<translate::PluginInfo>,
+}
+
+fn plugins_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("plugins"))
+        .map_err(|e| e.to_string())
+}
 
-    // Update state
-    {
-        let mut current = state.current_shortcut.lock().unwrap();
-        *current = shortcut;
+/// IDs of the enabled plugins, kept in `plugins.json`.
+fn enabled_plugins(app: &AppHandle) -> Result<Vec<String>, String> {
+    let path = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?
+        .join(PLUGINS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+        Err(_) => Ok(Vec::new()),
     }
+}
 
-    Ok(true)
+fn save_enabled_plugins(app: &AppHandle, enabled: &[String]) -> Result<(), String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(PLUGINS_FILE);
+    let contents = serde_json::to_string_pretty(enabled).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
 }
 
+/// (Re)loads the plugins of the config directory.
+fn load_plugins(app: &AppHandle) -> Result<Plugins, String> {
+    let dir = plugins_dir(app)?;
+    let enabled = enabled_plugins(app)?;
+    let plugins = translate::load_plugins(&dir, &enabled);
+    Ok(Plugins {
+        dir: dir.to_string_lossy().into_owned(),
+        plugins,
+    })
+}
+
+/// Lists the plugins, with the errors of their last calls.
 #[tauri::command]
-async fn set_proxy(settings: ProxySettings, state: State<'_, AppState>) -> Result<(), String> {
-    let mut proxy = state.proxy_settings.lock().unwrap();
-    *proxy = Some(settings);
-    Ok(())
+async fn list_plugins(app: AppHandle) -> Result<Plugins, String> {
+    Ok(Plugins {
+        dir: plugins_dir(&app)?.to_string_lossy().into_owned(),
+        plugins: translate::list_plugins(),
+    })
 }
 
+/// Reloads and lists the plugins.
 #[tauri::command]
-async fn set_auto_launch(app: AppHandle, enabled: bool) -> Result<(), String> {
-    use tauri_plugin_autostart::ManagerExt;
+async fn reload_plugins(app: AppHandle) -> Result<Plugins, String> {
+    // Compiling components takes a while
+    let plugins = tauri::async_runtime::spawn_blocking({
+        let app = app.clone();
+        move || load_plugins(&app)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    tray::refresh(&app);
+    Ok(plugins)
+}
 
-    let autostart = app.autolaunch();
+/// Enables or disables a plugin. Disabling the selected translator switches
+/// back to the default provider.
+#[tauri::command]
+async fn set_plugin_enabled(app: AppHandle, id: String, enabled: bool) -> Result<Plugins, String> {
+    let mut ids = enabled_plugins(&app)?;
+    ids.retain(|other| *other != id);
     if enabled {
-        autostart.enable().map_err(|e| e.to_string())?;
-    } else {
-        autostart.disable().map_err(|e| e.to_string())?;
+        ids.push(id.clone());
     }
-    Ok(())
+    save_enabled_plugins(&app, &ids)?;
+    let plugins = reload_plugins(app.clone()).await?;
+
+    let provider = app
+        .state::<AppState>()
+        .translation_settings
+        .lock()
+        .unwrap()
+        .provider
+        .clone();
+    if !enabled && provider == id {
+        set_provider(&app, &TranslationSettings::default().provider);
+    }
+    Ok(plugins)
 }
 
+/// Runs a translation made by the frontend itself through the enabled
+/// post-processor plugins.
 #[tauri::command]
-async fn get_auto_launch(app: AppHandle) -> Result<bool, String> {
-    use tauri_plugin_autostart::ManagerExt;
+async fn postprocess_translation(
+    app: AppHandle,
+    text: String,
+    source: String,
+    target: String,
+    provider: String,
+) -> Result<String, String> {
+    postprocess(&app, text, &source, &target, &provider).await
+}
 
-    let autostart = app.autolaunch();
-    autostart.is_enabled().map_err(|e| e.to_string())
+/// Languages `provider` supports, for dynamic language lists.
+#[tauri::command]
+async fn list_provider_languages(
+    provider: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<translate::Language>, String> {
+    let client = state.http_client()?;
+    let settings = state.translation_settings.lock().unwrap().clone();
+    translate::list_languages(&client, &provider, &settings).await
 }
 
+/// Guesses the language of `text` with the current provider.
 #[tauri::command]
-async fn resize_quick_window(app: AppHandle, dimensions: WindowDimensions) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("quick") {
-        let size = tauri::LogicalSize::new(dimensions.width, dimensions.height);
-        window.set_size(size).map_err(|e| e.to_string())?;
-    }
-    Ok(())
+async fn detect_language(
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<translate::Detection, String> {
+    let client = state.http_client()?;
+    let settings = state.translation_settings.lock().unwrap().clone();
+    translate::detect_language(&client, &settings.provider, &text, &settings).await
 }
 
+/// Asks for a GGUF model file for the llama.cpp provider. Returns `None` if
+/// the dialog was cancelled.
 #[tauri::command]
-async fn resize_main_window(app: AppHandle, dimensions: WindowDimensions) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let size = tauri::LogicalSize::new(dimensions.width, dimensions.height);
-        window.set_size(size).map_err(|e| e.to_string())?;
-    }
-    Ok(())
+async fn pick_gguf_model(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Choose GGUF Model")
+        .add_filter("GGUF models", &["gguf"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    Ok(Some(path.to_string_lossy().into_owned()))
 }
 
+/// Asks for the service account key file of Google Cloud Translation.
+/// Returns `None` if the dialog was cancelled.
 #[tauri::command]
-async fn quick_window_ready(app: AppHandle) -> Result<(), String> {
-    // Get clipboard text
-    use tauri_plugin_clipboard_manager::ClipboardExt;
+async fn pick_service_account_key(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
 
-    if let Ok(text) = app.clipboard().read_text() {
-        if !text.is_empty() {
-            // Emit to quick window
-            app.emit_to("quick", "quick-translate-text", text)
-                .map_err(|e| e.to_string())?;
-        }
-    }
-    Ok(())
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Choose Service Account Key")
+        .add_filter("JSON key files", &["json"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// Pulls an Ollama model, emitting `ollama-pull-progress` events to all
+/// windows until it is downloaded.
+#[tauri::command]
+async fn pull_ollama_model(app: AppHandle, model: String) -> Result<(), String> {
+    let (client, settings) = {
+        let state = app.state::<AppState>();
+        let settings = state.translation_settings.lock().unwrap().clone();
+        (state.http_client()?, settings)
+    };
+    let on_progress = |progress: translate::PullProgress| {
+        let _ = app.emit("ollama-pull-progress", progress);
+    };
+    translate::pull_ollama_model(&client, &settings, model.trim(), &on_progress).await
 }
 
 #[tauri::command]
 async fn close_quick_window(app: AppHandle) -> Result<(), String> {
+    replace::discard_pending(&app);
     if let Some(window) = app.get_webview_window("quick") {
         window.hide().map_err(|e| e.to_string())?;
     }
@@ -415,13 +761,220 @@ async fn close_quick_window(app: AppHandle) -> Result<(), String> {
 
 // --- Helper Functions ---
 
-fn should_start_hidden() -> bool {
-    std::env::args().any(|arg| arg == "--hidden" || arg == "--autostart")
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn open_settings(app: &AppHandle) {
+    show_main_window(app);
+    let _ = app.emit_to("main", "open-settings", ());
+}
+
+/// Runs [`ocr_capture`] off the calling thread (tray and CLI handlers).
+fn spawn_ocr_capture(app: &AppHandle) {
+    let app_clone = app.clone();
+    std::thread::spawn(move || {
+        // Failures are reported through the tray
+        let _ = tauri::async_runtime::block_on(ocr_capture(&app_clone));
+    });
+}
+
+/// Translates `text` with the current settings from the settings store. Empty
+/// `source`/`target` fall back to the quick window languages.
+async fn translate_with_settings(
+    app: &AppHandle,
+    text: &str,
+    source: &str,
+    target: &str,
+) -> Result<String, String> {
+    let overrides = app_rules::RuleOverrides::default();
+    translate_with_overrides(app, text, source, target, &overrides).await
+}
+
+/// [`translate_with_settings`] with an application rule applied on top.
+async fn translate_with_overrides(
+    app: &AppHandle,
+    text: &str,
+    source: &str,
+    target: &str,
+    overrides: &app_rules::RuleOverrides,
+) -> Result<String, String> {
+    translate_streaming(app, text, source, target, overrides, &|_| {}, &|_| {}).await
+}
+
+/// [`translate_with_overrides`], passing partial output to `on_delta` and
+/// other candidates to `on_alternatives`.
+async fn translate_streaming(
+    app: &AppHandle,
+    text: &str,
+    source: &str,
+    target: &str,
+    overrides: &app_rules::RuleOverrides,
+    on_delta: &(dyn Fn(&str) + Send + Sync),
+    on_alternatives: &(dyn Fn(&[String]) + Send + Sync),
+) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let mut settings = state.translation_settings.lock().unwrap().clone();
+    overrides.apply(&mut settings);
+    policy::current().check_provider(&settings.provider)?;
+    let client = if translate::is_plugin(&settings.provider) {
+        state.plugin_client()?
+    } else {
+        state.http_client()?
+    };
+
+    let source = if source.is_empty() {
+        &settings.quick_source_lang
+    } else {
+        source
+    };
+    let target = if target.is_empty() {
+        &settings.quick_target_lang
+    } else {
+        target
+    };
+
+    let translation = translate::translate_stream(
+        &client,
+        text,
+        source,
+        target,
+        &settings,
+        on_delta,
+        on_alternatives,
+    )
+    .await?;
+    postprocess(app, translation, source, target, &settings.provider).await
+}
+
+/// Runs a translation through the enabled post-processor plugins.
+async fn postprocess(
+    app: &AppHandle,
+    translation: String,
+    source: &str,
+    target: &str,
+    provider: &str,
+) -> Result<String, String> {
+    if translation.is_empty() || !translate::has_postprocessors() {
+        return Ok(translation);
+    }
+    let client = app.state::<AppState>().plugin_client()?;
+    Ok(translate::postprocess(&client, translation, source, target, provider).await)
+}
+
+/// Sets the source/target languages of both the main panel and the quick
+/// window.
+fn set_quick_languages(app: &AppHandle, source: &str, target: &str) -> Result<(), String> {
+    if target.is_empty() || target == "auto" {
+        return Err("Target language must be a concrete language code".to_string());
+    }
+    let source = if source.is_empty() { "auto" } else { source };
+
+    settings::update(app, None, |settings| {
+        settings.translation.quick_source_lang = source.to_string();
+        settings.translation.quick_target_lang = target.to_string();
+        Ok(())
+    })
+    .map(|_| ())
+}
+
+/// Switches the provider of both windows and backend translations.
+fn set_provider(app: &AppHandle, provider: &str) {
+    let result = settings::update(app, None, |settings| {
+        settings.translation.provider = provider.to_string();
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::error!("Failed to switch provider: {}", e);
+    }
+}
+
+/// Shows the main window and translates `text` there.
+fn open_text_in_main_window(app: &AppHandle, text: &str) {
+    show_main_window(app);
+    // The main window treats `ocr-result` as "translate this text"
+    let _ = app.emit_to("main", "ocr-result", text.to_string());
+}
+
+/// Shows a finished translation in the main window without translating again.
+fn show_translation_in_main_window(app: &AppHandle, text: &str, translation: &str) {
+    show_main_window(app);
+    let payload = ShownTranslation {
+        text: text.to_string(),
+        translation: translation.to_string(),
+    };
+    let _ = app.emit_to("main", "show-translation", payload);
+}
+
+fn copy_to_clipboard(app: &AppHandle, text: &str) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    app.state::<AppState>().clipboard_watch.note_own_text(text);
+    app.clipboard()
+        .write_text(text.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Lets the user pick a screen region, runs OCR on it and hands the text to
+/// the main window, or translates it into a notification. Returns `None` if
+/// the capture was cancelled. Progress and failures are shown on the tray
+/// icon.
+async fn ocr_capture(app: &AppHandle) -> Result<Option<String>, String> {
+    let busy = tray::busy(app, "Select a region to recognize");
+    let result = capture_and_recognize(&busy).await;
+    drop(busy);
+
+    match result {
+        Ok(Some(text)) => {
+            match app.state::<AppState>().outputs.settings().ocr {
+                OutputMode::Window => open_text_in_main_window(app, &text),
+                OutputMode::Notification => notifications::translate_to_notification(
+                    app,
+                    text.clone(),
+                    app_rules::RuleOverrides::default(),
+                    "OCR translation failed",
+                ),
+            }
+            Ok(Some(text))
+        }
+        Ok(None) => Ok(None),
+        Err(e) => {
+            tray::report_error(app, "OCR failed", &e);
+            Err(e)
+        }
+    }
+}
+
+async fn capture_and_recognize(busy: &tray::BusyGuard) -> Result<Option<String>, String> {
+    let Some(image_data) = capture_screen().await? else {
+        return Ok(None);
+    };
+
+    busy.step("Recognizing text");
+    let ocr_result = ocr_image(image_data).await?;
+    if !ocr_result.success {
+        return Err(ocr_result.error.unwrap_or_else(|| "OCR failed".to_string()));
+    }
+
+    let text = ocr_result.text.unwrap_or_default();
+    if text.trim().is_empty() {
+        return Err("No text recognized in the selected region".to_string());
+    }
+    Ok(Some(text))
 }
 
 fn trigger_quick_translate(app: &AppHandle) {
     use tauri_plugin_clipboard_manager::ClipboardExt;
 
+    // Evaluated while the source application still has focus
+    let overrides = match app_rules::for_focused_window(app) {
+        app_rules::Capture::Denied => return,
+        capture => capture.overrides(),
+    };
+
     // Get clipboard content first using xdotool to simulate Ctrl+C
     let _ = Command::new("xdotool")
         .args(["key", "--clearmodifiers", "ctrl+c"])
@@ -432,26 +985,33 @@ fn trigger_quick_translate(app: &AppHandle) {
 
     // Read the clipboard text
     let clipboard_text = app.clipboard().read_text().unwrap_or_default();
+    // Already shown, the clipboard watch must not pop it up again
+    app.state::<AppState>()
+        .clipboard_watch
+        .note_own_text(&clipboard_text);
+
+    match app.state::<AppState>().outputs.settings().quick_translate {
+        OutputMode::Window => show_quick_window_with(app, clipboard_text, overrides),
+        OutputMode::Notification if clipboard_text.trim().is_empty() => {}
+        OutputMode::Notification => notifications::translate_to_notification(
+            app,
+            clipboard_text,
+            overrides,
+            "Quick translation failed",
+        ),
+    }
+}
 
-    // Show quick window at cursor position
-    if let Some(window) = app.get_webview_window("quick") {
-        // Get cursor position using xdotool
-        if let Ok(output) = Command::new("xdotool").arg("getmouselocation").output() {
-            let location = String::from_utf8_lossy(&output.stdout);
-            // Parse "x:123 y:456 screen:0 window:123456"
-            let mut x: i32 = 100;
-            let mut y: i32 = 100;
-
-            for part in location.split_whitespace() {
-                if let Some(val) = part.strip_prefix("x:") {
-                    x = val.parse().unwrap_or(100);
-                } else if let Some(val) = part.strip_prefix("y:") {
-                    y = val.parse().unwrap_or(100);
-                }
-            }
+/// Shows the quick window where the placement policy puts it and translates
+/// `text` there.
+fn show_quick_window(app: &AppHandle, text: String) {
+    show_quick_window_with(app, text, app_rules::RuleOverrides::default());
+}
 
-            let _ = window.set_position(tauri::LogicalPosition::new(x, y));
-        }
+/// [`show_quick_window`] translating with an application rule's overrides.
+fn show_quick_window_with(app: &AppHandle, text: String, overrides: app_rules::RuleOverrides) {
+    if let Some(window) = app.get_webview_window("quick") {
+        placement::place_quick_window(app, &window);
 
         let _ = window.show();
         let _ = window.set_focus();
@@ -466,93 +1026,40 @@ fn trigger_quick_translate(app: &AppHandle) {
                 .output();
         });
 
-        // Emit clipboard text to the quick window after a small delay for window to be ready
-        if !clipboard_text.is_empty() {
+        // Emit the text to the quick window after a small delay for window to be ready
+        if !text.is_empty() {
             let app_clone = app.clone();
             std::thread::spawn(move || {
                 std::thread::sleep(std::time::Duration::from_millis(100));
-                let _ = app_clone.emit_to("quick", "quick-translate-text", clipboard_text);
+                let _ = app_clone.emit_to(
+                    "quick",
+                    "quick-translate-text",
+                    QuickTranslateText { text, overrides },
+                );
             });
         }
     }
 }
 
-fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let show_item = MenuItem::with_id(app, "show", "Show LightTranslator", true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-    let ocr_item = MenuItem::with_id(app, "ocr", "OCR Screenshot", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-    let menu = Menu::with_items(app, &[&show_item, &settings_item, &ocr_item, &quit_item])?;
-
-    // Use the same icon as dock (512x512) - let system handle scaling
-    let tray_icon = {
-        let icon_bytes = include_bytes!("../icons/icon.png");
-        let img = image::load_from_memory(icon_bytes)
-            .expect("Failed to load tray icon")
-            .into_rgba8();
-        let (width, height) = img.dimensions();
-        tauri::image::Image::new_owned(img.into_raw(), width, height)
-    };
-
-    let _tray = TrayIconBuilder::new()
-        .icon(tray_icon)
-        .menu(&menu)
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            "settings" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                    let _ = app.emit_to("main", "open-settings", ());
-                }
-            }
-            "ocr" => {
-                // Trigger OCR capture
-                let app_clone = app.clone();
-                std::thread::spawn(move || {
-                    if let Ok(Some(image_data)) = tauri::async_runtime::block_on(capture_screen()) {
-                        if let Ok(ocr_result) = tauri::async_runtime::block_on(ocr_image(image_data)) {
-                            if ocr_result.success {
-                                if let Some(text) = ocr_result.text {
-                                    // Show main window and emit OCR result
-                                    if let Some(window) = app_clone.get_webview_window("main") {
-                                        let _ = window.show();
-                                        let _ = window.set_focus();
-                                    }
-                                    let _ = app_clone.emit_to("main", "ocr-result", text);
-                                }
-                            }
-                        }
-                    }
-                });
-            }
-            "quit" => {
-                std::process::exit(0);
-            }
-            _ => {}
-        })
-        .build(app)?;
-
-    Ok(())
-}
+/// Registers `shortcut` for quick translate, replacing the previous one.
+fn register_selection_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let new_shortcut: Shortcut = shortcut.parse().map_err(|e| format!("{:?}", e))?;
 
-fn setup_global_shortcut(app: &AppHandle, state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
-    let shortcut_str = state.current_shortcut.lock().unwrap().clone();
-    let shortcut: Shortcut = shortcut_str.parse()?;
+    let state = app.state::<AppState>();
+    let mut current = state.current_shortcut.lock().unwrap();
+    if let Ok(old_shortcut) = current.parse::<Shortcut>() {
+        let _ = app.global_shortcut().unregister(old_shortcut);
+    }
 
     app.global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, event| {
-            if event.state == ShortcutState::Pressed {
+        .on_shortcut(new_shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed && !app.state::<AppState>().tray.is_paused() {
                 trigger_quick_translate(app);
             }
-        })?;
+        })
+        .map_err(|e| e.to_string())?;
 
+    *current = shortcut.to_string();
     Ok(())
 }
 
@@ -560,10 +1067,19 @@ fn setup_global_shortcut(app: &AppHandle, state: &AppState) -> Result<(), Box<dy
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Browser-launched native messaging host and its installer never start the GUI
+    if native_messaging::handle_cli() {
+        return;
+    }
+
     let state = AppState::default();
 
     tauri::Builder::default()
         .manage(state)
+        // Must come first: a second launch hands its arguments over and exits
+        .plugin(tauri_plugin_single_instance::init(
+            cli::handle_second_instance,
+        ))
         .plugin(tauri_plugin_log::Builder::default().build())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_clipboard_manager::init())
@@ -582,28 +1098,83 @@ pub fn run() {
             check_ocr_dependencies,
             install_ocr_dependencies,
             show_ocr_install_prompt,
-            update_shortcut,
-            set_proxy,
-            set_auto_launch,
-            get_auto_launch,
+            settings::get_settings,
+            settings::update_settings,
+            settings::import_frontend_settings,
+            policy::get_policy,
+            profiles::list_profiles,
+            profiles::save_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            profiles::export_profiles,
+            profiles::import_profiles,
+            replace::confirm_replace,
+            replace::cancel_replace,
+            app_rules::get_app_rules,
+            app_rules::set_app_rules,
+            tray::add_recent_translation,
+            pinned::pin_quick_window,
+            pinned::get_pinned_content,
+            pinned::close_pinned_window,
+            placement::restore_main_window_size,
+            native_messaging::install_native_messaging_host,
+            native_messaging::uninstall_native_messaging_host,
+            translate_text,
+            list_provider_models,
+            pull_ollama_model,
+            pick_gguf_model,
+            pick_service_account_key,
+            list_provider_languages,
+            list_custom_providers,
+            list_plugins,
+            reload_plugins,
+            set_plugin_enabled,
+            postprocess_translation,
+            detect_language,
             resize_quick_window,
             resize_main_window,
+            main_window_ready,
             quick_window_ready,
             close_quick_window,
         ])
         .setup(|app| {
-            let start_hidden = should_start_hidden();
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let action = cli::parse_args(&args, &cwd).unwrap_or_else(|e| {
+                log::error!("Invalid arguments: {}", e);
+                cli::CliAction::Show
+            });
+            // Switching profiles or providers is a background action as well
+            let start_hidden = matches!(
+                action,
+                cli::CliAction::Hidden | cli::CliAction::Profile(_) | cli::CliAction::Provider(_)
+            );
+
+            // First, the tray and shortcuts are set up from the settings
+            policy::load(app.handle());
+            // Before the settings, which may select one of them
+            if let Err(e) = load_custom_providers(app.handle()) {
+                log::error!("Failed to load custom providers: {}", e);
+            }
+            if let Err(e) = load_plugins(app.handle()) {
+                log::error!("Failed to load plugins: {}", e);
+            }
+            settings::load(app.handle());
+            profiles::load(app.handle());
+            pinned::load(app.handle());
+            placement::load(app.handle());
+            placement::restore_main_window(app.handle());
 
             // Setup tray
-            if let Err(e) = setup_tray(app.handle()) {
+            if let Err(e) = tray::setup(app.handle()) {
                 log::error!("Failed to setup tray: {}", e);
             }
 
-            // Setup global shortcut
-            let state = app.state::<AppState>();
-            if let Err(e) = setup_global_shortcut(app.handle(), &state) {
-                log::error!("Failed to setup global shortcut: {}", e);
-            }
+            // Export the D-Bus service for desktop integration
+            dbus::spawn(app.handle().clone());
+
+            // Idles until clipboard watch mode is switched on
+            clipboard_watch::spawn(app.handle().clone());
 
             // Hide quick window on startup (it starts hidden anyway)
             if let Some(quick) = app.get_webview_window("quick") {
@@ -619,6 +1190,12 @@ pub fn run() {
                 }
             }
 
+            // Perform the action requested on the command line, once the
+            // main webview reports its event listeners are registered
+            if !matches!(action, cli::CliAction::Show | cli::CliAction::Hidden) {
+                cli::perform_at_startup(app.handle(), action);
+            }
+
             // Open devtools in dev mode for debugging
             #[cfg(debug_assertions)]
             if !start_hidden {
@@ -637,12 +1214,15 @@ pub fn run() {
                     api.prevent_close();
                 }
             }
-            // Hide quick window on blur
+            // Hide quick window on blur, pinned copies stay
             if window.label() == "quick" {
                 if let tauri::WindowEvent::Focused(false) = event {
+                    replace::discard_pending(window.app_handle());
                     let _ = window.hide();
                 }
             }
+            pinned::handle_window_event(window, event);
+            placement::handle_window_event(window, event);
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,386 @@
+//! WebExtension native messaging host.
+//!
+//! Browsers start the binary with the caller's origin as argument and talk to
+//! it over stdin/stdout using length-prefixed JSON. The host relays each
+//! request to the running app over D-Bus, so the companion extension uses the
+//! app's configured provider, keys and proxy instead of shipping its own.
+//!
+//! Requests look like `{"id": 1, "type": "translate", "text": "...",
+//! "source": "auto", "target": "en"}`; every response echoes `id` and
+//! carries `ok` plus either the result or an `error`.
+
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::dbus::{LightTranslatorProxy, BUS_NAME};
+
+/// Name the extensions use in `runtime.connectNative()`.
+pub const HOST_NAME: &str = "io.github.lighttranslator";
+/// Gecko ID of the companion Firefox extension.
+const FIREFOX_EXTENSION_ID: &str = "lighttranslator@wsh95.github.io";
+/// ID of the companion Chromium extension (pinned through its manifest key).
+const CHROMIUM_EXTENSION_ID: &str = "kbfhmpoajbgmplbdnhgiofamhfmjnpcb";
+
+/// Browsers refuse host messages larger than 1 MB.
+const MAX_OUTGOING_MESSAGE: usize = 1024 * 1024;
+/// Requests are small; anything larger is a corrupt or hostile length.
+const MAX_INCOMING_MESSAGE: usize = 1024 * 1024;
+/// How long to wait for the app to claim its bus name after starting it.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// --- Protocol ---
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Request {
+    Ping,
+    Translate {
+        text: String,
+        #[serde(default)]
+        source: String,
+        #[serde(default)]
+        target: String,
+    },
+    ShowQuickWindow {
+        text: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    id: Value,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Response {
+    fn from_result(id: Value, result: Result<Value, String>) -> Self {
+        match result {
+            Ok(value) => Self {
+                id,
+                ok: true,
+                result: Some(value),
+                error: None,
+            },
+            Err(error) => Self {
+                id,
+                ok: false,
+                result: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+fn read_message(input: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut length = [0u8; 4];
+    match input.read_exact(&mut length) {
+        Ok(()) => {}
+        // The browser closed the port
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let length = u32::from_ne_bytes(length) as usize;
+    if length > MAX_INCOMING_MESSAGE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Message of {} bytes exceeds the size limit", length),
+        ));
+    }
+
+    let mut message = vec![0u8; length];
+    input.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+fn write_message(output: &mut impl Write, response: &Response) -> io::Result<()> {
+    let mut message = serde_json::to_vec(response)?;
+    if message.len() > MAX_OUTGOING_MESSAGE {
+        message = serde_json::to_vec(&Response::from_result(
+            response.id.clone(),
+            Err("Response exceeds the native messaging size limit".to_string()),
+        ))?;
+    }
+
+    output.write_all(&(message.len() as u32).to_ne_bytes())?;
+    output.write_all(&message)?;
+    output.flush()
+}
+
+// --- Host ---
+
+/// Connects to the running app, starting it in the background if needed.
+async fn connect() -> Result<LightTranslatorProxy<'static>, String> {
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| format!("Session bus unavailable: {}", e))?;
+    let bus = zbus::fdo::DBusProxy::new(&connection)
+        .await
+        .map_err(|e| e.to_string())?;
+    let bus_name = zbus::names::BusName::try_from(BUS_NAME).map_err(|e| e.to_string())?;
+
+    if !bus.name_has_owner(bus_name.clone()).await.unwrap_or(false) {
+        std::process::Command::new(host_executable()?)
+            .arg("--hidden")
+            .spawn()
+            .map_err(|e| format!("Failed to start LightTranslator: {}", e))?;
+
+        let deadline = std::time::Instant::now() + STARTUP_TIMEOUT;
+        while !bus.name_has_owner(bus_name.clone()).await.unwrap_or(false) {
+            if std::time::Instant::now() > deadline {
+                return Err("LightTranslator did not start in time".to_string());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    LightTranslatorProxy::new(&connection)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn connected<'a>(
+    proxy: &'a mut Option<LightTranslatorProxy<'static>>,
+) -> Result<&'a LightTranslatorProxy<'static>, String> {
+    if proxy.is_none() {
+        *proxy = Some(connect().await?);
+    }
+    Ok(proxy.as_ref().expect("connected above"))
+}
+
+async fn handle(
+    proxy: &mut Option<LightTranslatorProxy<'static>>,
+    request: Request,
+) -> Result<Value, String> {
+    match request {
+        Request::Ping => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+        Request::Translate {
+            text,
+            source,
+            target,
+        } => connected(proxy)
+            .await?
+            .translate(&text, &source, &target)
+            .await
+            .map(|translation| json!({ "translation": translation }))
+            .map_err(|e| e.to_string()),
+        // Reuses the shortcut's window placement in the running app
+        Request::ShowQuickWindow { text } => connected(proxy)
+            .await?
+            .show_quick_translate(&text)
+            .await
+            .map(|_| Value::Null)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Serves messages until the browser closes the port.
+fn run_host() {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    tauri::async_runtime::block_on(async {
+        let mut proxy = None;
+        loop {
+            let message = match read_message(&mut stdin) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(e) => {
+                    log::error!("Failed to read native message: {}", e);
+                    break;
+                }
+            };
+
+            let response = match serde_json::from_slice::<Envelope>(&message) {
+                Ok(envelope) => {
+                    let result = handle(&mut proxy, envelope.request).await;
+                    if result.is_err() {
+                        // Reconnect next time, the app may have restarted
+                        proxy = None;
+                    }
+                    Response::from_result(envelope.id, result)
+                }
+                Err(e) => {
+                    Response::from_result(Value::Null, Err(format!("Invalid message: {}", e)))
+                }
+            };
+
+            if write_message(&mut stdout, &response).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+// --- Manifests ---
+
+/// Path the browsers should launch. Inside an AppImage `current_exe` points
+/// into a temporary mount, so prefer the image itself.
+fn host_executable() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| e.to_string())
+}
+
+/// `(manifest path, manifest)` for every supported browser.
+fn manifests() -> Result<Vec<(PathBuf, Value)>, String> {
+    let home = dirs::home_dir().ok_or("Could not determine the home directory")?;
+    let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+    let path = host_executable()?;
+    let file_name = format!("{}.json", HOST_NAME);
+    let description = "LightTranslator native messaging host";
+
+    let firefox = json!({
+        "name": HOST_NAME,
+        "description": description,
+        "path": path,
+        "type": "stdio",
+        "allowed_extensions": [FIREFOX_EXTENSION_ID],
+    });
+    let chromium = json!({
+        "name": HOST_NAME,
+        "description": description,
+        "path": path,
+        "type": "stdio",
+        "allowed_origins": [format!("chrome-extension://{}/", CHROMIUM_EXTENSION_ID)],
+    });
+
+    Ok(vec![
+        (
+            home.join(".mozilla/native-messaging-hosts")
+                .join(&file_name),
+            firefox,
+        ),
+        (
+            config
+                .join("chromium/NativeMessagingHosts")
+                .join(&file_name),
+            chromium.clone(),
+        ),
+        (
+            config
+                .join("google-chrome/NativeMessagingHosts")
+                .join(&file_name),
+            chromium,
+        ),
+    ])
+}
+
+fn install() -> Result<Vec<String>, String> {
+    let mut written = Vec::new();
+    for (path, manifest) in manifests()? {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let contents = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written.push(path.display().to_string());
+    }
+    Ok(written)
+}
+
+fn uninstall() -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for (path, _) in manifests()? {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            removed.push(path.display().to_string());
+        }
+    }
+    Ok(removed)
+}
+
+// --- Entry Points ---
+
+/// Firefox passes the manifest path and extension ID, Chromium passes the
+/// caller's origin.
+fn is_host_invocation(args: &[String]) -> bool {
+    args.iter().any(|arg| {
+        arg.starts_with("chrome-extension://")
+            || arg == FIREFOX_EXTENSION_ID
+            || arg.ends_with(&format!("{}.json", HOST_NAME))
+    })
+}
+
+/// Handles the native messaging related command line modes. Returns `true`
+/// if the process should exit instead of starting the GUI.
+pub fn handle_cli() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if is_host_invocation(&args) {
+        run_host();
+        return true;
+    }
+
+    let result = if args.iter().any(|arg| arg == "--install-native-host") {
+        install().map(|paths| ("Installed", paths))
+    } else if args.iter().any(|arg| arg == "--uninstall-native-host") {
+        uninstall().map(|paths| ("Removed", paths))
+    } else {
+        return false;
+    };
+
+    match result {
+        Ok((verb, paths)) => {
+            for path in paths {
+                println!("{} {}", verb, path);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    true
+}
+
+#[tauri::command]
+pub async fn install_native_messaging_host() -> Result<Vec<String>, String> {
+    install()
+}
+
+#[tauri::command]
+pub async fn uninstall_native_messaging_host() -> Result<Vec<String>, String> {
+    uninstall()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_message() {
+        let mut input = 2u32.to_ne_bytes().to_vec();
+        input.extend_from_slice(b"{}");
+        let message = read_message(&mut input.as_slice()).unwrap();
+        assert_eq!(message.as_deref(), Some(&b"{}"[..]));
+        assert!(read_message(&mut &[][..]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_oversized_length() {
+        let input = u32::MAX.to_ne_bytes();
+        let error = read_message(&mut &input[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
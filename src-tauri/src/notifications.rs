@@ -0,0 +1,200 @@
+//! Desktop notifications through the freedesktop notification service.
+//!
+//! Besides errors, notifications are an alternative result surface to the
+//! quick window for the shortcut, OCR and clipboard watch: the translation is
+//! shown without taking focus, with actions to copy it, open it in the main
+//! window or read it out loud.
+
+use std::process::Command;
+use std::sync::Mutex;
+
+use notify_rust::Notification;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::app_rules::RuleOverrides;
+use crate::AppState;
+
+const APP_NAME: &str = "LightTranslator";
+const ICON: &str = "lighttranslator";
+/// Longer translations are cut off, with an action to open them in full.
+const MAX_BODY_CHARS: usize = 400;
+
+/// Where the result of an action is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    #[default]
+    Window,
+    Notification,
+}
+
+/// Output mode per action. The clipboard watch keeps its own setting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OutputSettings {
+    pub quick_translate: OutputMode,
+    pub ocr: OutputMode,
+}
+
+#[derive(Default)]
+pub struct Outputs {
+    settings: Mutex<OutputSettings>,
+}
+
+impl Outputs {
+    pub fn settings(&self) -> OutputSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, settings: OutputSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+}
+
+/// Translates `text` in the background and shows the result as a
+/// notification. Progress and failures are shown on the tray icon.
+pub fn translate_to_notification(
+    app: &AppHandle,
+    text: String,
+    overrides: RuleOverrides,
+    operation: &'static str,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let target = overrides.target_lang.clone().unwrap_or_else(|| {
+            app.state::<AppState>()
+                .translation_settings
+                .lock()
+                .unwrap()
+                .quick_target_lang
+                .clone()
+        });
+
+        let busy = crate::tray::busy(&app, "Translating");
+        let result = match crate::translate_with_overrides(&app, &text, "", "", &overrides).await {
+            Ok(translation) => {
+                crate::tray::record_translation(&app, &text, &translation);
+                show_translation(&app, &text, &translation, &target).await
+            }
+            Err(e) => Err(e),
+        };
+        drop(busy);
+        if let Err(e) = result {
+            crate::tray::report_error(&app, operation, &e);
+        }
+    });
+}
+
+/// Shows `translation` with the start of `original` as the summary. The
+/// actions are handled on a separate thread for as long as the notification
+/// stays open.
+pub async fn show_translation(
+    app: &AppHandle,
+    original: &str,
+    translation: &str,
+    target_lang: &str,
+) -> Result<(), String> {
+    let (body, truncated) = truncate(translation);
+    let open_label = if truncated {
+        "Show Full Text"
+    } else {
+        "Open in Window"
+    };
+
+    let handle = Notification::new()
+        .appname(APP_NAME)
+        .icon(ICON)
+        .summary(&summary(original))
+        .body(&body)
+        // Clicking the notification; servers that show it as a button need
+        // a label apart from the "open" button's
+        .action("default", "Open LightTranslator")
+        .action("copy", "Copy")
+        .action("open", open_label)
+        .action("speak", "Speak")
+        .show_async()
+        .await
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+    let app = app.clone();
+    let original = original.to_string();
+    let translation = translation.to_string();
+    let target_lang = target_lang.to_string();
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| {
+            let result = match action {
+                "copy" => crate::copy_to_clipboard(&app, &translation),
+                "default" | "open" => {
+                    crate::show_translation_in_main_window(&app, &original, &translation);
+                    Ok(())
+                }
+                "speak" => speak(&translation, &target_lang),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                crate::tray::report_error(&app, "Notification action failed", &e);
+            }
+        });
+    });
+    Ok(())
+}
+
+pub async fn show_error(operation: &str, reason: &str) -> Result<(), String> {
+    Notification::new()
+        .appname(APP_NAME)
+        .icon("dialog-error")
+        .summary(operation)
+        .body(reason)
+        .show_async()
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to show notification: {}", e))
+}
+
+/// First line of `text`, shortened to fit a notification title.
+fn summary(text: &str) -> String {
+    const MAX_CHARS: usize = 60;
+
+    let line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let line = line.trim();
+    if line.chars().count() > MAX_CHARS {
+        let short: String = line.chars().take(MAX_CHARS - 1).collect();
+        format!("{}…", short.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+/// `text` shortened to [`MAX_BODY_CHARS`], and whether it was cut off.
+fn truncate(text: &str) -> (String, bool) {
+    let text = text.trim();
+    if text.chars().count() <= MAX_BODY_CHARS {
+        return (text.to_string(), false);
+    }
+    let short: String = text.chars().take(MAX_BODY_CHARS - 1).collect();
+    (format!("{}…", short.trim_end()), true)
+}
+
+/// Reads `text` out loud with speech-dispatcher, or eSpeak if it is missing.
+fn speak(text: &str, lang: &str) -> Result<(), String> {
+    // Both take plain language codes ("zh", not "zh-CN")
+    let lang = lang.split(['-', '_']).next().unwrap_or(lang);
+
+    let candidates: [(&str, &[&str]); 3] = [
+        ("spd-say", &["-l", lang]),
+        ("espeak-ng", &["-v", lang]),
+        ("espeak", &["-v", lang]),
+    ];
+    for (program, args) in candidates {
+        match Command::new(program).args(args).arg(text).status() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => return Err(format!("{} exited with {}", program, status)),
+            Err(_) => continue,
+        }
+    }
+    Err("No speech synthesizer available. Install speech-dispatcher or espeak-ng.".to_string())
+}
@@ -0,0 +1,269 @@
+//! Pinned translations: detached copies of the quick window's result that
+//! stay on screen instead of hiding on blur.
+//!
+//! Pinning hands the current translation to a new `quick-pinned-N` window
+//! and hides the quick window, which is free for the next translation. Each
+//! slot `N` remembers where its window was last placed (`pinned-windows.json`
+//! in the app config directory), so pins reopen where the user left them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::AppState;
+
+const LABEL_PREFIX: &str = "quick-pinned-";
+const GEOMETRY_FILE: &str = "pinned-windows.json";
+/// Offset of a new pin from the quick window when its slot has no position.
+const CASCADE_OFFSET: f64 = 24.0;
+
+/// What a pinned window shows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedContent {
+    pub text: String,
+    pub translation: String,
+    pub provider: String,
+    pub target_lang: String,
+}
+
+/// Logical position and size of a pinned window.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Geometry {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[derive(Default)]
+pub struct PinnedWindows {
+    /// Open pins by slot
+    open: Mutex<BTreeMap<u32, PinnedContent>>,
+    /// Last geometry per slot, persisted
+    geometry: Mutex<HashMap<u32, Geometry>>,
+}
+
+impl PinnedWindows {
+    /// `(label, translation)` of the open pins, oldest first.
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.open
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(slot, content)| (label(*slot), content.translation.clone()))
+            .collect()
+    }
+}
+
+fn label(slot: u32) -> String {
+    format!("{}{}", LABEL_PREFIX, slot)
+}
+
+fn slot(label: &str) -> Option<u32> {
+    label.strip_prefix(LABEL_PREFIX)?.parse().ok()
+}
+
+// --- Window Events ---
+
+/// Tracks geometry changes and closing of pinned windows.
+pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    let Some(slot) = slot(window.label()) else {
+        return;
+    };
+    let app = window.app_handle();
+    let state = app.state::<AppState>();
+
+    match event {
+        WindowEvent::Moved(position) => {
+            let scale = window.scale_factor().unwrap_or(1.0);
+            let position = position.to_logical::<f64>(scale);
+            let mut geometry = state.pinned.geometry.lock().unwrap();
+            let entry = geometry.entry(slot).or_default();
+            entry.x = position.x;
+            entry.y = position.y;
+        }
+        WindowEvent::Resized(size) => {
+            let scale = window.scale_factor().unwrap_or(1.0);
+            let size = size.to_logical::<f64>(scale);
+            let mut geometry = state.pinned.geometry.lock().unwrap();
+            let entry = geometry.entry(slot).or_default();
+            entry.width = size.width;
+            entry.height = size.height;
+        }
+        WindowEvent::Destroyed => {
+            state.pinned.open.lock().unwrap().remove(&slot);
+            save(app);
+            crate::tray::refresh(app);
+        }
+        _ => {}
+    }
+}
+
+// --- Persistence ---
+
+fn geometry_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(GEOMETRY_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// Loads the remembered pin positions, if any.
+pub fn load(app: &AppHandle) {
+    let Ok(path) = geometry_path(app) else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<HashMap<u32, Geometry>>(&contents) {
+        Ok(geometry) => *app.state::<AppState>().pinned.geometry.lock().unwrap() = geometry,
+        Err(e) => log::error!("Ignoring invalid {}: {}", path.display(), e),
+    }
+}
+
+/// Writes the pin positions. Errors are logged, positions are a convenience.
+pub fn save(app: &AppHandle) {
+    let result = geometry_path(app).and_then(|path| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let geometry = app
+            .state::<AppState>()
+            .pinned
+            .geometry
+            .lock()
+            .unwrap()
+            .clone();
+        let contents = serde_json::to_string_pretty(&geometry).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    });
+    if let Err(e) = result {
+        log::error!("Failed to save pinned window positions: {}", e);
+    }
+}
+
+// --- Pinning ---
+
+/// Geometry for a new pin in `slot`: where that slot was last, or cascaded
+/// from the quick window.
+fn initial_geometry(app: &AppHandle, slot: u32) -> Geometry {
+    let remembered = app
+        .state::<AppState>()
+        .pinned
+        .geometry
+        .lock()
+        .unwrap()
+        .get(&slot)
+        .copied();
+    if let Some(geometry) = remembered.filter(|g| g.width > 0.0 && g.height > 0.0) {
+        return geometry;
+    }
+
+    let mut geometry = Geometry {
+        x: 100.0,
+        y: 100.0,
+        width: 400.0,
+        height: 200.0,
+    };
+    if let Some(quick) = app.get_webview_window("quick") {
+        let scale = quick.scale_factor().unwrap_or(1.0);
+        if let Ok(position) = quick.outer_position() {
+            let position = position.to_logical::<f64>(scale);
+            geometry.x = position.x + CASCADE_OFFSET * (slot - 1) as f64;
+            geometry.y = position.y + CASCADE_OFFSET * (slot - 1) as f64;
+        }
+        if let Ok(size) = quick.inner_size() {
+            let size = size.to_logical::<f64>(scale);
+            geometry.width = size.width;
+            geometry.height = size.height;
+        }
+    }
+    geometry
+}
+
+fn open_window(app: &AppHandle, content: PinnedContent) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let slot = {
+        let mut open = state.pinned.open.lock().unwrap();
+        let slot = (1..).find(|slot| !open.contains_key(slot)).unwrap();
+        open.insert(slot, content);
+        slot
+    };
+    let label = label(slot);
+    let geometry = initial_geometry(app, slot);
+
+    let result = WebviewWindowBuilder::new(
+        app,
+        &label,
+        WebviewUrl::App("index.html?mode=pinned".into()),
+    )
+    .title("Pinned Translation")
+    .position(geometry.x, geometry.y)
+    .inner_size(geometry.width, geometry.height)
+    .min_inner_size(200.0, 80.0)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .build();
+
+    if let Err(e) = result {
+        state.pinned.open.lock().unwrap().remove(&slot);
+        return Err(format!("Failed to open pinned window: {}", e));
+    }
+    crate::tray::refresh(app);
+    Ok(label)
+}
+
+/// Closes the pinned window `label`, or all of them with `None`.
+pub fn close(app: &AppHandle, label: Option<&str>) {
+    for (pinned, _) in app.state::<AppState>().pinned.list() {
+        if label.map_or(true, |label| label == pinned) {
+            if let Some(window) = app.get_webview_window(&pinned) {
+                let _ = window.close();
+            }
+        }
+    }
+}
+
+// --- Commands ---
+
+/// Moves the quick window's translation into a new pinned window.
+#[tauri::command]
+pub async fn pin_quick_window(app: AppHandle, content: PinnedContent) -> Result<String, String> {
+    let label = open_window(&app, content)?;
+    // A pinned copy can't confirm a replacement
+    crate::replace::discard_pending(&app);
+    if let Some(quick) = app.get_webview_window("quick") {
+        let _ = quick.hide();
+    }
+    Ok(label)
+}
+
+/// Content for the calling pinned window.
+#[tauri::command]
+pub async fn get_pinned_content(window: WebviewWindow) -> Result<PinnedContent, String> {
+    let slot = slot(window.label()).ok_or("Not a pinned window")?;
+    let app = window.app_handle();
+    let content = app
+        .state::<AppState>()
+        .pinned
+        .open
+        .lock()
+        .unwrap()
+        .get(&slot)
+        .cloned();
+    content.ok_or_else(|| "Pinned translation not found".to_string())
+}
+
+#[tauri::command]
+pub async fn close_pinned_window(window: WebviewWindow) -> Result<(), String> {
+    window.close().map_err(|e| e.to_string())
+}
@@ -0,0 +1,381 @@
+//! Window geometry: the main window's position and size are remembered per
+//! monitor configuration, and the quick window is placed by the user's
+//! policy while staying fully on the cursor's monitor.
+//!
+//! Positions are handled in physical pixels of the global desktop, sizes are
+//! converted with the scale factor of the monitor a window lands on.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent,
+};
+
+use crate::AppState;
+
+const GEOMETRY_FILE: &str = "window-geometry.json";
+/// Logical distance kept from the screen edges in the corner placements.
+const EDGE_MARGIN: f64 = 16.0;
+/// Logical distance between the cursor and the quick window when it is
+/// placed near the selection, enough to keep the selected line visible.
+const SELECTION_GAP: f64 = 24.0;
+
+/// Where the quick window opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuickPlacement {
+    /// Top-left corner at the cursor
+    #[default]
+    Cursor,
+    /// Below the line the selection ended on (where the mouse was
+    /// released), or above it when there is no room
+    Selection,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// Physical position and size of a window.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Geometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Default)]
+pub struct Placement {
+    quick: Mutex<QuickPlacement>,
+    /// Main window geometry by monitor configuration, persisted
+    main: Mutex<HashMap<String, Geometry>>,
+    /// The user's main window size while the settings dialog widens it
+    main_size_before_resize: Mutex<Option<PhysicalSize<u32>>>,
+}
+
+impl Placement {
+    pub fn set_quick(&self, placement: QuickPlacement) {
+        *self.quick.lock().unwrap() = placement;
+    }
+}
+
+// --- Monitors ---
+
+/// Identifies the connected monitors and their arrangement, so a geometry
+/// saved on a docked laptop is not applied to its built-in screen alone.
+fn monitor_config_key(monitors: &[Monitor]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            format!(
+                "{}@{},{}:{}x{}*{}",
+                monitor.name().map(String::as_str).unwrap_or("?"),
+                position.x,
+                position.y,
+                size.width,
+                size.height,
+                monitor.scale_factor()
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+fn contains(monitor: &Monitor, x: i32, y: i32) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    x >= position.x
+        && y >= position.y
+        && x < position.x + size.width as i32
+        && y < position.y + size.height as i32
+}
+
+/// Cursor position in physical desktop coordinates.
+fn cursor_position(app: &AppHandle) -> Option<PhysicalPosition<i32>> {
+    // "x:123 y:456 screen:0 window:123456"
+    if let Ok(output) = Command::new("xdotool").arg("getmouselocation").output() {
+        let location = String::from_utf8_lossy(&output.stdout);
+        let mut x = None;
+        let mut y = None;
+        for part in location.split_whitespace() {
+            if let Some(val) = part.strip_prefix("x:") {
+                x = val.parse().ok();
+            } else if let Some(val) = part.strip_prefix("y:") {
+                y = val.parse().ok();
+            }
+        }
+        if let (Some(x), Some(y)) = (x, y) {
+            return Some(PhysicalPosition::new(x, y));
+        }
+    }
+    app.cursor_position()
+        .ok()
+        .map(|position| PhysicalPosition::new(position.x as i32, position.y as i32))
+}
+
+/// Moves `position` so a window of `size` lies fully inside the monitor's
+/// work area (or at its top-left if the window is larger).
+fn clamp_to_work_area(
+    monitor: &Monitor,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> PhysicalPosition<i32> {
+    let area = monitor.work_area();
+    let right = area.position.x + area.size.width as i32 - size.width as i32;
+    let bottom = area.position.y + area.size.height as i32 - size.height as i32;
+    PhysicalPosition::new(
+        position.x.min(right).max(area.position.x),
+        position.y.min(bottom).max(area.position.y),
+    )
+}
+
+/// Size of `window` once it is on `monitor`, whose scale may differ from
+/// the one the window is on now.
+fn size_on(window: &WebviewWindow, monitor: &Monitor) -> PhysicalSize<u32> {
+    let current_scale = window.scale_factor().unwrap_or(1.0);
+    window
+        .outer_size()
+        .unwrap_or_default()
+        .to_logical::<f64>(current_scale)
+        .to_physical(monitor.scale_factor())
+}
+
+// --- Quick Window ---
+
+/// Positions the quick window by the placement policy, on the monitor under
+/// the cursor.
+pub fn place_quick_window(app: &AppHandle, window: &WebviewWindow) {
+    let policy = *app.state::<AppState>().placement.quick.lock().unwrap();
+    let monitors = app.available_monitors().unwrap_or_default();
+    let cursor = cursor_position(app);
+
+    let monitor = cursor
+        .and_then(|cursor| monitors.iter().find(|m| contains(m, cursor.x, cursor.y)))
+        .cloned()
+        .or_else(|| app.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else {
+        if let Some(cursor) = cursor {
+            let _ = window.set_position(cursor);
+        }
+        return;
+    };
+
+    let scale = monitor.scale_factor();
+    let size = size_on(window, &monitor);
+    let (width, height) = (size.width as i32, size.height as i32);
+    let area = monitor.work_area();
+    let (left, top) = (area.position.x, area.position.y);
+    let (right, bottom) = (left + area.size.width as i32, top + area.size.height as i32);
+    let margin = (EDGE_MARGIN * scale) as i32;
+    let cursor = cursor.unwrap_or(PhysicalPosition::new(left, top));
+
+    let (x, y) = match policy {
+        QuickPlacement::Cursor => (cursor.x, cursor.y),
+        QuickPlacement::Selection => {
+            let gap = (SELECTION_GAP * scale) as i32;
+            let below = cursor.y + gap;
+            let y = if below + height <= bottom {
+                below
+            } else {
+                cursor.y - gap - height
+            };
+            (cursor.x - width / 2, y)
+        }
+        QuickPlacement::TopLeft => (left + margin, top + margin),
+        QuickPlacement::TopRight => (right - margin - width, top + margin),
+        QuickPlacement::BottomLeft => (left + margin, bottom - margin - height),
+        QuickPlacement::BottomRight => (right - margin - width, bottom - margin - height),
+        QuickPlacement::Center => (
+            left + (right - left - width) / 2,
+            top + (bottom - top - height) / 2,
+        ),
+    };
+
+    let position = clamp_to_work_area(&monitor, PhysicalPosition::new(x, y), size);
+    let _ = window.set_position(position);
+}
+
+/// Pulls `window` back onto its monitor after it grew, e.g. when the quick
+/// window fits itself to a long translation.
+pub fn keep_on_screen(window: &WebviewWindow) {
+    let (Ok(Some(monitor)), Ok(position), Ok(size)) = (
+        window.current_monitor(),
+        window.outer_position(),
+        window.outer_size(),
+    ) else {
+        return;
+    };
+    let clamped = clamp_to_work_area(&monitor, position, size);
+    if clamped != position {
+        let _ = window.set_position(clamped);
+    }
+}
+
+// --- Main Window ---
+
+/// Records the main window's geometry as it is moved and resized, and saves
+/// it when the window is hidden or loses focus.
+pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    if window.label() != "main" {
+        return;
+    }
+    let app = window.app_handle();
+    let placement = &app.state::<AppState>().placement;
+
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            if window.is_maximized().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+                return;
+            }
+            let (Ok(position), Ok(size), Ok(monitors)) = (
+                window.outer_position(),
+                window.inner_size(),
+                window.available_monitors(),
+            ) else {
+                return;
+            };
+            // Keep the user's size, not the one of the settings dialog
+            let size = placement
+                .main_size_before_resize
+                .lock()
+                .unwrap()
+                .unwrap_or(size);
+            placement.main.lock().unwrap().insert(
+                monitor_config_key(&monitors),
+                Geometry {
+                    x: position.x,
+                    y: position.y,
+                    width: size.width,
+                    height: size.height,
+                },
+            );
+        }
+        WindowEvent::CloseRequested { .. } | WindowEvent::Focused(false) => save(app),
+        _ => {}
+    }
+}
+
+/// Applies the geometry saved for the current monitor configuration, as long
+/// as the window would still be on one of the monitors.
+pub fn restore_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let monitors = app.available_monitors().unwrap_or_default();
+    let geometry = app
+        .state::<AppState>()
+        .placement
+        .main
+        .lock()
+        .unwrap()
+        .get(&monitor_config_key(&monitors))
+        .copied();
+    let Some(geometry) = geometry else {
+        return;
+    };
+    let Some(monitor) = monitors
+        .iter()
+        .find(|monitor| contains(monitor, geometry.x, geometry.y))
+    else {
+        return;
+    };
+
+    let size = PhysicalSize::new(geometry.width, geometry.height);
+    let position = clamp_to_work_area(monitor, PhysicalPosition::new(geometry.x, geometry.y), size);
+    let _ = window.set_size(size);
+    let _ = window.set_position(position);
+}
+
+/// Temporarily resizes the main window, remembering the user's size for
+/// [`restore_main_window_size`].
+pub fn resize_main_window(
+    window: &WebviewWindow,
+    size: tauri::LogicalSize<f64>,
+) -> Result<(), String> {
+    let placement = &window.app_handle().state::<AppState>().placement;
+    {
+        let mut before = placement.main_size_before_resize.lock().unwrap();
+        if before.is_none() {
+            *before = window.inner_size().ok();
+        }
+    }
+    window.set_size(size).map_err(|e| e.to_string())?;
+    keep_on_screen(window);
+    Ok(())
+}
+
+// --- Persistence ---
+
+fn geometry_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(GEOMETRY_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// Loads the saved main window geometries, if any.
+pub fn load(app: &AppHandle) {
+    let Ok(path) = geometry_path(app) else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<HashMap<String, Geometry>>(&contents) {
+        Ok(geometry) => *app.state::<AppState>().placement.main.lock().unwrap() = geometry,
+        Err(e) => log::error!("Ignoring invalid {}: {}", path.display(), e),
+    }
+}
+
+/// Writes the main window geometries. Errors are logged.
+pub fn save(app: &AppHandle) {
+    let result = geometry_path(app).and_then(|path| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let geometry = app
+            .state::<AppState>()
+            .placement
+            .main
+            .lock()
+            .unwrap()
+            .clone();
+        let contents = serde_json::to_string_pretty(&geometry).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    });
+    if let Err(e) = result {
+        log::error!("Failed to save window geometry: {}", e);
+    }
+}
+
+// --- Commands ---
+
+/// Gives the main window back the size it had before [`resize_main_window`].
+#[tauri::command]
+pub async fn restore_main_window_size(app: AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let before = app
+        .state::<AppState>()
+        .placement
+        .main_size_before_resize
+        .lock()
+        .unwrap()
+        .take();
+    if let Some(size) = before {
+        window.set_size(size).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! System-wide policy for managed deployments.
+//!
+//! Administrators can install `/etc/lighttranslator/policy.toml` to lock
+//! settings to fixed values, restrict the providers and the hosts the app
+//! may reach, and turn off the translation history:
+//!
+//! ```toml
+//! allowed_providers = ["microsoft", "deepl"]
+//! blocked_hosts = ["translate.googleapis.com"]
+//! disable_history = true
+//!
+//! [locked]
+//! proxyEnabled = true
+//! proxyHost = "proxy.example.com"
+//! proxyPort = 3128
+//! ```
+//!
+//! Locked settings (by their camelCase names) are forced when the settings
+//! are loaded and changes to them are rejected; the UI shows them read-only.
+//!
+//! Host rules apply to every request the backend makes: providers, the
+//! frontend's `proxy_request` and plugins. Requests are sent through
+//! [`send`] and the HTTP clients follow only redirects that [`redirects`]
+//! lets through.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::settings::{self, Settings};
+
+const POLICY_PATH: &str = "/etc/lighttranslator/policy.toml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all(serialize = "camelCase"))]
+pub struct Policy {
+    /// Settings forced to these values
+    pub locked: Map<String, Value>,
+    /// Providers that may be selected or used by application rules
+    pub allowed_providers: Option<Vec<String>>,
+    /// Hosts (and their subdomains) the app may reach; any if unset
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Hosts (and their subdomains) the app never reaches
+    pub blocked_hosts: Vec<String>,
+    /// Forces incognito mode: no recent translations are kept
+    pub disable_history: bool,
+}
+
+impl Policy {
+    pub fn is_locked(&self, field: &str) -> bool {
+        self.locked.contains_key(field)
+    }
+
+    pub fn history_disabled(&self) -> bool {
+        self.disable_history
+    }
+
+    pub fn check_provider(&self, provider: &str) -> Result<(), String> {
+        match &self.allowed_providers {
+            Some(allowed) if !allowed.iter().any(|id| id == provider) => Err(format!(
+                "Provider '{}' is not allowed by the system policy",
+                provider
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects settings that differ from the locked values or use a provider
+    /// that is not allowed.
+    pub fn check_settings(&self, settings: &Settings) -> Result<(), String> {
+        self.check_provider(&settings.translation.provider)?;
+        for rule in &settings.app_rules {
+            if let Some(provider) = &rule.provider {
+                self.check_provider(provider)
+                    .map_err(|e| format!("{}: {}", rule.app, e))?;
+            }
+        }
+        if self.locked.is_empty() {
+            return Ok(());
+        }
+        let document = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+        for (field, value) in &self.locked {
+            if document.get(field) != Some(value) {
+                return Err(format!("{} is set by the system policy", field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Brings `settings` in line with the policy: locked values are set and a
+    /// provider that is not allowed is replaced by the first allowed one, or
+    /// dropped from application rules.
+    pub fn enforce(&self, settings: &mut Settings) {
+        if !self.locked.is_empty() {
+            if let Err(e) = settings::merge_patch(settings, self.locked.clone()) {
+                log::error!("Ignoring locked settings of {}: {}", POLICY_PATH, e);
+            }
+        }
+        if self.check_provider(&settings.translation.provider).is_err() {
+            if let Some(provider) = self.allowed_providers.as_ref().and_then(|a| a.first()) {
+                settings.translation.provider = provider.clone();
+            }
+        }
+        // Rules fall back to the default provider instead
+        for rule in &mut settings.app_rules {
+            if rule
+                .provider
+                .as_ref()
+                .is_some_and(|p| self.check_provider(p).is_err())
+            {
+                rule.provider = None;
+            }
+        }
+    }
+
+    /// Checks a URL the app is about to request.
+    pub fn check_url(&self, url: &str) -> Result<(), String> {
+        if self.allowed_hosts.is_none() && self.blocked_hosts.is_empty() {
+            return Ok(());
+        }
+        let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+        let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_ascii_lowercase();
+            host == pattern || host.ends_with(&format!(".{}", pattern))
+        };
+
+        let allowed = self
+            .allowed_hosts
+            .as_ref()
+            .map_or(true, |hosts| hosts.iter().any(matches));
+        if !allowed || self.blocked_hosts.iter().any(matches) {
+            return Err(format!("{} is blocked by the system policy", host));
+        }
+        Ok(())
+    }
+}
+
+/// The policy in effect, read once at startup. Process-wide so that the
+/// request helpers, which have no `AppHandle`, can enforce it.
+static POLICY: OnceLock<Policy> = OnceLock::new();
+
+pub fn current() -> &'static Policy {
+    POLICY.get_or_init(Policy::default)
+}
+
+/// Sends `request` unless the policy blocks its URL.
+pub async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    let (client, request) = request.build_split();
+    let request = request.map_err(|e| e.to_string())?;
+    current().check_url(request.url().as_str())?;
+    client.execute(request).await.map_err(|e| e.to_string())
+}
+
+/// Redirect policy of the HTTP clients: reqwest's default limit of 10
+/// redirects, and none to a host the policy blocks.
+pub fn redirects() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= 10 {
+            return attempt.error("too many redirects");
+        }
+        match current().check_url(attempt.url().as_str()) {
+            Ok(()) => attempt.follow(),
+            Err(e) => attempt.error(e),
+        }
+    })
+}
+
+/// Reads the policy file, if there is one. Called at startup before the
+/// settings are loaded.
+pub fn load(app: &AppHandle) {
+    let policy = match std::fs::read_to_string(POLICY_PATH) {
+        Ok(contents) => match toml::from_str::<Policy>(&contents) {
+            Ok(policy) => {
+                log::info!("Applying system policy from {}", POLICY_PATH);
+                policy
+            }
+            Err(e) => {
+                crate::tray::report_error(app, "Invalid system policy", &e.to_string());
+                Policy::default()
+            }
+        },
+        Err(_) => Policy::default(),
+    };
+    let disable_history = policy.disable_history;
+    let _ = POLICY.set(policy);
+    if disable_history {
+        crate::tray::set_incognito(app, true);
+    }
+}
+
+// --- Commands ---
+
+/// The policy in effect, so the settings dialog can show locked fields
+/// read-only and offer only the allowed providers.
+#[tauri::command]
+pub async fn get_policy() -> Result<Policy, String> {
+    Ok(current().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_rules::AppRule;
+
+    fn policy(toml: &str) -> Policy {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn allows_any_host_by_default() {
+        assert!(Policy::default().check_url("https://example.com/").is_ok());
+        assert!(Policy::default().check_url("not a url").is_ok());
+    }
+
+    #[test]
+    fn checks_hosts() {
+        let policy = policy(
+            r#"
+            allowed_hosts = ["example.com"]
+            blocked_hosts = ["blocked.example.com"]
+            "#,
+        );
+        assert!(policy.check_url("https://example.com/v2/translate").is_ok());
+        assert!(policy.check_url("https://API.Example.com/").is_ok());
+        assert!(policy.check_url("https://blocked.example.com/").is_err());
+        assert!(policy.check_url("https://a.blocked.example.com/").is_err());
+        assert!(policy.check_url("https://notexample.com/").is_err());
+        assert!(policy.check_url("https://example.com.evil.test/").is_err());
+        assert!(policy.check_url("not a url").is_err());
+    }
+
+    #[test]
+    fn blocks_hosts_without_allow_list() {
+        let policy = policy(r#"blocked_hosts = ["translate.googleapis.com"]"#);
+        assert!(policy
+            .check_url("https://translate.googleapis.com/translate_a/single")
+            .is_err());
+        assert!(policy
+            .check_url("https://api.deepl.com/v2/translate")
+            .is_ok());
+    }
+
+    #[test]
+    fn forces_locked_fields() {
+        let policy = policy(
+            r#"
+            [locked]
+            proxyEnabled = true
+            proxyPort = 3128
+            "#,
+        );
+        let mut settings = Settings::default();
+        assert!(policy.check_settings(&settings).is_err());
+
+        policy.enforce(&mut settings);
+        assert!(settings.proxy_enabled);
+        assert_eq!(settings.proxy_port, 3128);
+        assert!(policy.check_settings(&settings).is_ok());
+
+        settings.proxy_port = 8080;
+        assert_eq!(
+            policy.check_settings(&settings).unwrap_err(),
+            "proxyPort is set by the system policy"
+        );
+    }
+
+    #[test]
+    fn restricts_providers() {
+        let policy = policy(r#"allowed_providers = ["deepl", "microsoft"]"#);
+        let mut settings = Settings {
+            app_rules: vec![
+                AppRule {
+                    app: "kitty".to_string(),
+                    provider: Some("openai".to_string()),
+                    ..Default::default()
+                },
+                AppRule {
+                    app: "thunderbird".to_string(),
+                    provider: Some("microsoft".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(policy.check_settings(&settings).is_err());
+
+        settings.translation.provider = "deepl".to_string();
+        assert_eq!(
+            policy.check_settings(&settings).unwrap_err(),
+            "kitty: Provider 'openai' is not allowed by the system policy"
+        );
+
+        settings.translation.provider = "google".to_string();
+        policy.enforce(&mut settings);
+        assert_eq!(settings.translation.provider, "deepl");
+        assert_eq!(settings.app_rules[0].provider, None);
+        assert_eq!(settings.app_rules[1].provider.as_deref(), Some("microsoft"));
+        assert!(policy.check_settings(&settings).is_ok());
+    }
+}
@@ -0,0 +1,640 @@
+//! Named configuration profiles ("work", "personal", ...).
+//!
+//! A profile bundles the provider, model, proxy, shortcuts and language
+//! defaults, with API keys and the proxy password kept as secret references
+//! (see [`crate::secrets`]). Switching applies it through
+//! [`settings::update`], so the proxy and the shortcut registrations change
+//! together or not at all. Profiles are stored in `profiles.json`; exports
+//! leave the secret references out.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::settings;
+use crate::AppState;
+
+const PROFILES_FILE: &str = "profiles.json";
+const EXPORT_VERSION: u64 = 1;
+
+/// Settings a profile carries, by their camelCase names.
+const PROFILE_FIELDS: &[&str] = &[
+    "provider",
+    "modelId",
+    "customSystemInstruction",
+    "systemPromptEnabled",
+    "openaiBaseUrl",
+    "openaiModel",
+    "openrouterModel",
+    "anthropicBaseUrl",
+    "anthropicModel",
+    "azureOpenaiEndpoint",
+    "azureOpenaiRegion",
+    "azureOpenaiDeployment",
+    "azureOpenaiApiVersion",
+    "azureOpenaiAuth",
+    "azureOpenaiTenantId",
+    "azureOpenaiClientId",
+    "ollamaBaseUrl",
+    "ollamaModel",
+    "ollamaKeepAlive",
+    "ollamaNumCtx",
+    "ggufModelPath",
+    "ggufTemplate",
+    "ggufCustomTemplate",
+    "ggufThreads",
+    "ggufContextLength",
+    "formality",
+    "microsoftRegion",
+    "libretranslateBaseUrl",
+    "libretranslateFormat",
+    "libretranslateAlternatives",
+    "baiduAppId",
+    "youdaoAppKey",
+    "tencentSecretId",
+    "tencentRegion",
+    "awsAccessKeyId",
+    "awsProfile",
+    "awsRegion",
+    "awsTerminology",
+    "awsEndpoint",
+    "googleCloudCredentialsPath",
+    "googleCloudProjectId",
+    "googleCloudLocation",
+    "googleCloudGlossary",
+    "googleCloudEndpoint",
+    "offlineModelsDir",
+    "offlineThreads",
+    "offlineMemoryLimitMb",
+    "proxyEnabled",
+    "proxyProtocol",
+    "proxyHost",
+    "proxyPort",
+    "proxyUsername",
+    "proxyBypass",
+    "selectionShortcut",
+    "replaceShortcut",
+    "sourceLang",
+    "targetLang",
+    "quickSourceLang",
+    "quickTargetLang",
+    "writingLang",
+    "appRules",
+];
+
+/// Settings holding secrets, which profiles only reference.
+const SECRET_FIELDS: &[&str] = &[
+    "geminiApiKey",
+    "openaiApiKey",
+    "openrouterApiKey",
+    "anthropicApiKey",
+    "azureOpenaiApiKey",
+    "azureOpenaiClientSecret",
+    "deeplApiKey",
+    "microsoftSubscriptionKey",
+    "libretranslateApiKey",
+    "baiduSecretKey",
+    "youdaoAppSecret",
+    "tencentSecretKey",
+    "awsSecretAccessKey",
+    "proxyPassword",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    /// Values of [`PROFILE_FIELDS`]; missing ones are left as they are
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+    /// Secret references by setting name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub secrets: BTreeMap<String, String>,
+    /// Keep no translation history while the profile is active
+    #[serde(default)]
+    pub incognito: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfileList {
+    /// Profile switched to last
+    pub active: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+/// Export file, `{"version": 1, "profiles": [...]}`.
+#[derive(Serialize, Deserialize)]
+struct ProfileExport {
+    version: u64,
+    profiles: Vec<Profile>,
+}
+
+#[derive(Default)]
+pub struct Profiles {
+    list: Mutex<ProfileList>,
+}
+
+impl Profiles {
+    pub fn list(&self) -> ProfileList {
+        self.list.lock().unwrap().clone()
+    }
+}
+
+/// Name of the keyring or `secrets.json` entry for a profile's setting.
+fn secret_name(profile: &str, field: &str) -> String {
+    format!("profile/{}/{}", profile, field)
+}
+
+/// Keeps only the fields a profile may set.
+fn profile_settings(mut settings: Map<String, Value>) -> Map<String, Value> {
+    settings.retain(|key, _| PROFILE_FIELDS.contains(&key.as_str()));
+    settings
+}
+
+/// Captures the current settings as profile `name`. Secrets are moved into
+/// the secret store; references of `previous` that are no longer needed are
+/// deleted.
+fn capture(
+    app: &AppHandle,
+    name: &str,
+    incognito: bool,
+    previous: Option<&Profile>,
+) -> Result<Profile, String> {
+    let Value::Object(current) =
+        serde_json::to_value(settings::current(app)).map_err(|e| e.to_string())?
+    else {
+        unreachable!("settings serialize to an object");
+    };
+
+    let (profile, orphaned) = capture_from(current, name, incognito, previous, |name, value| {
+        crate::secrets::store(app, name, value)
+    })?;
+    for reference in &orphaned {
+        crate::secrets::delete(app, reference);
+    }
+    Ok(profile)
+}
+
+/// [`capture`] of the settings `current`, storing secrets with `store`.
+/// Also returns the references of `previous` that are no longer needed.
+fn capture_from(
+    current: Map<String, Value>,
+    name: &str,
+    incognito: bool,
+    previous: Option<&Profile>,
+    mut store: impl FnMut(&str, &str) -> Result<String, String>,
+) -> Result<(Profile, Vec<String>), String> {
+    let mut secrets = BTreeMap::new();
+    for field in SECRET_FIELDS {
+        let value = current.get(*field).and_then(Value::as_str).unwrap_or("");
+        if value.is_empty() {
+            continue;
+        }
+        let reference = match previous.and_then(|profile| profile.secrets.get(*field)) {
+            // Keys provisioned through the environment stay there
+            Some(reference) if reference.starts_with("env:") => reference.clone(),
+            _ => store(&secret_name(name, field), value)?,
+        };
+        secrets.insert(field.to_string(), reference);
+    }
+    let orphaned = previous
+        .map(|previous| {
+            previous
+                .secrets
+                .iter()
+                .filter(|(field, _)| !secrets.contains_key(*field))
+                .map(|(_, reference)| reference.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let profile = Profile {
+        name: name.to_string(),
+        settings: profile_settings(current),
+        secrets,
+        incognito,
+    };
+    Ok((profile, orphaned))
+}
+
+/// Applies profile `name`: its secrets are resolved first, so a missing key
+/// leaves everything unchanged.
+pub fn switch(app: &AppHandle, name: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let profile = state
+        .profiles
+        .list()
+        .profiles
+        .into_iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| format!("No profile named '{}'", name))?;
+
+    let mut patch = switch_patch(&profile, |reference| {
+        crate::secrets::resolve(app, reference)
+    })?;
+    // Settings locked by the system policy stay as they are
+    let policy = crate::policy::current();
+    patch.retain(|field, _| !policy.is_locked(field));
+
+    settings::update(app, None, |settings| settings::merge_patch(settings, patch))?;
+    crate::tray::set_incognito(app, profile.incognito);
+
+    state.profiles.list.lock().unwrap().active = Some(name.to_string());
+    changed(app);
+    log::info!("Switched to profile '{}'", name);
+    Ok(())
+}
+
+/// The settings patch applying `profile`, its secrets resolved with
+/// `resolve`.
+fn switch_patch(
+    profile: &Profile,
+    resolve: impl Fn(&str) -> Result<String, String>,
+) -> Result<Map<String, Value>, String> {
+    let mut patch = profile_settings(profile.settings.clone());
+    for (field, reference) in &profile.secrets {
+        if !SECRET_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        let secret =
+            resolve(reference).map_err(|e| format!("Profile '{}': {}", profile.name, e))?;
+        patch.insert(field.clone(), Value::String(secret));
+    }
+    Ok(patch)
+}
+
+/// Saves, refreshes the tray and tells the windows after a change.
+fn changed(app: &AppHandle) {
+    if let Err(e) = save(app) {
+        log::error!("Failed to save profiles: {}", e);
+    }
+    crate::tray::refresh(app);
+    let _ = app.emit("profiles-changed", app.state::<AppState>().profiles.list());
+}
+
+// --- Persistence ---
+
+fn profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(PROFILES_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// Loads `profiles.json`, if any.
+pub fn load(app: &AppHandle) {
+    let Ok(path) = profiles_path(app) else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<ProfileList>(&contents) {
+        Ok(list) => *app.state::<AppState>().profiles.list.lock().unwrap() = list,
+        Err(e) => log::error!("Ignoring invalid {}: {}", path.display(), e),
+    }
+}
+
+fn save(app: &AppHandle) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let list = app.state::<AppState>().profiles.list();
+    let contents = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn export_to(app: &AppHandle, path: &Path) -> Result<(), String> {
+    write_export(path, app.state::<AppState>().profiles.list().profiles)
+}
+
+fn write_export(path: &Path, profiles: Vec<Profile>) -> Result<(), String> {
+    let profiles = profiles
+        .into_iter()
+        .map(|profile| Profile {
+            secrets: BTreeMap::new(),
+            ..profile
+        })
+        .collect();
+    let export = ProfileExport {
+        version: EXPORT_VERSION,
+        profiles,
+    };
+    let contents = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Adds the profiles of an export file. Profiles with the same name are
+/// replaced but keep their secrets; secrets in the file are ignored.
+fn import_from(app: &AppHandle, path: &Path) -> Result<usize, String> {
+    let export = read_export(path)?;
+    let state = app.state::<AppState>();
+    let mut list = state.profiles.list.lock().unwrap();
+    Ok(merge_import(&mut list, export))
+}
+
+fn read_export(path: &Path) -> Result<ProfileExport, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let export: ProfileExport = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid profile file {}: {}", path.display(), e))?;
+    if export.version > EXPORT_VERSION {
+        return Err("The profiles were exported by a newer version".to_string());
+    }
+    Ok(export)
+}
+
+/// Adds the profiles of `export` to `list`, see [`import_from`]. Returns
+/// how many were added or replaced.
+fn merge_import(list: &mut ProfileList, export: ProfileExport) -> usize {
+    let mut count = 0;
+    for imported in export.profiles {
+        if imported.name.trim().is_empty() {
+            continue;
+        }
+        let secrets = list
+            .profiles
+            .iter()
+            .find(|profile| profile.name == imported.name)
+            .map(|profile| profile.secrets.clone())
+            .unwrap_or_default();
+        let profile = Profile {
+            settings: profile_settings(imported.settings),
+            secrets,
+            ..imported
+        };
+        match list.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => list.profiles.push(profile),
+        }
+        count += 1;
+    }
+    count
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<ProfileList, String> {
+    Ok(app.state::<AppState>().profiles.list())
+}
+
+/// Saves the current settings as profile `name`, replacing one with the same
+/// name, and makes it the active profile.
+#[tauri::command]
+pub async fn save_profile(app: AppHandle, name: String, incognito: bool) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name can't be empty".to_string());
+    }
+    let state = app.state::<AppState>();
+    let previous = state
+        .profiles
+        .list()
+        .profiles
+        .into_iter()
+        .find(|profile| profile.name == name);
+    let profile = capture(&app, name, incognito, previous.as_ref())?;
+
+    {
+        let mut list = state.profiles.list.lock().unwrap();
+        match list.profiles.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = profile,
+            None => list.profiles.push(profile),
+        }
+        list.active = Some(name.to_string());
+    }
+    changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let removed = {
+        let mut list = state.profiles.list.lock().unwrap();
+        let index = list
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+            .ok_or_else(|| format!("No profile named '{}'", name))?;
+        if list.active.as_deref() == Some(name.as_str()) {
+            list.active = None;
+        }
+        list.profiles.remove(index)
+    };
+    for reference in removed.secrets.values() {
+        crate::secrets::delete(&app, reference);
+    }
+    changed(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    switch(&app, &name)
+}
+
+/// Asks for a file and writes all profiles to it, without secrets. Returns
+/// `false` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_profiles(app: AppHandle) -> Result<bool, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Export Profiles")
+        .set_file_name("lighttranslator-profiles.json")
+        .add_filter("Profiles", &["json"])
+        .blocking_save_file()
+    else {
+        return Ok(false);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    export_to(&app, &path)?;
+    Ok(true)
+}
+
+/// Asks for an exported file and adds its profiles. Returns how many were
+/// imported.
+#[tauri::command]
+pub async fn import_profiles(app: AppHandle) -> Result<usize, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Import Profiles")
+        .add_filter("Profiles", &["json"])
+        .blocking_pick_file()
+    else {
+        return Ok(0);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    let count = import_from(&app, &path)?;
+    changed(&app);
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    fn profile(name: &str, secrets: &[(&str, &str)]) -> Profile {
+        Profile {
+            name: name.to_string(),
+            settings: object(json!({ "provider": "deepl" })),
+            secrets: secrets
+                .iter()
+                .map(|(field, reference)| (field.to_string(), reference.to_string()))
+                .collect(),
+            incognito: false,
+        }
+    }
+
+    #[test]
+    fn keeps_only_profile_fields() {
+        let settings = profile_settings(object(json!({
+            "provider": "openai",
+            "targetLang": "de",
+            "openaiApiKey": "sk-secret",
+            "proxyPassword": "hunter2",
+            "theme": "dark",
+            "unknown": 1
+        })));
+        assert_eq!(
+            settings,
+            object(json!({ "provider": "openai", "targetLang": "de" }))
+        );
+    }
+
+    #[test]
+    fn captures_secrets_as_references() {
+        let current = object(json!({
+            "provider": "openai",
+            "openaiApiKey": "sk-new",
+            "deeplApiKey": "deepl-key",
+            "anthropicApiKey": ""
+        }));
+        let previous = profile(
+            "work",
+            &[
+                ("openaiApiKey", "env:OPENAI_API_KEY"),
+                ("deeplApiKey", "keyring:profile/work/deeplApiKey"),
+                ("proxyPassword", "keyring:profile/work/proxyPassword"),
+            ],
+        );
+        let mut stored = Vec::new();
+
+        let (profile, orphaned) =
+            capture_from(current, "work", true, Some(&previous), |name, value| {
+                stored.push((name.to_string(), value.to_string()));
+                Ok(format!("keyring:{}", name))
+            })
+            .unwrap();
+
+        // The environment reference is kept, the changed key is not stored
+        assert_eq!(profile.secrets["openaiApiKey"], "env:OPENAI_API_KEY");
+        assert_eq!(
+            profile.secrets["deeplApiKey"],
+            "keyring:profile/work/deeplApiKey"
+        );
+        assert!(!profile.secrets.contains_key("anthropicApiKey"));
+        assert_eq!(
+            stored,
+            [(
+                "profile/work/deeplApiKey".to_string(),
+                "deepl-key".to_string()
+            )]
+        );
+        // The proxy password is gone from the settings, so is its secret
+        assert_eq!(orphaned, ["keyring:profile/work/proxyPassword"]);
+        assert_eq!(profile.settings, object(json!({ "provider": "openai" })));
+        assert!(profile.incognito);
+    }
+
+    #[test]
+    fn capture_fails_when_a_secret_cant_be_stored() {
+        let current = object(json!({ "deeplApiKey": "deepl-key" }));
+        let result = capture_from(current, "work", false, None, |_, _| {
+            Err("keyring locked".to_string())
+        });
+        assert_eq!(result.unwrap_err(), "keyring locked");
+    }
+
+    #[test]
+    fn resolves_secrets_when_switching() {
+        let mut profile = profile(
+            "work",
+            &[
+                ("deeplApiKey", "env:DEEPL_KEY"),
+                ("theme", "env:NOT_A_SECRET_FIELD"),
+            ],
+        );
+        profile.settings.insert("theme".to_string(), json!("dark"));
+
+        let patch = switch_patch(&profile, |reference| Ok(format!("<{}>", reference))).unwrap();
+        assert_eq!(
+            patch,
+            object(json!({ "provider": "deepl", "deeplApiKey": "<env:DEEPL_KEY>" }))
+        );
+
+        let error = switch_patch(&profile, |_| Err("not set".to_string())).unwrap_err();
+        assert_eq!(error, "Profile 'work': not set");
+    }
+
+    #[test]
+    fn exports_and_imports_without_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut exported = profile("work", &[("deeplApiKey", "local:profile/work/deeplApiKey")]);
+        exported.settings.insert("theme".to_string(), json!("dark"));
+        write_export(&path, vec![exported, profile("home", &[])]).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("secrets"));
+
+        // Secrets written into the file by hand are dropped as well
+        let mut export = read_export(&path).unwrap();
+        export.profiles[1]
+            .secrets
+            .insert("deeplApiKey".to_string(), "env:HOME_KEY".to_string());
+        let mut list = ProfileList {
+            active: None,
+            profiles: vec![profile("work", &[("deeplApiKey", "keyring:kept")])],
+        };
+
+        assert_eq!(merge_import(&mut list, export), 2);
+        let names: Vec<_> = list.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["work", "home"]);
+        // An existing profile keeps its own secrets
+        assert_eq!(list.profiles[0].secrets["deeplApiKey"], "keyring:kept");
+        assert!(!list.profiles[0].settings.contains_key("theme"));
+        assert!(list.profiles[1].secrets.is_empty());
+    }
+
+    #[test]
+    fn rejects_newer_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        std::fs::write(&path, r#"{"version": 2, "profiles": []}"#).unwrap();
+        assert!(read_export(&path).is_err());
+        std::fs::write(&path, "not json").unwrap();
+        assert!(read_export(&path).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! Replace-selection mode: a second shortcut that translates the selection
+//! into the "writing language" and pastes the result over it.
+//!
+//! The selection is copied and the translation pasted through simulated
+//! Ctrl+C/Ctrl+V (xdotool on X11, wtype or ydotool on Wayland). The user's
+//! clipboard (text, an image or copied files) is restored afterwards. With `preview` enabled the translation
+//! is shown in the quick window first and only pasted once confirmed.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::image::Image;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::app_rules::Capture;
+use crate::AppState;
+
+/// Time for the focused application to serve or read the clipboard.
+const CLIPBOARD_SETTLE: Duration = Duration::from_millis(150);
+/// Time for the target application to fetch the pasted text before the
+/// previous clipboard content is put back.
+const PASTE_SETTLE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplaceSettings {
+    /// Accelerator, empty to disable the mode
+    pub shortcut: String,
+    pub writing_lang: String,
+    /// Confirm the translation in the quick window before pasting
+    pub preview: bool,
+}
+
+impl Default for ReplaceSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: "CommandOrControl+Alt+X".to_string(),
+            writing_lang: "en".to_string(),
+            preview: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplacePreview {
+    text: String,
+    translation: String,
+}
+
+/// The clipboard content from before the selection was copied.
+enum SavedClipboard {
+    Text(String),
+    Image(Image<'static>),
+    Files(Vec<PathBuf>),
+    Empty,
+}
+
+/// A translation waiting for confirmation in the quick window.
+struct PendingReplace {
+    window: Option<u32>,
+    previous_clipboard: SavedClipboard,
+}
+
+#[derive(Default)]
+pub struct Replacer {
+    settings: Mutex<ReplaceSettings>,
+    /// Accelerator currently registered with the global shortcut plugin
+    registered: Mutex<Option<String>>,
+    pending: Mutex<Option<PendingReplace>>,
+}
+
+// --- Keystrokes ---
+
+#[derive(Debug, Clone, Copy)]
+enum Keystroke {
+    Copy,
+    Paste,
+}
+
+/// Sends Ctrl+C or Ctrl+V to the focused window with the first input tool
+/// that works in this session.
+fn send_keystroke(keystroke: Keystroke) -> Result<(), String> {
+    // Letter and its evdev keycode for ydotool (KEY_LEFTCTRL is 29)
+    let (key, code) = match keystroke {
+        Keystroke::Copy => ("c", 46),
+        Keystroke::Paste => ("v", 47),
+    };
+
+    let xdotool = vec![
+        "xdotool".to_string(),
+        "key".to_string(),
+        "--clearmodifiers".to_string(),
+        format!("ctrl+{}", key),
+    ];
+    let wtype = vec![
+        "wtype".to_string(),
+        "-M".to_string(),
+        "ctrl".to_string(),
+        key.to_string(),
+        "-m".to_string(),
+        "ctrl".to_string(),
+    ];
+    let ydotool = vec![
+        "ydotool".to_string(),
+        "key".to_string(),
+        "29:1".to_string(),
+        format!("{}:1", code),
+        format!("{}:0", code),
+        "29:0".to_string(),
+    ];
+
+    let candidates = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        [wtype, ydotool, xdotool]
+    } else {
+        [xdotool, ydotool, wtype]
+    };
+
+    for command in candidates {
+        let succeeded = Command::new(&command[0])
+            .args(&command[1..])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        if succeeded {
+            return Ok(());
+        }
+    }
+
+    Err(
+        "No input tool available. Install xdotool (X11), or wtype or ydotool (Wayland)."
+            .to_string(),
+    )
+}
+
+// --- Flow ---
+
+fn write_clipboard(app: &AppHandle, text: &str) {
+    app.state::<AppState>().clipboard_watch.note_own_text(text);
+    let _ = app.clipboard().write_text(text.to_string());
+}
+
+/// Copied files come first, file managers also offer their paths as text.
+fn save_clipboard(app: &AppHandle) -> SavedClipboard {
+    let files = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get().file_list());
+    if let Ok(files) = files {
+        if !files.is_empty() {
+            return SavedClipboard::Files(files);
+        }
+    }
+    if let Ok(text) = app.clipboard().read_text() {
+        return SavedClipboard::Text(text);
+    }
+    if let Ok(image) = app.clipboard().read_image() {
+        return SavedClipboard::Image(image.to_owned());
+    }
+    SavedClipboard::Empty
+}
+
+fn restore_clipboard(app: &AppHandle, previous: SavedClipboard) {
+    let result = match previous {
+        SavedClipboard::Text(text) => {
+            write_clipboard(app, &text);
+            Ok(())
+        }
+        SavedClipboard::Image(image) => app
+            .clipboard()
+            .write_image(&image)
+            .map_err(|e| e.to_string()),
+        SavedClipboard::Files(files) => arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set().file_list(&files))
+            .map_err(|e| e.to_string()),
+        SavedClipboard::Empty => app.clipboard().clear().map_err(|e| e.to_string()),
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to restore the clipboard: {}", e);
+    }
+}
+
+/// Shortcut handler. Blocks while copying and translating, so it runs on
+/// its own thread.
+pub fn trigger(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let busy = crate::tray::busy(&app, "Translating selection");
+        let result = replace_selection(&app);
+        drop(busy);
+        if let Err(e) = result {
+            crate::tray::report_error(&app, "Replace selection failed", &e);
+        }
+    });
+}
+
+fn replace_selection(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let settings = state.replace.settings.lock().unwrap().clone();
+
+    let overrides = match crate::app_rules::for_focused_window(app) {
+        Capture::Denied => return Ok(()),
+        capture => capture.overrides(),
+    };
+
+    // While a preview is pending the clipboard holds its selection, the
+    // user's content is the one saved back then
+    let pending = state.replace.pending.lock().unwrap().take();
+    let previous_clipboard = match pending {
+        Some(pending) => pending.previous_clipboard,
+        None => save_clipboard(app),
+    };
+    let window = crate::active_window::focused_window_id();
+
+    // Clear first so an empty selection is detectable
+    write_clipboard(app, "");
+    if let Err(e) = send_keystroke(Keystroke::Copy) {
+        restore_clipboard(app, previous_clipboard);
+        return Err(e);
+    }
+    std::thread::sleep(CLIPBOARD_SETTLE);
+
+    let text = app.clipboard().read_text().unwrap_or_default();
+    state.clipboard_watch.note_own_text(&text);
+    if text.trim().is_empty() {
+        restore_clipboard(app, previous_clipboard);
+        return Ok(());
+    }
+
+    // The writing language wins over a rule's target language
+    let translation = match tauri::async_runtime::block_on(crate::translate_with_overrides(
+        app,
+        &text,
+        "auto",
+        &settings.writing_lang,
+        &overrides,
+    )) {
+        Ok(translation) => translation,
+        Err(e) => {
+            restore_clipboard(app, previous_clipboard);
+            return Err(e);
+        }
+    };
+
+    let pending = PendingReplace {
+        window,
+        previous_clipboard,
+    };
+
+    if settings.preview {
+        *state.replace.pending.lock().unwrap() = Some(pending);
+        crate::show_quick_window(app, String::new());
+        let app = app.clone();
+        std::thread::spawn(move || {
+            // Same delay show_quick_window uses for its own event
+            std::thread::sleep(Duration::from_millis(100));
+            let _ = app.emit_to(
+                "quick",
+                "replace-preview",
+                ReplacePreview { text, translation },
+            );
+        });
+        Ok(())
+    } else {
+        paste(app, &translation, pending)
+    }
+}
+
+fn paste(app: &AppHandle, translation: &str, pending: PendingReplace) -> Result<(), String> {
+    if let Some(window) = pending.window {
+        // Focus may still be on the quick window after a preview
+        let _ = Command::new("xdotool")
+            .args(["windowactivate", "--sync", &window.to_string()])
+            .output();
+    }
+
+    write_clipboard(app, translation);
+    let result = send_keystroke(Keystroke::Paste);
+    std::thread::sleep(PASTE_SETTLE);
+    restore_clipboard(app, pending.previous_clipboard);
+    result
+}
+
+/// Drops a previewed translation and puts the user's clipboard back. Called
+/// when the quick window is dismissed without confirming.
+pub fn discard_pending(app: &AppHandle) {
+    let pending = app
+        .state::<AppState>()
+        .replace
+        .pending
+        .lock()
+        .unwrap()
+        .take();
+    if let Some(pending) = pending {
+        restore_clipboard(app, pending.previous_clipboard);
+    }
+}
+
+// --- Shortcut ---
+
+/// Registers `shortcut` for the mode, replacing the previous accelerator.
+fn register_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut registered = state.replace.registered.lock().unwrap();
+    if registered.as_deref() == Some(shortcut) {
+        return Ok(());
+    }
+
+    if let Some(old) = registered.take() {
+        if let Ok(old) = old.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(old);
+        }
+    }
+
+    if shortcut.is_empty() {
+        return Ok(());
+    }
+
+    let new_shortcut: Shortcut = shortcut.parse().map_err(|e| format!("{:?}", e))?;
+    app.global_shortcut()
+        .on_shortcut(new_shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed && !app.state::<AppState>().tray.is_paused() {
+                trigger(app);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    *registered = Some(shortcut.to_string());
+    Ok(())
+}
+
+/// Registers the shortcut of `settings` and makes them current, see
+/// [`crate::settings`].
+pub fn apply_settings(app: &AppHandle, settings: ReplaceSettings) -> Result<(), String> {
+    register_shortcut(app, &settings.shortcut)?;
+    *app.state::<AppState>().replace.settings.lock().unwrap() = settings;
+    Ok(())
+}
+
+// --- Commands ---
+
+/// Pastes the (possibly edited) previewed translation over the selection.
+#[tauri::command]
+pub async fn confirm_replace(app: AppHandle, translation: String) -> Result<(), String> {
+    let pending = app
+        .state::<AppState>()
+        .replace
+        .pending
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No replacement pending")?;
+
+    if let Some(quick) = app.get_webview_window("quick") {
+        let _ = quick.hide();
+    }
+
+    tauri::async_runtime::spawn_blocking(move || paste(&app, &translation, pending))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn cancel_replace(app: AppHandle) -> Result<(), String> {
+    discard_pending(&app);
+    Ok(())
+}
@@ -0,0 +1,267 @@
+//! Launcher integration: `tr <text>` typed into GNOME Shell's overview or
+//! KRunner shows the translation inline.
+//!
+//! Both interfaces are exported on the connection owned by [`crate::dbus`]
+//! and share one [`SearchEngine`], which debounces keystrokes and caches
+//! finished translations so the launcher stays responsive. Cached entries
+//! are per provider and language pair, switching either translates anew.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use zbus::{interface, zvariant::Value};
+
+use crate::AppState;
+
+pub const GNOME_OBJECT_PATH: &str = "/io/github/LightTranslator/SearchProvider";
+pub const KRUNNER_OBJECT_PATH: &str = "/io/github/LightTranslator/KRunner";
+
+/// First word of a launcher query that routes it to us.
+const TRIGGER_KEYWORD: &str = "tr";
+/// Quiet period before a query is sent to the provider.
+const DEBOUNCE: Duration = Duration::from_millis(350);
+const CACHE_CAPACITY: usize = 32;
+
+// KRunner match types (Plasma::QueryMatch::Type)
+const KRUNNER_EXACT_MATCH: i32 = 100;
+
+// --- Search Engine ---
+
+/// A query with the settings its translation was made with.
+#[derive(Debug, Clone, PartialEq)]
+struct CacheKey {
+    provider: String,
+    source: String,
+    target: String,
+    text: String,
+}
+
+pub struct SearchEngine {
+    app: AppHandle,
+    generation: AtomicU64,
+    cache: Mutex<VecDeque<(CacheKey, String)>>,
+}
+
+impl SearchEngine {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            generation: AtomicU64::new(0),
+            cache: Mutex::new(VecDeque::with_capacity(CACHE_CAPACITY)),
+        }
+    }
+
+    /// Extracts the text to translate from a launcher query, if it starts
+    /// with the trigger keyword.
+    fn parse_query(query: &str) -> Option<String> {
+        let rest = query.trim_start().strip_prefix(TRIGGER_KEYWORD)?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let text = rest.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// Key of `text` under the current provider and quick language pair,
+    /// which [`crate::translate_with_settings`] translates with.
+    fn cache_key(&self, text: &str) -> CacheKey {
+        let state = self.app.state::<AppState>();
+        let settings = state.translation_settings.lock().unwrap();
+        CacheKey {
+            provider: settings.provider.clone(),
+            source: settings.quick_source_lang.clone(),
+            target: settings.quick_target_lang.clone(),
+            text: text.to_string(),
+        }
+    }
+
+    fn cached(&self, text: &str) -> Option<String> {
+        let key = self.cache_key(text);
+        let cache = self.cache.lock().unwrap();
+        cache
+            .iter()
+            .find(|(cached, _)| *cached == key)
+            .map(|(_, translation)| translation.clone())
+    }
+
+    fn remember(&self, key: CacheKey, translation: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|(cached, _)| *cached != key);
+        if cache.len() == CACHE_CAPACITY {
+            cache.pop_front();
+        }
+        cache.push_back((key, translation.to_string()));
+    }
+
+    /// Translates `text`, or returns `None` if a newer query arrived during
+    /// the debounce window or the provider failed.
+    async fn lookup(&self, text: &str) -> Option<String> {
+        if let Some(translation) = self.cached(text) {
+            return Some(translation);
+        }
+        // Taken before translating, a later settings change must not file
+        // this result under the new provider
+        let key = self.cache_key(text);
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(DEBOUNCE).await;
+        if self.generation.load(Ordering::SeqCst) != generation {
+            // Superseded by a newer keystroke
+            return None;
+        }
+
+        match crate::translate_with_settings(&self.app, text, "", "").await {
+            Ok(translation) if !translation.is_empty() => {
+                self.remember(key, &translation);
+                Some(translation)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Launcher translation failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn copy_result(&self, text: &str) {
+        if let Some(translation) = self.cached(text) {
+            if let Err(e) = crate::copy_to_clipboard(&self.app, &translation) {
+                log::error!("Failed to copy translation: {}", e);
+            }
+        }
+    }
+
+    fn open_in_window(&self, text: &str) {
+        crate::open_text_in_main_window(&self.app, text);
+    }
+}
+
+// --- GNOME Shell ---
+
+pub struct GnomeSearchProvider {
+    engine: Arc<SearchEngine>,
+}
+
+impl GnomeSearchProvider {
+    pub fn new(engine: Arc<SearchEngine>) -> Self {
+        Self { engine }
+    }
+
+    async fn search(&self, terms: &[String]) -> Vec<String> {
+        let Some(text) = SearchEngine::parse_query(&terms.join(" ")) else {
+            return vec![];
+        };
+        match self.engine.lookup(&text).await {
+            // The identifier is the source text, metas are served from the cache
+            Some(_) => vec![text],
+            None => vec![],
+        }
+    }
+}
+
+#[interface(name = "org.gnome.Shell.SearchProvider2")]
+impl GnomeSearchProvider {
+    async fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+        self.search(&terms).await
+    }
+
+    async fn get_subsearch_result_set(
+        &self,
+        _previous_results: Vec<String>,
+        terms: Vec<String>,
+    ) -> Vec<String> {
+        self.search(&terms).await
+    }
+
+    async fn get_result_metas(&self, identifiers: Vec<String>) -> Vec<HashMap<String, Value<'_>>> {
+        identifiers
+            .into_iter()
+            .filter_map(|text| {
+                let translation = self.engine.cached(&text)?;
+                let mut meta = HashMap::new();
+                meta.insert("id".to_string(), Value::from(text.clone()));
+                meta.insert("name".to_string(), Value::from(translation));
+                meta.insert("description".to_string(), Value::from(text));
+                Some(meta)
+            })
+            .collect()
+    }
+
+    /// Clicking a result copies the translation.
+    async fn activate_result(&self, identifier: String, _terms: Vec<String>, _timestamp: u32) {
+        self.engine.copy_result(&identifier);
+    }
+
+    /// Clicking the app icon opens the query in the main window.
+    async fn launch_search(&self, terms: Vec<String>, _timestamp: u32) {
+        if let Some(text) = SearchEngine::parse_query(&terms.join(" ")) {
+            self.engine.open_in_window(&text);
+        }
+    }
+}
+
+// --- KRunner ---
+
+pub struct KRunnerPlugin {
+    engine: Arc<SearchEngine>,
+}
+
+impl KRunnerPlugin {
+    pub fn new(engine: Arc<SearchEngine>) -> Self {
+        Self { engine }
+    }
+}
+
+#[interface(name = "org.kde.krunner1")]
+impl KRunnerPlugin {
+    async fn actions(&self) -> Vec<(String, String, String)> {
+        vec![
+            (
+                "copy".to_string(),
+                "Copy translation".to_string(),
+                "edit-copy".to_string(),
+            ),
+            (
+                "open".to_string(),
+                "Open in LightTranslator".to_string(),
+                "window-new".to_string(),
+            ),
+        ]
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[zbus(name = "Match")]
+    async fn match_query(
+        &self,
+        query: String,
+    ) -> Vec<(String, String, String, i32, f64, HashMap<String, Value<'_>>)> {
+        let Some(text) = SearchEngine::parse_query(&query) else {
+            return vec![];
+        };
+        let Some(translation) = self.engine.lookup(&text).await else {
+            return vec![];
+        };
+
+        let mut properties = HashMap::new();
+        properties.insert("subtext".to_string(), Value::from(text.clone()));
+        vec![(
+            text,
+            translation,
+            "accessories-dictionary".to_string(),
+            KRUNNER_EXACT_MATCH,
+            1.0,
+            properties,
+        )]
+    }
+
+    /// An empty `action_id` is the default action (Enter), which copies.
+    async fn run(&self, match_id: String, action_id: String) {
+        match action_id.as_str() {
+            "open" => self.engine.open_in_window(&match_id),
+            _ => self.engine.copy_result(&match_id),
+        }
+    }
+}
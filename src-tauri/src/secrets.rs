@@ -0,0 +1,152 @@
+//! Secret references.
+//!
+//! Profiles name their API keys and passwords instead of containing them, so
+//! `profiles.json` can be shared or exported as is. A reference is one of:
+//!
+//! - `keyring:<name>`: the desktop keyring (GNOME Keyring, KWallet) through
+//!   libsecret's `secret-tool`
+//! - `local:<name>`: `secrets.json` in the config directory, readable by the
+//!   user only, used when no keyring is available
+//! - `env:<VAR>`: an environment variable, for keys provisioned from outside
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use tauri::{AppHandle, Manager};
+
+const SECRETS_FILE: &str = "secrets.json";
+/// `application` attribute of the keyring items.
+const KEYRING_APPLICATION: &str = "lighttranslator";
+
+/// Returns the secret `reference` points to.
+pub fn resolve(app: &AppHandle, reference: &str) -> Result<String, String> {
+    let (kind, name) = reference
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid secret reference '{}'", reference))?;
+    let secret = match kind {
+        "keyring" => keyring_lookup(name)?,
+        "local" => read_local(app)?.remove(name),
+        "env" => std::env::var(name).ok(),
+        _ => return Err(format!("Unknown secret store '{}'", kind)),
+    };
+    secret.ok_or_else(|| format!("Secret '{}' not found", reference))
+}
+
+/// Stores `secret` under `name` in the keyring, or in `secrets.json` if
+/// there is none, and returns its reference.
+pub fn store(app: &AppHandle, name: &str, secret: &str) -> Result<String, String> {
+    match keyring_store(name, secret) {
+        Ok(()) => Ok(format!("keyring:{}", name)),
+        Err(e) => {
+            log::warn!(
+                "Keyring unavailable, keeping secret in {}: {}",
+                SECRETS_FILE,
+                e
+            );
+            let mut secrets = read_local(app)?;
+            secrets.insert(name.to_string(), secret.to_string());
+            write_local(app, &secrets)?;
+            Ok(format!("local:{}", name))
+        }
+    }
+}
+
+/// Removes the secret behind `reference`. Environment variables are left
+/// alone.
+pub fn delete(app: &AppHandle, reference: &str) {
+    let result = match reference.split_once(':') {
+        Some(("keyring", name)) => keyring_clear(name),
+        Some(("local", name)) => read_local(app).and_then(|mut secrets| {
+            if secrets.remove(name).is_some() {
+                write_local(app, &secrets)
+            } else {
+                Ok(())
+            }
+        }),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to delete secret {}: {}", reference, e);
+    }
+}
+
+// --- Keyring ---
+
+fn keyring_lookup(name: &str) -> Result<Option<String>, String> {
+    let output = Command::new("secret-tool")
+        .args(["lookup", "application", KEYRING_APPLICATION, "name", name])
+        .output()
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+    // Exits with 1 and no output when there is no such item
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+fn keyring_store(name: &str, secret: &str) -> Result<(), String> {
+    let mut child = Command::new("secret-tool")
+        .args([
+            "store",
+            &format!("--label=LightTranslator: {}", name),
+            "application",
+            KEYRING_APPLICATION,
+            "name",
+            name,
+        ])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+    // The secret goes through stdin, never the command line
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(secret.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn keyring_clear(name: &str) -> Result<(), String> {
+    Command::new("secret-tool")
+        .args(["clear", "application", KEYRING_APPLICATION, "name", name])
+        .status()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))
+}
+
+// --- Local File ---
+
+fn local_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SECRETS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn read_local(app: &AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let path = local_path(app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+fn write_local(app: &AppHandle, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = local_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let contents = serde_json::to_string_pretty(secrets).map_err(|e| e.to_string())?;
+    crate::settings::write_private(&path, &contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,671 @@
+//! Central settings store.
+//!
+//! The backend owns the settings: they are kept in `settings.json` in the app
+//! config directory, validated and applied here (proxy, shortcuts, autostart,
+//! output modes, ...) and broadcast to the windows as `settings-changed` after
+//! every change, whether it came from the settings dialog, the tray or D-Bus.
+//!
+//! The file carries a `version`. Older documents are upgraded step by step by
+//! [`migrate`]; version 0 is the frontend's former localStorage store, which
+//! is imported once on the first start of a version with this module.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::app_rules::{self, AppRule};
+use crate::clipboard_watch::ClipboardWatchSettings;
+use crate::notifications::{OutputMode, OutputSettings};
+use crate::placement::QuickPlacement;
+use crate::replace::ReplaceSettings;
+use crate::translate::{self, TranslationSettings};
+use crate::{AppState, ProxySettings};
+
+pub const CURRENT_VERSION: u64 = 1;
+const SETTINGS_FILE: &str = "settings.json";
+/// Key of the zustand store the frontend used to persist its settings in.
+const LEGACY_STORE_KEY: &str = "light-translator-storage";
+
+/// Everything the user can configure. Field names and defaults match the
+/// frontend's `AppSettings` and `DEFAULT_SETTINGS`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u64,
+    // Main panel
+    pub auto_translate: bool,
+    pub debounce_ms: u32,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub use_ocr_pre_processing: bool,
+    // Provider, keys and quick window languages
+    #[serde(flatten)]
+    pub translation: TranslationSettings,
+    // Proxy
+    pub proxy_enabled: bool,
+    pub proxy_protocol: String,
+    pub proxy_host: String,
+    pub proxy_port: u16,
+    pub proxy_username: String,
+    pub proxy_password: String,
+    /// Hosts reached without the proxy, e.g. `translate.internal, 10.0.0.0/8`
+    pub proxy_bypass: String,
+    // Shortcuts
+    pub selection_shortcut: String,
+    // Result output
+    pub quick_translate_output: OutputMode,
+    pub ocr_output: OutputMode,
+    // Replace selection
+    pub replace_shortcut: String,
+    pub writing_lang: String,
+    pub replace_preview: bool,
+    // Startup
+    pub launch_at_startup: bool,
+    // Quick window appearance
+    pub quick_window_opacity: f64,
+    pub quick_window_border_opacity: f64,
+    pub quick_window_placement: QuickPlacement,
+    pub clipboard_watch: ClipboardWatchSettings,
+    /// Edited through `get_app_rules`/`set_app_rules` rather than the
+    /// frontend's `AppSettings`
+    pub app_rules: Vec<AppRule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let replace = ReplaceSettings::default();
+        Self {
+            version: CURRENT_VERSION,
+            auto_translate: true,
+            debounce_ms: 500,
+            source_lang: "auto".to_string(),
+            target_lang: "zh-CN".to_string(),
+            use_ocr_pre_processing: false,
+            translation: TranslationSettings::default(),
+            proxy_enabled: false,
+            proxy_protocol: "http".to_string(),
+            proxy_host: String::new(),
+            proxy_port: 8080,
+            proxy_username: String::new(),
+            proxy_password: String::new(),
+            proxy_bypass: String::new(),
+            selection_shortcut: "CommandOrControl+Shift+X".to_string(),
+            quick_translate_output: OutputMode::Window,
+            ocr_output: OutputMode::Window,
+            replace_shortcut: replace.shortcut,
+            writing_lang: replace.writing_lang,
+            replace_preview: replace.preview,
+            launch_at_startup: false,
+            quick_window_opacity: 0.95,
+            quick_window_border_opacity: 0.05,
+            quick_window_placement: QuickPlacement::Cursor,
+            clipboard_watch: ClipboardWatchSettings::default(),
+            app_rules: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn proxy(&self) -> ProxySettings {
+        let optional = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        ProxySettings {
+            enabled: self.proxy_enabled,
+            protocol: self.proxy_protocol.clone(),
+            host: self.proxy_host.clone(),
+            port: self.proxy_port,
+            username: optional(&self.proxy_username),
+            password: optional(&self.proxy_password),
+            bypass: self.proxy_bypass.clone(),
+        }
+    }
+
+    fn replace(&self) -> ReplaceSettings {
+        ReplaceSettings {
+            shortcut: self.replace_shortcut.clone(),
+            writing_lang: self.writing_lang.clone(),
+            preview: self.replace_preview,
+        }
+    }
+
+    fn outputs(&self) -> OutputSettings {
+        OutputSettings {
+            quick_translate: self.quick_translate_output,
+            ocr: self.ocr_output,
+        }
+    }
+
+    /// Checks what the types alone don't. Text that is still being typed
+    /// (hosts, model names, keys) is accepted as is.
+    pub fn validate(&self) -> Result<(), String> {
+        if !translate::is_provider(&self.translation.provider) {
+            return Err(format!("Unknown provider '{}'", self.translation.provider));
+        }
+
+        let languages = [
+            ("sourceLang", &self.source_lang, true),
+            ("targetLang", &self.target_lang, false),
+            ("quickSourceLang", &self.translation.quick_source_lang, true),
+            (
+                "quickTargetLang",
+                &self.translation.quick_target_lang,
+                false,
+            ),
+            ("writingLang", &self.writing_lang, false),
+        ];
+        for (field, code, auto_allowed) in languages {
+            let known = translate::LANGUAGES
+                .iter()
+                .any(|(c, _)| *c == code.as_str());
+            if !known || (code == "auto" && !auto_allowed) {
+                return Err(format!("Invalid language '{}' for {}", code, field));
+            }
+        }
+
+        if !["http", "https", "socks5"].contains(&self.proxy_protocol.as_str()) {
+            return Err(format!("Unknown proxy protocol '{}'", self.proxy_protocol));
+        }
+        if self.proxy_enabled && !self.proxy_host.is_empty() && self.proxy_port == 0 {
+            return Err("Proxy port must be between 1 and 65535".to_string());
+        }
+        if self.selection_shortcut.is_empty() {
+            return Err("The selection shortcut can't be empty".to_string());
+        }
+        if !self.replace_shortcut.is_empty()
+            && same_shortcut(&self.replace_shortcut, &self.selection_shortcut)
+        {
+            return Err("The replace shortcut must differ from the selection shortcut".to_string());
+        }
+        if !(0.5..=1.0).contains(&self.quick_window_opacity) {
+            return Err("quickWindowOpacity must be between 0.5 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.quick_window_border_opacity) {
+            return Err("quickWindowBorderOpacity must be between 0 and 1".to_string());
+        }
+        if self.debounce_ms > 10_000 {
+            return Err("debounceMs must be at most 10000".to_string());
+        }
+        if !["", "formal", "informal"].contains(&self.translation.formality.as_str()) {
+            return Err(format!(
+                "Invalid formality '{}'",
+                self.translation.formality
+            ));
+        }
+        app_rules::validate(&self.app_rules)
+    }
+}
+
+/// Whether two accelerators name the same keys, "Ctrl+Shift+X" and
+/// "control+shift+x" do.
+fn same_shortcut(a: &str, b: &str) -> bool {
+    match (a.parse::<Shortcut>(), b.parse::<Shortcut>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.eq_ignore_ascii_case(b),
+    }
+}
+
+#[derive(Default)]
+pub struct SettingsStore {
+    settings: Mutex<Settings>,
+    /// Held through a whole [`update`], so changes are applied and saved in
+    /// the order they are made
+    updating: Mutex<()>,
+    /// Whether `settings.json` exists, i.e. nothing is left to import
+    stored: Mutex<bool>,
+}
+
+/// Payload of `settings-changed`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChanged {
+    settings: Settings,
+    /// Label of the window that made the change, which already shows it
+    origin: Option<String>,
+}
+
+pub fn current(app: &AppHandle) -> Settings {
+    app.state::<AppState>()
+        .settings
+        .settings
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+// --- Migrations ---
+
+/// Upgrades a settings document to [`CURRENT_VERSION`].
+fn migrate(mut document: Value) -> Result<Value, String> {
+    let mut version = document.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Settings were written by a newer version (format {})",
+            version
+        ));
+    }
+    while version < CURRENT_VERSION {
+        document = match version {
+            0 => migrate_v0(document),
+            _ => unreachable!("no migration from settings version {}", version),
+        };
+        version += 1;
+        document["version"] = Value::from(version);
+    }
+    Ok(document)
+}
+
+/// Version 0 is the frontend's zustand store, `{"state": {...}, "version": 0}`.
+fn migrate_v0(document: Value) -> Value {
+    match document {
+        Value::Object(mut store) => match store.remove("state") {
+            Some(Value::Object(state)) => Value::Object(state),
+            _ => Value::Object(store),
+        },
+        _ => Value::Object(Map::new()),
+    }
+}
+
+/// Parses a settings document of any version. Fields that no longer exist
+/// are dropped and invalid ones are reset to their defaults, so one bad value
+/// doesn't cost the others (API keys in particular); the names of the reset
+/// fields are returned along with the settings.
+fn parse(document: Value) -> Result<(Settings, Vec<String>), String> {
+    let Value::Object(fields) = migrate(document)? else {
+        return Err("Invalid settings: not an object".to_string());
+    };
+    let mut settings = Settings::default();
+    let Value::Object(known) = serde_json::to_value(&settings).map_err(|e| e.to_string())? else {
+        unreachable!("settings serialize to an object");
+    };
+
+    let mut reset = Vec::new();
+    for (key, value) in fields {
+        if key == "version" || !known.contains_key(&key) {
+            continue;
+        }
+        // Custom providers and plugins can be removed between runs, which
+        // resets the provider too
+        let mut patched = settings.clone();
+        let patch = Map::from_iter([(key.clone(), value)]);
+        match merge_patch(&mut patched, patch).and_then(|()| patched.validate()) {
+            Ok(()) => settings = patched,
+            Err(e) => {
+                log::warn!("Resetting {} to its default: {}", key, e);
+                reset.push(key);
+            }
+        }
+    }
+    Ok((settings, reset))
+}
+
+// --- Persistence ---
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn save(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_private(&path, &contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    *app.state::<AppState>().settings.stored.lock().unwrap() = true;
+    Ok(())
+}
+
+/// Writes `contents` readable by the user only, the file holds API keys. It
+/// is written next to `path` first and then renamed over it, so `path` is
+/// never left half written and gets the permissions even if it existed.
+pub fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    #[cfg(unix)]
+    file.as_file()
+        .set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Keeps a copy of a settings file before repaired settings replace it.
+fn back_up(path: &Path) {
+    let backup = path.with_extension("json.bak");
+    match std::fs::copy(path, &backup) {
+        Ok(_) => log::warn!("Kept the previous settings in {}", backup.display()),
+        Err(e) => log::error!("Failed to back up {}: {}", path.display(), e),
+    }
+}
+
+/// Loads `settings.json` (or the defaults) and applies it. Called once at
+/// startup, before the tray and the shortcuts are needed.
+pub fn load(app: &AppHandle) {
+    use tauri_plugin_autostart::ManagerExt;
+
+    let mut stored = false;
+    let mut settings = match settings_path(app).and_then(|path| {
+        std::fs::read_to_string(&path)
+            .map(|contents| (path.clone(), contents))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }) {
+        Ok((path, contents)) => {
+            stored = true;
+            let parsed = serde_json::from_str::<Value>(&contents)
+                .map_err(|e| e.to_string())
+                .and_then(parse);
+            match parsed {
+                Ok((settings, reset)) => {
+                    if !reset.is_empty() {
+                        back_up(&path);
+                    }
+                    settings
+                }
+                Err(e) => {
+                    log::error!("Ignoring invalid {}: {}", SETTINGS_FILE, e);
+                    back_up(&path);
+                    Settings::default()
+                }
+            }
+        }
+        Err(_) => Settings::default(),
+    };
+
+    // Rules used to be kept in a file of their own. Saving doesn't count as
+    // stored below, so the frontend's settings are still imported.
+    if let Some(rules) = app_rules::take_legacy(app) {
+        settings.app_rules = rules;
+        if let Err(e) = save(app, &settings) {
+            log::error!("{}", e);
+        }
+    }
+
+    crate::policy::current().enforce(&mut settings);
+
+    // The autostart entry may have been removed behind our back
+    if let Ok(enabled) = app.autolaunch().is_enabled() {
+        settings.launch_at_startup = enabled;
+    }
+
+    let state = app.state::<AppState>();
+    *state.settings.settings.lock().unwrap() = settings.clone();
+    *state.settings.stored.lock().unwrap() = stored;
+    if let Err(e) = apply(app, None, &settings) {
+        log::error!("Failed to apply settings: {}", e);
+    }
+}
+
+// --- Applying ---
+
+/// Pushes `new` into the running app, skipping what didn't change since
+/// `old`. Every part is applied even if one fails; failed registrations are
+/// returned together.
+fn apply(app: &AppHandle, old: Option<&Settings>, new: &Settings) -> Result<(), String> {
+    use tauri_plugin_autostart::ManagerExt;
+
+    let state = app.state::<AppState>();
+    let mut errors = Vec::new();
+
+    if old.map_or(true, |old| old.selection_shortcut != new.selection_shortcut) {
+        if let Err(e) = crate::register_selection_shortcut(app, &new.selection_shortcut) {
+            errors.push(format!("Shortcut {}: {}", new.selection_shortcut, e));
+        }
+    }
+    if let Err(e) = crate::replace::apply_settings(app, new.replace()) {
+        errors.push(format!("Shortcut {}: {}", new.replace_shortcut, e));
+    }
+    if old.is_some_and(|old| old.launch_at_startup != new.launch_at_startup) {
+        let autostart = app.autolaunch();
+        let result = if new.launch_at_startup {
+            autostart.enable()
+        } else {
+            autostart.disable()
+        };
+        if let Err(e) = result {
+            errors.push(format!("Launch at startup: {}", e));
+        }
+    }
+
+    *state.proxy_settings.lock().unwrap() = Some(new.proxy());
+    *state.translation_settings.lock().unwrap() = new.translation.clone();
+    state.outputs.set(new.outputs());
+    state.placement.set_quick(new.quick_window_placement);
+    state.app_rules.set(new.app_rules.clone());
+    crate::clipboard_watch::apply_settings(app, new.clipboard_watch.clone());
+
+    // The tray shows the provider, language pair and clipboard watch state
+    crate::tray::refresh(app);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Changes the settings through `change`, then validates, applies, saves and
+/// broadcasts them. Nothing changes if validation or applying fails.
+///
+/// Not to be called on the main thread: registering shortcuts and rebuilding
+/// the tray wait for it, while another update may be holding the lock.
+pub fn update(
+    app: &AppHandle,
+    origin: Option<&str>,
+    change: impl FnOnce(&mut Settings) -> Result<(), String>,
+) -> Result<Settings, String> {
+    let state = app.state::<AppState>();
+    let store = &state.settings;
+    let _updating = store.updating.lock().unwrap();
+
+    let old = store.settings.lock().unwrap().clone();
+    let mut new = old.clone();
+    change(&mut new)?;
+    new.version = CURRENT_VERSION;
+    new.validate()?;
+    crate::policy::current().check_settings(&new)?;
+
+    if let Err(e) = apply(app, Some(&old), &new) {
+        // Put back the registrations of the old settings
+        let _ = apply(app, Some(&new), &old);
+        return Err(e);
+    }
+    if let Err(e) = save(app, &new) {
+        log::error!("{}", e);
+    }
+    *store.settings.lock().unwrap() = new.clone();
+
+    let payload = SettingsChanged {
+        settings: new.clone(),
+        origin: origin.map(str::to_string),
+    };
+    let _ = app.emit("settings-changed", payload);
+    Ok(new)
+}
+
+/// Merges a partial settings object (camelCase keys) into `settings`.
+pub fn merge_patch(settings: &mut Settings, patch: Map<String, Value>) -> Result<(), String> {
+    let Value::Object(mut document) =
+        serde_json::to_value(&*settings).map_err(|e| e.to_string())?
+    else {
+        unreachable!("settings serialize to an object");
+    };
+    for (key, value) in patch {
+        if key == "version" || !document.contains_key(&key) {
+            return Err(format!("Unknown setting '{}'", key));
+        }
+        document.insert(key, value);
+    }
+    *settings = serde_json::from_value(Value::Object(document))
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+    Ok(())
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn get_settings(app: AppHandle) -> Result<Settings, String> {
+    Ok(current(&app))
+}
+
+/// Applies the given fields and returns the resulting settings.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    window: WebviewWindow,
+    patch: Map<String, Value>,
+) -> Result<Settings, String> {
+    update(&app, Some(window.label()), |settings| {
+        merge_patch(settings, patch)
+    })
+}
+
+/// One-time import of the settings the frontend kept in localStorage under
+/// `light-translator-storage`. Ignored once `settings.json` exists.
+#[tauri::command]
+pub async fn import_frontend_settings(app: AppHandle, store: String) -> Result<Settings, String> {
+    if *app.state::<AppState>().settings.stored.lock().unwrap() {
+        return Ok(current(&app));
+    }
+    let document: Value = serde_json::from_str(&store)
+        .map_err(|e| format!("Invalid {} store: {}", LEGACY_STORE_KEY, e))?;
+    let (imported, _) = parse(document)?;
+    update(&app, None, |settings| {
+        // Autostart reflects the system, not the stale frontend copy, and
+        // the frontend never had the application rules
+        let launch_at_startup = settings.launch_at_startup;
+        let rules = std::mem::take(&mut settings.app_rules);
+        *settings = imported;
+        settings.launch_at_startup = launch_at_startup;
+        settings.app_rules = rules;
+        crate::policy::current().enforce(settings);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn migrates_the_frontend_store() {
+        let document = migrate(json!({
+            "state": { "provider": "deepl", "deeplApiKey": "key" },
+            "version": 0
+        }))
+        .unwrap();
+        assert_eq!(
+            document,
+            json!({ "provider": "deepl", "deeplApiKey": "key", "version": CURRENT_VERSION })
+        );
+
+        let current = json!({ "provider": "deepl", "version": CURRENT_VERSION });
+        assert_eq!(migrate(current.clone()).unwrap(), current);
+        assert!(migrate(json!({ "version": CURRENT_VERSION + 1 })).is_err());
+    }
+
+    #[test]
+    fn merges_patches() {
+        let mut settings = Settings::default();
+        merge_patch(
+            &mut settings,
+            object(json!({ "provider": "deepl", "proxyPort": 3128 })),
+        )
+        .unwrap();
+        assert_eq!(settings.translation.provider, "deepl");
+        assert_eq!(settings.proxy_port, 3128);
+
+        assert_eq!(
+            merge_patch(&mut settings, object(json!({ "noSuchSetting": true }))).unwrap_err(),
+            "Unknown setting 'noSuchSetting'"
+        );
+        assert!(merge_patch(&mut settings, object(json!({ "version": 0 }))).is_err());
+        assert!(merge_patch(&mut settings, object(json!({ "proxyPort": "x" }))).is_err());
+        assert_eq!(settings.proxy_port, 3128);
+    }
+
+    #[test]
+    fn validates_settings() {
+        assert!(Settings::default().validate().is_ok());
+
+        let valid = |change: fn(&mut Settings)| {
+            let mut settings = Settings::default();
+            change(&mut settings);
+            settings.validate().is_ok()
+        };
+        assert!(valid(|s| s.source_lang = "auto".to_string()));
+        assert!(valid(|s| s.translation.formality = "formal".to_string()));
+        assert!(!valid(|s| s.translation.provider = "nope".to_string()));
+        assert!(!valid(|s| s.target_lang = "auto".to_string()));
+        assert!(!valid(
+            |s| s.translation.quick_source_lang = "xx".to_string()
+        ));
+        assert!(!valid(|s| s.proxy_protocol = "ftp".to_string()));
+        assert!(!valid(|s| s.selection_shortcut.clear()));
+        assert!(valid(|s| s.replace_shortcut.clear()));
+        assert!(!valid(|s| s.replace_shortcut = s.selection_shortcut.clone()));
+        assert!(!valid(|s| s.quick_window_opacity = 0.2));
+        assert!(!valid(|s| s.debounce_ms = 60_000));
+        assert!(!valid(|s| s.translation.formality = "casual".to_string()));
+    }
+
+    #[test]
+    fn resets_only_invalid_fields() {
+        let (settings, mut reset) = parse(json!({
+            "version": CURRENT_VERSION,
+            "provider": "deepl",
+            "deeplApiKey": "secret",
+            "targetLang": "not-a-language",
+            "proxyPort": "not a number",
+            "removedSetting": true
+        }))
+        .unwrap();
+        let defaults = Settings::default();
+        assert_eq!(settings.translation.provider, "deepl");
+        assert_eq!(settings.translation.deepl_api_key, "secret");
+        assert_eq!(settings.target_lang, defaults.target_lang);
+        assert_eq!(settings.proxy_port, defaults.proxy_port);
+        reset.sort();
+        assert_eq!(reset, ["proxyPort", "targetLang"]);
+
+        assert!(parse(json!({ "version": CURRENT_VERSION + 1 })).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_private_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, "new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The temporary file was renamed, not left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
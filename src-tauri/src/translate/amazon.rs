@@ -0,0 +1,264 @@
+//! Amazon Translate (`TranslateText`), signed with AWS Signature Version 4.
+//!
+//! Credentials come from the settings or, when those are empty, from a
+//! profile of the shared credentials file (`~/.aws/credentials`), like the
+//! AWS CLI.
+
+use std::path::PathBuf;
+
+use serde_json::json;
+
+use super::{send_json, sign, TranslationSettings};
+
+const SERVICE: &str = "translate";
+const TARGET: &str = "AWSShineFrontendService_20170701.TranslateText";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+const DEFAULT_REGION: &str = "us-east-1";
+
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let credentials = credentials(settings)?;
+    let region = region(settings);
+    let endpoint = if settings.aws_endpoint.is_empty() {
+        format!("https://translate.{}.amazonaws.com", region)
+    } else {
+        settings.aws_endpoint.trim_end_matches('/').to_string()
+    };
+    let url = reqwest::Url::parse(&endpoint).map_err(|e| format!("Invalid endpoint: {}", e))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => return Err(format!("Invalid endpoint: {}", endpoint)),
+    };
+
+    let mut body = json!({
+        "Text": text,
+        "SourceLanguageCode": map_lang_code(source),
+        "TargetLanguageCode": map_lang_code(target),
+    });
+    let terminologies = names(&settings.aws_terminology);
+    if !terminologies.is_empty() {
+        body["TerminologyNames"] = json!(terminologies);
+    }
+    match settings.formality.as_str() {
+        "formal" => body["Settings"] = json!({ "Formality": "FORMAL" }),
+        "informal" => body["Settings"] = json!({ "Formality": "INFORMAL" }),
+        _ => {}
+    }
+    let payload = body.to_string();
+
+    let timestamp = sign::timestamp();
+    let amz_date = amz_date(timestamp);
+    let mut headers = vec![
+        ("content-type", CONTENT_TYPE.to_string()),
+        ("host", host),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    headers.push(("x-amz-target", TARGET.to_string()));
+    let signer = sign::SigV4 {
+        access_key_id: &credentials.access_key_id,
+        secret_access_key: &credentials.secret_access_key,
+        region: &region,
+        service: SERVICE,
+    };
+    let authorization = signer.authorization("POST", &headers, &payload, &amz_date);
+
+    let mut request = client
+        .post(format!("{}/", endpoint))
+        .header("Authorization", authorization)
+        .body(payload);
+    // `host` is set by reqwest from the URL
+    for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+        request = request.header(*name, value);
+    }
+    let data = send_json(request, "Amazon Translate Error").await?;
+    Ok(translated_text(&data))
+}
+
+fn translated_text(data: &serde_json::Value) -> String {
+    data["TranslatedText"]
+        .as_str()
+        .unwrap_or("Translation empty.")
+        .to_string()
+}
+
+// --- Credentials ---
+
+fn credentials(settings: &TranslationSettings) -> Result<Credentials, String> {
+    if !settings.aws_access_key_id.is_empty() {
+        if settings.aws_secret_access_key.is_empty() {
+            return Err("AWS Secret Access Key is required.".to_string());
+        }
+        return Ok(Credentials {
+            access_key_id: settings.aws_access_key_id.clone(),
+            secret_access_key: settings.aws_secret_access_key.clone(),
+            session_token: None,
+        });
+    }
+
+    let profile = profile(settings);
+    let path = shared_file("AWS_SHARED_CREDENTIALS_FILE", "credentials")?;
+    let contents = std::fs::read_to_string(&path).map_err(|_| {
+        format!(
+            "AWS credentials are required: enter an access key or add the '{}' profile to {}",
+            profile,
+            path.display()
+        )
+    })?;
+    let value = |key: &str| ini_value(&contents, &profile, key);
+    match (value("aws_access_key_id"), value("aws_secret_access_key")) {
+        (Some(access_key_id), Some(secret_access_key)) => Ok(Credentials {
+            access_key_id,
+            secret_access_key,
+            session_token: value("aws_session_token"),
+        }),
+        _ => Err(format!(
+            "Profile '{}' in {} has no access key",
+            profile,
+            path.display()
+        )),
+    }
+}
+
+fn profile(settings: &TranslationSettings) -> String {
+    if !settings.aws_profile.is_empty() {
+        return settings.aws_profile.clone();
+    }
+    std::env::var("AWS_PROFILE").unwrap_or_else(|_| "default".to_string())
+}
+
+/// The region in the settings, else that of the profile in `~/.aws/config`.
+fn region(settings: &TranslationSettings) -> String {
+    if !settings.aws_region.is_empty() {
+        return settings.aws_region.clone();
+    }
+    let profile = profile(settings);
+    // The config file names sections `[profile name]`, except `[default]`
+    let section = if profile == "default" {
+        profile
+    } else {
+        format!("profile {}", profile)
+    };
+    shared_file("AWS_CONFIG_FILE", "config")
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| ini_value(&contents, &section, "region"))
+        .unwrap_or_else(|| DEFAULT_REGION.to_string())
+}
+
+/// `~/.aws/<name>`, or the path in the environment variable `var`.
+fn shared_file(var: &str, name: &str) -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var(var) {
+        return Ok(PathBuf::from(path));
+    }
+    let home = dirs::home_dir().ok_or("Could not determine the home directory")?;
+    Ok(home.join(".aws").join(name))
+}
+
+/// `key` in `[section]` of an INI file.
+fn ini_value(contents: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim() == section;
+        } else if in_section {
+            if let Some((name, value)) = line.split_once('=') {
+                if name.trim() == key {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+// --- Helpers ---
+
+/// `YYYYMMDD'T'HHMMSS'Z'`
+fn amz_date(timestamp: u64) -> String {
+    let (year, month, day) = sign::utc_date(timestamp);
+    let seconds = timestamp % 86_400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Comma-separated names, e.g. of terminologies.
+fn names(list: &str) -> Vec<&str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn map_lang_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_amz_date() {
+        assert_eq!(amz_date(1_440_938_160), "20150830T123600Z");
+    }
+
+    #[test]
+    fn reads_shared_credentials_profile() {
+        let contents = "[default]\n\
+                        aws_access_key_id = AKIDDEFAULT\n\
+                        \n\
+                        [work]\n\
+                        aws_access_key_id=AKIDWORK\n\
+                        aws_secret_access_key = secret\n";
+        assert_eq!(
+            ini_value(contents, "work", "aws_access_key_id").as_deref(),
+            Some("AKIDWORK")
+        );
+        assert_eq!(
+            ini_value(contents, "default", "aws_access_key_id").as_deref(),
+            Some("AKIDDEFAULT")
+        );
+        assert_eq!(
+            ini_value(contents, "default", "aws_secret_access_key"),
+            None
+        );
+    }
+
+    #[test]
+    fn splits_terminology_names() {
+        assert_eq!(names(" brand, , legal "), ["brand", "legal"]);
+        assert!(names("").is_empty());
+    }
+
+    #[test]
+    fn parses_translation() {
+        let data =
+            serde_json::from_str(include_str!("fixtures/amazon_translate_text.json")).unwrap();
+        assert_eq!(translated_text(&data), "Hallo Welt, sagte LightLamp");
+    }
+}
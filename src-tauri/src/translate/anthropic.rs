@@ -0,0 +1,359 @@
+//! Anthropic Messages API (`/v1/messages`).
+//!
+//! The base URL is configurable so the provider can be pointed at a local
+//! mock server or a gateway speaking the same API.
+
+use serde_json::json;
+
+use super::{or_default, send_json, send_lines, Model, TranslationSettings};
+
+const API_VERSION: &str = "2023-06-01";
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const DEFAULT_MODEL: &str = "claude-haiku-4-5";
+const MAX_TOKENS: u32 = 4096;
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let request = messages_request(client, text, source, target, settings, false)?;
+    let data = send_json(request, "Anthropic API Error").await?;
+
+    Ok(data["content"][0]["text"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Translation empty.".to_string()))
+}
+
+/// Like [`translate`], calling `on_delta` with every piece of text as it
+/// arrives. Returns the whole translation.
+pub(super) async fn translate_stream(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+    on_delta: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    let label = "Anthropic API Error";
+    let request = messages_request(client, text, source, target, settings, true)?;
+
+    // Server-sent events; only the `data:` lines matter, the event type is
+    // repeated in the payload
+    let mut translation = String::new();
+    send_lines(request, label, |line| {
+        let Some(data) = line.strip_prefix("data:") else {
+            return Ok(());
+        };
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(data.trim()) else {
+            return Ok(());
+        };
+        match event["type"].as_str() {
+            Some("content_block_delta") => {
+                if let Some(delta) = event["delta"]["text"].as_str() {
+                    translation.push_str(delta);
+                    on_delta(delta);
+                }
+                Ok(())
+            }
+            Some("error") => {
+                let message = event["error"]["message"]
+                    .as_str()
+                    .unwrap_or("unknown error");
+                Err(format!("{}: {}", label, message))
+            }
+            _ => Ok(()),
+        }
+    })
+    .await?;
+
+    if translation.trim().is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(translation.trim().to_string())
+}
+
+/// Models available to the configured key, newest first.
+pub(super) async fn list_models(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+) -> Result<Vec<Model>, String> {
+    if settings.anthropic_api_key.is_empty() {
+        return Err("Anthropic API Key is required.".to_string());
+    }
+
+    let request = client
+        .get(format!("{}/v1/models", base_url(settings)))
+        .query(&[("limit", "1000")])
+        .header("x-api-key", &settings.anthropic_api_key)
+        .header("anthropic-version", API_VERSION);
+    let data = send_json(request, "Anthropic API Error").await?;
+
+    Ok(data["data"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|model| {
+                    let id = model["id"].as_str()?.to_string();
+                    let name = model["display_name"].as_str().unwrap_or(&id).to_string();
+                    Some(Model { id, name })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// --- Helpers ---
+
+fn base_url(settings: &TranslationSettings) -> &str {
+    or_default(&settings.anthropic_base_url, DEFAULT_BASE_URL).trim_end_matches('/')
+}
+
+fn messages_request(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+    stream: bool,
+) -> Result<reqwest::RequestBuilder, String> {
+    if settings.anthropic_api_key.is_empty() {
+        return Err("Anthropic API Key is required.".to_string());
+    }
+
+    let mut body = json!({
+        "model": or_default(&settings.anthropic_model, DEFAULT_MODEL),
+        "max_tokens": MAX_TOKENS,
+        "messages": [{ "role": "user", "content": text }],
+        "temperature": 0.3
+    });
+    // The system prompt is a top-level field rather than a message
+    if settings.system_prompt_enabled {
+        body["system"] = json!(super::system_prompt(source, target, settings));
+    }
+    if stream {
+        body["stream"] = json!(true);
+    }
+
+    Ok(client
+        .post(format!("{}/v1/messages", base_url(settings)))
+        .header("x-api-key", &settings.anthropic_api_key)
+        .header("anthropic-version", API_VERSION)
+        .json(&body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    fn settings(base_url: &str) -> TranslationSettings {
+        TranslationSettings {
+            anthropic_api_key: "test-key".to_string(),
+            anthropic_base_url: base_url.to_string(),
+            ..TranslationSettings::default()
+        }
+    }
+
+    /// Whether `request` holds the headers and the whole body.
+    fn is_complete(request: &[u8]) -> bool {
+        let request = String::from_utf8_lossy(request);
+        let Some((head, body)) = request.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse().ok())
+            .unwrap_or(0);
+        body.len() >= length
+    }
+
+    /// Answers one request on a local port. Returns the base URL and the
+    /// raw request once it is answered.
+    async fn serve(status: &'static str, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !is_complete(&request) {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, server)
+    }
+
+    fn body(request: &str) -> serde_json::Value {
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn builds_messages_request() {
+        let client = reqwest::Client::new();
+        let settings = settings("https://gateway.example/");
+
+        let request = messages_request(&client, "Hello", "en", "de", &settings, true)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://gateway.example/v1/messages"
+        );
+        assert_eq!(request.headers()["x-api-key"], "test-key");
+        assert_eq!(request.headers()["anthropic-version"], API_VERSION);
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["model"], "claude-haiku-4-5");
+        assert_eq!(body["messages"][0]["content"], "Hello");
+        assert_eq!(body["stream"], true);
+        assert!(body["system"].as_str().unwrap().contains("from en to de"));
+
+        let settings = TranslationSettings {
+            system_prompt_enabled: false,
+            anthropic_model: String::new(),
+            ..settings
+        };
+        let request = messages_request(&client, "Hello", "en", "de", &settings, false)
+            .unwrap()
+            .build()
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["model"], DEFAULT_MODEL);
+        assert!(body.get("system").is_none());
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn requires_api_key() {
+        let client = reqwest::Client::new();
+        let settings = TranslationSettings {
+            anthropic_api_key: String::new(),
+            ..settings("")
+        };
+        assert!(messages_request(&client, "Hello", "en", "de", &settings, false).is_err());
+    }
+
+    #[tokio::test]
+    async fn translates() {
+        let (base_url, server) =
+            serve("200 OK", include_str!("fixtures/anthropic_messages.json")).await;
+        let client = reqwest::Client::new();
+
+        let translation = translate(&client, "Hello world", "en", "de", &settings(&base_url))
+            .await
+            .unwrap();
+        assert_eq!(translation, "Hallo Welt");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/messages HTTP/1.1"));
+        assert!(request.contains("x-api-key: test-key"));
+        assert_eq!(body(&request)["messages"][0]["content"], "Hello world");
+    }
+
+    #[tokio::test]
+    async fn reports_api_errors() {
+        let (base_url, _server) = serve(
+            "401 Unauthorized",
+            r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        )
+        .await;
+        let client = reqwest::Client::new();
+
+        let error = translate(&client, "Hello", "en", "de", &settings(&base_url))
+            .await
+            .unwrap_err();
+        assert!(error.starts_with("Anthropic API Error: 401 - "));
+        assert!(error.contains("invalid x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn streams_deltas() {
+        let (base_url, server) = serve(
+            "200 OK",
+            include_str!("fixtures/anthropic_messages_stream.txt"),
+        )
+        .await;
+        let client = reqwest::Client::new();
+        let deltas = std::sync::Mutex::new(Vec::new());
+
+        let translation = translate_stream(
+            &client,
+            "Hello world",
+            "en",
+            "de",
+            &settings(&base_url),
+            &|delta| deltas.lock().unwrap().push(delta.to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(translation, "Hallo Welt");
+        assert_eq!(*deltas.lock().unwrap(), ["Hallo", " Welt"]);
+        assert_eq!(body(&server.await.unwrap())["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn reports_stream_errors() {
+        let (base_url, _server) = serve(
+            "200 OK",
+            "event: content_block_delta\n\
+             data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hallo\"}}\n\n\
+             event: error\n\
+             data: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+        )
+        .await;
+        let client = reqwest::Client::new();
+
+        let error = translate_stream(&client, "Hello", "en", "de", &settings(&base_url), &|_| {})
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Anthropic API Error: Overloaded");
+    }
+
+    #[tokio::test]
+    async fn lists_models() {
+        let (base_url, server) =
+            serve("200 OK", include_str!("fixtures/anthropic_models.json")).await;
+        let client = reqwest::Client::new();
+
+        let models = list_models(&client, &settings(&base_url)).await.unwrap();
+        let models: Vec<_> = models
+            .iter()
+            .map(|model| (model.id.as_str(), model.name.as_str()))
+            .collect();
+        assert_eq!(
+            models,
+            [
+                ("claude-sonnet-4-5", "Claude Sonnet 4.5"),
+                ("claude-haiku-4-5", "claude-haiku-4-5")
+            ]
+        );
+        assert!(server
+            .await
+            .unwrap()
+            .starts_with("GET /v1/models?limit=1000 HTTP/1.1"));
+    }
+}
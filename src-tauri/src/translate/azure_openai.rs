@@ -0,0 +1,278 @@
+//! Azure OpenAI chat completions, addressed by deployment
+//! (`/openai/deployments/{name}/chat/completions?api-version=...`).
+//!
+//! Requests authenticate with a resource key (`api-key` header) or a
+//! Microsoft Entra ID token. Entra tokens come from an app registration
+//! (client credentials), or from the Azure CLI when no client is configured,
+//! and are kept until shortly before they expire.
+
+use std::sync::Mutex;
+
+use serde_json::json;
+
+use super::{or_default, sign, Model, TranslationSettings};
+
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+const DEFAULT_REGION: &str = "eastus";
+const SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+/// Tokens are renewed this many seconds before they expire
+const EXPIRY_MARGIN: u64 = 300;
+const LABEL: &str = "Azure OpenAI Error";
+
+struct Token {
+    /// `tenant/client` it was issued to, empty for the Azure CLI
+    account: String,
+    access_token: String,
+    expires_at: u64,
+}
+
+static TOKEN: Mutex<Option<Token>> = Mutex::new(None);
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let mut messages = Vec::new();
+    if settings.system_prompt_enabled {
+        let system_prompt = super::system_prompt(source, target, settings);
+        messages.push(json!({ "role": "system", "content": system_prompt }));
+    }
+    messages.push(json!({ "role": "user", "content": text }));
+
+    let body = json!({ "messages": messages, "temperature": 0.3 });
+    let data = chat_completion(client, settings, &body).await?;
+
+    let choice = &data["choices"][0];
+    // The prompt passed the filter but the completion didn't
+    if choice["finish_reason"].as_str() == Some("content_filter") {
+        return Err(content_filter_error(&choice["content_filter_results"]));
+    }
+    Ok(choice["message"]["content"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Translation empty.".to_string()))
+}
+
+/// The configured deployment and the model it serves, found with a one-token
+/// completion (deployments can't be listed with the data-plane API).
+pub(super) async fn list_models(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+) -> Result<Vec<Model>, String> {
+    let body = json!({
+        "messages": [{ "role": "user", "content": "Hi" }],
+        "max_tokens": 1
+    });
+    let data = chat_completion(client, settings, &body).await?;
+
+    let deployment = settings.azure_openai_deployment.clone();
+    let name = data["model"].as_str().unwrap_or(&deployment).to_string();
+    Ok(vec![Model {
+        id: deployment,
+        name,
+    }])
+}
+
+/// Posts `body` to the deployment's chat completions.
+async fn chat_completion(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    if settings.azure_openai_deployment.is_empty() {
+        return Err("Azure OpenAI deployment name is required.".to_string());
+    }
+    let url = format!(
+        "{}/openai/deployments/{}/chat/completions",
+        endpoint(settings),
+        settings.azure_openai_deployment
+    );
+    let api_version = or_default(&settings.azure_openai_api_version, DEFAULT_API_VERSION);
+    let request = client
+        .post(url)
+        .query(&[("api-version", api_version)])
+        .json(body);
+    let request = if settings.azure_openai_auth == "entra" {
+        request.bearer_auth(entra_token(client, settings).await?)
+    } else {
+        if settings.azure_openai_api_key.is_empty() {
+            return Err("Azure OpenAI API Key is required.".to_string());
+        }
+        request.header("api-key", &settings.azure_openai_api_key)
+    };
+
+    let response = crate::policy::send(request)
+        .await
+        .map_err(|e| format!("{}: {}", LABEL, e))?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let data: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+
+    if !status.is_success() {
+        let error = &data["error"];
+        // Prompts rejected by the content filter answer 400 with this code
+        if error["code"].as_str() == Some("content_filter") {
+            return Err(content_filter_error(
+                &error["innererror"]["content_filter_result"],
+            ));
+        }
+        let message = error["message"].as_str().unwrap_or(&text);
+        return Err(format!("{}: {} - {}", LABEL, status.as_u16(), message));
+    }
+    if data.is_null() {
+        return Err(format!("{}: invalid response", LABEL));
+    }
+    Ok(data)
+}
+
+/// Names the categories (hate, violence, ...) the content filter flagged.
+fn content_filter_error(results: &serde_json::Value) -> String {
+    let categories: Vec<&str> = results
+        .as_object()
+        .map(|results| {
+            results
+                .iter()
+                .filter(|(_, result)| {
+                    result["filtered"].as_bool() == Some(true)
+                        || result["detected"].as_bool() == Some(true)
+                })
+                .map(|(category, _)| category.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    if categories.is_empty() {
+        "Blocked by the Azure OpenAI content filter.".to_string()
+    } else {
+        format!(
+            "Blocked by the Azure OpenAI content filter ({}).",
+            categories.join(", ")
+        )
+    }
+}
+
+/// The resource endpoint, or the regional endpoint of `azure_openai_region`
+/// when none is set.
+fn endpoint(settings: &TranslationSettings) -> String {
+    if settings.azure_openai_endpoint.is_empty() {
+        format!(
+            "https://{}.api.cognitive.microsoft.com",
+            or_default(&settings.azure_openai_region, DEFAULT_REGION)
+        )
+    } else {
+        settings
+            .azure_openai_endpoint
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
+
+// --- Entra ID ---
+
+async fn entra_token(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let account = if settings.azure_openai_client_id.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "{}/{}",
+            settings.azure_openai_tenant_id, settings.azure_openai_client_id
+        )
+    };
+    let now = sign::timestamp();
+    if let Some(token) = TOKEN.lock().unwrap().as_ref() {
+        if token.account == account && token.expires_at > now + EXPIRY_MARGIN {
+            return Ok(token.access_token.clone());
+        }
+    }
+
+    let (access_token, expires_at) = if account.is_empty() {
+        azure_cli_token().await?
+    } else {
+        client_credentials_token(client, settings, now).await?
+    };
+    *TOKEN.lock().unwrap() = Some(Token {
+        account,
+        access_token: access_token.clone(),
+        expires_at,
+    });
+    Ok(access_token)
+}
+
+/// Token of an app registration with a client secret.
+async fn client_credentials_token(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+    now: u64,
+) -> Result<(String, u64), String> {
+    if settings.azure_openai_tenant_id.is_empty() || settings.azure_openai_client_secret.is_empty()
+    {
+        return Err("Entra ID tenant ID and client secret are required.".to_string());
+    }
+    let request = client
+        .post(format!(
+            "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+            settings.azure_openai_tenant_id
+        ))
+        .form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", settings.azure_openai_client_id.as_str()),
+            (
+                "client_secret",
+                settings.azure_openai_client_secret.as_str(),
+            ),
+            ("scope", SCOPE),
+        ]);
+    let data = super::send_json(request, "Entra ID Error").await?;
+    let access_token = data["access_token"]
+        .as_str()
+        .ok_or("Entra ID Error: no access token")?
+        .to_string();
+    Ok((
+        access_token,
+        now + data["expires_in"].as_u64().unwrap_or(3600),
+    ))
+}
+
+/// Token of the user signed in to the Azure CLI (`az login`).
+async fn azure_cli_token() -> Result<(String, u64), String> {
+    let output = tokio::process::Command::new("az")
+        .args([
+            "account",
+            "get-access-token",
+            "--scope",
+            SCOPE,
+            "--output",
+            "json",
+        ])
+        .output()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to run the Azure CLI: {}. Install it and run `az login`, or configure an app registration.",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "Azure CLI: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let data: serde_json::Value =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Azure CLI: {}", e))?;
+    let access_token = data["accessToken"]
+        .as_str()
+        .ok_or("Azure CLI: no access token")?
+        .to_string();
+    // `expires_on` (Unix time) is only in recent versions of the CLI
+    let expires_at = data["expires_on"]
+        .as_u64()
+        .unwrap_or_else(|| sign::timestamp() + 1800);
+    Ok((access_token, expires_at))
+}
@@ -0,0 +1,93 @@
+//! Baidu Fanyi (general text translation API), reachable from mainland China.
+
+use md5::{Digest, Md5};
+
+use super::{send_json, sign, TranslationSettings};
+
+const API_URL: &str = "https://fanyi-api.baidu.com/api/trans/vip/translate";
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.baidu_app_id.is_empty() || settings.baidu_secret_key.is_empty() {
+        return Err("Baidu App ID and Secret Key are required.".to_string());
+    }
+
+    let salt = sign::nonce();
+    let signature = signature(
+        &settings.baidu_app_id,
+        text,
+        &salt,
+        &settings.baidu_secret_key,
+    );
+    let params = [
+        ("q", text),
+        ("from", map_lang_code(source)),
+        ("to", map_lang_code(target)),
+        ("appid", &settings.baidu_app_id),
+        ("salt", &salt),
+        ("sign", &signature),
+    ];
+
+    let request = client.post(API_URL).form(&params);
+    let data = send_json(request, "Baidu Translate Error").await?;
+
+    // Failures still answer 200, with an error code
+    if let Some(code) = data["error_code"].as_str().filter(|code| *code != "52000") {
+        return Err(format!(
+            "Baidu Translate Error: {} - {}",
+            code,
+            data["error_msg"].as_str().unwrap_or_default()
+        ));
+    }
+
+    // One result per line of the input
+    let lines: Vec<&str> = data["trans_result"]
+        .as_array()
+        .map(|results| results.iter().filter_map(|r| r["dst"].as_str()).collect())
+        .unwrap_or_default();
+    if lines.is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+/// `MD5(appid + q + salt + key)`, lowercase hex.
+fn signature(app_id: &str, text: &str, salt: &str, secret_key: &str) -> String {
+    let mut md5 = Md5::new();
+    md5.update(app_id);
+    md5.update(text);
+    md5.update(salt);
+    md5.update(secret_key);
+    hex::encode(md5.finalize())
+}
+
+fn map_lang_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh",
+        "zh-TW" => "cht",
+        "ja" => "jp",
+        "ko" => "kor",
+        "fr" => "fra",
+        "es" => "spa",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_matches_docs() {
+        // Example of the "general translation" API docs
+        assert_eq!(
+            signature("2015063000000001", "apple", "1435660288", "12345678"),
+            "f89f9594663708c1605f3d736d01d2d4"
+        );
+    }
+}
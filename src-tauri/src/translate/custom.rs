@@ -0,0 +1,512 @@
+//! User-defined HTTP providers, declared in TOML or JSON files of the
+//! `providers` config directory instead of code. `deepl-proxy.toml` becomes
+//! the provider `custom:deepl-proxy`:
+//!
+//! ```toml
+//! name = "Internal MT"
+//! url = "https://mt.example.com/v1/translate"
+//! method = "POST"                   # default
+//! key = "env:INTERNAL_MT_KEY"       # secret reference, see `secrets`
+//! headers = { Authorization = "Bearer {{key}}", Content-Type = "application/json" }
+//! body = '{"q": "{{text}}", "from": "{{source}}", "to": "{{target}}"}'
+//! response = "$.data.translations[0].text"
+//! error = "$.error.message"
+//!
+//! [languages]                       # app code -> API code
+//! zh-CN = "zh"
+//! auto = ""
+//! ```
+//!
+//! Placeholders are escaped for where they appear: percent-encoded in the
+//! URL and in form bodies, JSON-escaped in JSON bodies. Without `response`
+//! the whole response body is the translation.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the provider IDs
+pub const PREFIX: &str = "custom:";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    name: String,
+    url: String,
+    #[serde(default = "default_method")]
+    method: String,
+    /// Secret reference (`env:`, `keyring:`, `local:`) for `{{key}}`
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+    /// Path of the translation in the JSON response
+    #[serde(default)]
+    response: Option<String>,
+    /// Path of an error message in the JSON response
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+/// A loaded definition, as listed to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomProvider {
+    pub id: String,
+    pub name: String,
+    /// Host of the URL, to tell providers apart
+    pub host: String,
+}
+
+struct Loaded {
+    id: String,
+    definition: Definition,
+    key: Option<String>,
+}
+
+static PROVIDERS: Mutex<Vec<Loaded>> = Mutex::new(Vec::new());
+
+/// Replaces the loaded providers with the definitions in `dir`, resolving
+/// their keys with `resolve`. Returns the providers and the errors of the
+/// files that were skipped.
+pub fn load(
+    dir: &Path,
+    resolve: impl Fn(&str) -> Result<String, String>,
+) -> (Vec<CustomProvider>, Vec<String>) {
+    let mut loaded = Vec::new();
+    let mut errors = Vec::new();
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        if extension != "toml" && extension != "json" {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let file = path.display();
+
+        let definition = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                if extension == "toml" {
+                    toml::from_str::<Definition>(&contents).map_err(|e| e.to_string())
+                } else {
+                    serde_json::from_str::<Definition>(&contents).map_err(|e| e.to_string())
+                }
+            });
+        let definition = match definition.and_then(|d| check(&d).map(|()| d)) {
+            Ok(definition) => definition,
+            Err(e) => {
+                errors.push(format!("{}: {}", file, e));
+                continue;
+            }
+        };
+        let key = match &definition.key {
+            Some(reference) => match resolve(reference) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    errors.push(format!("{}: {}", file, e));
+                    continue;
+                }
+            },
+            None => None,
+        };
+        loaded.push(Loaded {
+            id: format!("{}{}", PREFIX, stem),
+            definition,
+            key,
+        });
+    }
+
+    let providers = loaded
+        .iter()
+        .map(|provider| CustomProvider {
+            id: provider.id.clone(),
+            name: provider.definition.name.clone(),
+            host: reqwest::Url::parse(&provider.definition.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default(),
+        })
+        .collect();
+    *PROVIDERS.lock().unwrap() = loaded;
+    (providers, errors)
+}
+
+/// Whether `id` is a loaded custom provider.
+pub fn exists(id: &str) -> bool {
+    PROVIDERS.lock().unwrap().iter().any(|p| p.id == id)
+}
+
+/// `(id, name)` of the loaded providers.
+pub fn providers() -> Vec<(String, String)> {
+    PROVIDERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|p| (p.id.clone(), p.definition.name.clone()))
+        .collect()
+}
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    id: &str,
+    text: &str,
+    source: &str,
+    target: &str,
+) -> Result<String, String> {
+    let (definition, key) = {
+        let providers = PROVIDERS.lock().unwrap();
+        let provider = providers
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Custom provider '{}' is not loaded", id))?;
+        (provider.definition.clone(), provider.key.clone())
+    };
+    let label = format!("{} Error", definition.name);
+
+    let language = |code: &str| {
+        definition
+            .languages
+            .get(code)
+            .cloned()
+            .unwrap_or_else(|| code.to_string())
+    };
+    let values = [
+        ("text", text.to_string()),
+        ("source", language(source)),
+        ("target", language(target)),
+        ("key", key.unwrap_or_default()),
+    ];
+    let content_type = definition
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let url = fill(&definition.url, &values, Escape::Url);
+    let method = reqwest::Method::from_bytes(definition.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("{}: invalid method {}", label, definition.method))?;
+    let mut request = client.request(method, url);
+    for (name, value) in &definition.headers {
+        request = request.header(name, fill(value, &values, Escape::None));
+    }
+    if let Some(body) = &definition.body {
+        let escape = if content_type.contains("json") {
+            Escape::Json
+        } else if content_type.contains("x-www-form-urlencoded") {
+            Escape::Url
+        } else {
+            Escape::None
+        };
+        request = request.body(fill(body, &values, escape));
+    }
+
+    let response = crate::policy::send(request)
+        .await
+        .map_err(|e| format!("{}: {}", label, e))?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let data = serde_json::from_str::<serde_json::Value>(&body).ok();
+
+    // A message at the error path is an error even with a success status
+    let error = match (&definition.error, &data) {
+        (Some(path), Some(data)) => select(data, path).and_then(message),
+        _ => None,
+    };
+    if let Some(error) = error {
+        return Err(format!("{}: {}", label, error));
+    }
+    if !status.is_success() {
+        return Err(format!("{}: {} - {}", label, status.as_u16(), body));
+    }
+
+    let translation = match &definition.response {
+        Some(path) => {
+            let data = data.ok_or_else(|| format!("{}: response is not JSON", label))?;
+            select(&data, path)
+                .and_then(message)
+                .ok_or_else(|| format!("{}: no translation at {}", label, path))?
+        }
+        None => body,
+    };
+    if translation.trim().is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(translation.trim().to_string())
+}
+
+// --- Helpers ---
+
+/// Rejects definitions that can't work, before they are used.
+fn check(definition: &Definition) -> Result<(), String> {
+    if definition.name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    let url = fill(&definition.url, &[], Escape::None);
+    reqwest::Url::parse(&url).map_err(|e| format!("invalid url: {}", e))?;
+    for path in [&definition.response, &definition.error]
+        .into_iter()
+        .flatten()
+    {
+        parse_path(path)?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Escape {
+    None,
+    Url,
+    Json,
+}
+
+/// Replaces the `{{name}}` placeholders of `template` with `values`, in one
+/// pass so that placeholders in the values (the text) are left alone.
+fn fill(template: &str, values: &[(&str, String)], escape: Escape) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let name = after[..end].trim();
+            values
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&escaped(value, escape));
+                rest = &after[end + 2..];
+            }
+            None => {
+                filled.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+fn escaped(value: &str, escape: Escape) -> String {
+    match escape {
+        Escape::None => value.to_string(),
+        Escape::Url => percent_encode(value),
+        // A JSON string without its quotes, the template has them
+        Escape::Json => {
+            let quoted = serde_json::Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+    }
+}
+
+/// Percent-encodes all but the unreserved characters of RFC 3986.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+enum Step {
+    Field(String),
+    Index(usize),
+}
+
+/// Parses a JSONPath of fields and indices: `$.data.items[0].text`,
+/// `$['translated text']`, or without the `$`.
+fn parse_path(path: &str) -> Result<Vec<Step>, String> {
+    let invalid = || format!("invalid path '{}'", path);
+    let mut steps = Vec::new();
+    let mut rest = path.trim().strip_prefix('$').unwrap_or(path.trim());
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = after[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            steps.push(match quoted {
+                Some(field) => Step::Field(field.to_string()),
+                None => Step::Index(inner.parse().map_err(|_| invalid())?),
+            });
+            rest = &after[end + 1..];
+        } else {
+            let after = rest.strip_prefix('.').unwrap_or(rest);
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            steps.push(Step::Field(after[..end].to_string()));
+            rest = &after[end..];
+        }
+    }
+    Ok(steps)
+}
+
+fn select<'a>(data: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    parse_path(path)
+        .ok()?
+        .iter()
+        .try_fold(data, |value, step| match step {
+            Step::Field(name) => value.get(name),
+            Step::Index(index) => value.get(index),
+        })
+}
+
+/// Text of a selected value; strings as is, other values as JSON. Nothing
+/// for `null` and empty strings.
+fn message(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) if s.is_empty() => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn values(text: &str) -> [(&'static str, String); 4] {
+        [
+            ("text", text.to_string()),
+            ("source", "en".to_string()),
+            ("target", "zh".to_string()),
+            ("key", "secret".to_string()),
+        ]
+    }
+
+    #[test]
+    fn escapes_json_bodies() {
+        let body = fill(
+            r#"{"q": "{{text}}", "to": "{{ target }}"}"#,
+            &values("Say \"hi\"\n\\ \u{1}"),
+            Escape::Json,
+        );
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body, json!({ "q": "Say \"hi\"\n\\ \u{1}", "to": "zh" }));
+    }
+
+    #[test]
+    fn escapes_urls_and_forms() {
+        assert_eq!(
+            fill(
+                "https://mt.example/t?q={{text}}&to={{target}}",
+                &values("a&b=c d/é"),
+                Escape::Url
+            ),
+            "https://mt.example/t?q=a%26b%3Dc%20d%2F%C3%A9&to=zh"
+        );
+        assert_eq!(percent_encode("AZaz09-_.~"), "AZaz09-_.~");
+        assert_eq!(percent_encode("+%#?"), "%2B%25%23%3F");
+    }
+
+    #[test]
+    fn keeps_header_values_as_is() {
+        assert_eq!(
+            fill("Bearer {{key}}", &values(""), Escape::None),
+            "Bearer secret"
+        );
+        assert_eq!(fill("{{text}}", &values("a b&c"), Escape::None), "a b&c");
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_in_values() {
+        assert_eq!(
+            fill(
+                "{{text}} -> {{target}}",
+                &values("{{key}} {{target}}"),
+                Escape::None
+            ),
+            "{{key}} {{target}} -> zh"
+        );
+        assert_eq!(
+            fill("{{text}}", &values("{{key}}"), Escape::Json),
+            "{{key}}"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders() {
+        assert_eq!(
+            fill("{{unknown}} {{text", &values("x"), Escape::None),
+            "{{unknown}} {{text"
+        );
+        assert_eq!(fill("{{}}{{text}}", &values("x"), Escape::None), "{{}}x");
+    }
+
+    #[test]
+    fn selects_paths() {
+        let data = json!({
+            "data": { "translations": [{ "text": "Hallo" }, { "text": "Welt" }] },
+            "translated text": "Hi",
+            "nested": [[1, 2], [3]]
+        });
+        let text = |path| select(&data, path).and_then(message);
+
+        assert_eq!(
+            text("$.data.translations[0].text").as_deref(),
+            Some("Hallo")
+        );
+        assert_eq!(text("data.translations[1].text").as_deref(), Some("Welt"));
+        assert_eq!(text("$['translated text']").as_deref(), Some("Hi"));
+        assert_eq!(
+            text("$[\"data\"].translations[ 0 ]['text']").as_deref(),
+            Some("Hallo")
+        );
+        assert_eq!(text("$.nested[0][1]").as_deref(), Some("2"));
+        assert_eq!(text("$.nested[1]").as_deref(), Some("[3]"));
+        assert_eq!(text("$").as_deref(), Some(data.to_string().as_str()));
+    }
+
+    #[test]
+    fn misses_absent_paths() {
+        let data = json!({ "data": { "items": [{ "text": "" }], "none": null } });
+        assert!(select(&data, "$.data.missing").is_none());
+        assert!(select(&data, "$.data.items[3]").is_none());
+        assert!(select(&data, "$.data.items.text").is_none());
+        assert!(select(&data, "$.data[0]").is_none());
+        // Present but empty is no message either
+        assert!(select(&data, "$.data.items[0].text")
+            .and_then(message)
+            .is_none());
+        assert!(select(&data, "$.data.none").and_then(message).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        for path in ["$.data[", "$.data[x]", "$..data", "$.data.", "$[-1]"] {
+            assert!(parse_path(path).is_err(), "{}", path);
+        }
+        assert!(parse_path("$.data.items[0]").is_ok());
+    }
+}
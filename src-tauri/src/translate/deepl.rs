@@ -0,0 +1,54 @@
+use super::{send_json, TranslationSettings};
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.deepl_api_key.is_empty() {
+        return Err("DeepL API Key is required.".to_string());
+    }
+
+    let url = if settings.deepl_api_key.ends_with(":fx") {
+        "https://api-free.deepl.com/v2/translate"
+    } else {
+        "https://api.deepl.com/v2/translate"
+    };
+
+    // DeepL wants EN-US / ZH style codes rather than en / zh-CN
+    let target_lang = match target.to_uppercase().as_str() {
+        "EN" => "EN-US".to_string(),
+        "ZH-CN" => "ZH".to_string(),
+        other => other.to_string(),
+    };
+
+    let mut params = vec![("text", text.to_string()), ("target_lang", target_lang)];
+    if source != "auto" {
+        let source_lang = source.split('-').next().unwrap_or(source).to_uppercase();
+        params.push(("source_lang", source_lang));
+    }
+    // The `prefer_` values fall back to the default for target languages
+    // without formality instead of failing
+    match settings.formality.as_str() {
+        "formal" => params.push(("formality", "prefer_more".to_string())),
+        "informal" => params.push(("formality", "prefer_less".to_string())),
+        _ => {}
+    }
+
+    let request = client
+        .post(url)
+        .header(
+            "Authorization",
+            format!("DeepL-Auth-Key {}", settings.deepl_api_key),
+        )
+        .form(&params);
+
+    let data = send_json(request, "DeepL Error").await?;
+
+    Ok(data["translations"][0]["text"]
+        .as_str()
+        .unwrap_or("Translation empty.")
+        .to_string())
+}
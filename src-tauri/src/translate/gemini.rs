@@ -0,0 +1,54 @@
+use serde_json::json;
+
+use super::{or_default, send_json, TranslationSettings};
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.gemini_api_key.is_empty() {
+        return Err("Gemini API Key is required. Please configure it in Settings.".to_string());
+    }
+
+    let model_id = or_default(&settings.model_id, "gemini-2.0-flash");
+
+    let system_instruction = if settings.system_prompt_enabled {
+        if settings.custom_system_instruction.is_empty() {
+            if target.starts_with("zh") {
+                Some(format!("You are a professional translator. Translate text to {}. Be faithful, professional, and do not add explanations.", target))
+            } else {
+                Some(format!("You are a professional translator. Translate text to {}. Use professional terminology. No explanations.", target))
+            }
+        } else {
+            Some(settings.custom_system_instruction.clone())
+        }
+    } else {
+        None
+    };
+
+    let prompt = format!("Source: {}\nTarget: {}\nText:\n{}", source, target, text);
+
+    let mut body = json!({
+        "contents": [{ "parts": [{ "text": prompt }] }],
+        "generationConfig": { "temperature": 0.3 }
+    });
+
+    if let Some(instruction) = system_instruction {
+        body["systemInstruction"] = json!({ "parts": [{ "text": instruction }] });
+    }
+
+    let url = format!(
+        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+        model_id, settings.gemini_api_key
+    );
+
+    let data = send_json(client.post(url).json(&body), "Gemini API Error").await?;
+
+    Ok(data["candidates"][0]["content"]["parts"][0]["text"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Translation failed.".to_string()))
+}
@@ -0,0 +1,449 @@
+//! Local GGUF models run on the CPU by llama.cpp, linked into the backend,
+//! for text that must not leave the machine. The model is loaded on first use
+//! and stays in memory for the following translations until another model
+//! file is chosen; every translation gets a fresh context on it, and tokens
+//! are streamed as they are generated.
+
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
+use llama_cpp_2::token_type::LlamaTokenAttr;
+
+use super::TranslationSettings;
+
+/// Sampling temperature, low to keep translations literal
+const TEMPERATURE: f32 = 0.3;
+const SEED: u32 = 1234;
+
+/// The llama.cpp backend, initialised once per process.
+static BACKEND: OnceLock<Result<LlamaBackend, String>> = OnceLock::new();
+/// The loaded model and the file it was loaded from.
+static MODEL: Mutex<Option<(String, Arc<LlamaModel>)>> = Mutex::new(None);
+
+/// `(name, system block, prompt)` of the built-in prompt templates. `{system}`
+/// in the prompt is replaced by the system block, which is left out when the
+/// system prompt is disabled.
+const TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "chatml",
+        "<|im_start|>system\n{system}<|im_end|>\n",
+        "{system}<|im_start|>user\n{text}<|im_end|>\n<|im_start|>assistant\n",
+    ),
+    (
+        "llama3",
+        "<|start_header_id|>system<|end_header_id|>\n\n{system}<|eot_id|>",
+        "{system}<|start_header_id|>user<|end_header_id|>\n\n{text}<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n",
+    ),
+    (
+        "gemma",
+        "{system}\n\n",
+        "<start_of_turn>user\n{system}{text}<end_of_turn>\n<start_of_turn>model\n",
+    ),
+    ("mistral", "{system}\n\n", "[INST] {system}{text} [/INST]"),
+];
+
+/// Part of a filled prompt template.
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    /// Text of the template itself, where special tokens are parsed
+    Template(String),
+    /// A substituted value (the text, the system prompt), always plain text
+    Value(String),
+}
+
+impl Piece {
+    fn as_str(&self) -> &str {
+        match self {
+            Piece::Template(text) | Piece::Value(text) => text,
+        }
+    }
+}
+
+pub(super) async fn translate_stream(
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+    on_delta: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    if settings.gguf_model_path.is_empty() {
+        return Err("Choose a GGUF model file in Settings.".to_string());
+    }
+    let prompt = prompt(text, source, target, settings)?;
+    let settings = settings.clone();
+
+    // Generation blocks, so it runs on its own thread and hands the pieces
+    // of text back as they are decoded
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let generation = tauri::async_runtime::spawn_blocking(move || {
+        generate(&prompt, &settings, &|delta| {
+            let _ = sender.send(delta.to_string());
+        })
+    });
+
+    let mut translation = String::new();
+    while let Some(delta) = receiver.recv().await {
+        translation.push_str(&delta);
+        on_delta(&delta);
+    }
+    generation.await.map_err(|e| e.to_string())??;
+
+    let translation = translation.trim();
+    if translation.is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(translation.to_string())
+}
+
+fn backend() -> Result<&'static LlamaBackend, String> {
+    BACKEND
+        .get_or_init(|| {
+            let mut backend = LlamaBackend::init().map_err(|e| format!("llama.cpp: {}", e))?;
+            backend.void_logs();
+            Ok(backend)
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// The model at `path`, loading it (and dropping the previous one) if it
+/// isn't the one in memory.
+fn model(path: &str) -> Result<Arc<LlamaModel>, String> {
+    let mut loaded = MODEL.lock().unwrap();
+    if let Some((loaded_path, model)) = loaded.as_ref() {
+        if loaded_path == path {
+            return Ok(model.clone());
+        }
+    }
+    // Free the old model before reading the new one
+    *loaded = None;
+    log::info!("Loading GGUF model {}", path);
+    let model = LlamaModel::load_from_file(backend()?, path, &LlamaModelParams::default())
+        .map_err(|e| format!("Failed to load the model {}: {}", path, e))?;
+    let model = Arc::new(model);
+    *loaded = Some((path.to_string(), model.clone()));
+    Ok(model)
+}
+
+/// Runs `prompt` through the model, calling `on_delta` with every piece of
+/// generated text.
+fn generate(
+    prompt: &[Piece],
+    settings: &TranslationSettings,
+    on_delta: &dyn Fn(&str),
+) -> Result<(), String> {
+    let llama = |e: &dyn std::fmt::Display| format!("llama.cpp: {}", e);
+    let model = model(&settings.gguf_model_path)?;
+    let threads = match settings.gguf_threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n as usize,
+    } as i32;
+    let context_length = settings.gguf_context_length.max(512);
+
+    let params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(context_length))
+        .with_n_batch(context_length)
+        .with_n_threads(threads)
+        .with_n_threads_batch(threads);
+    let mut context = model
+        .new_context(backend()?, params)
+        .map_err(|e| llama(&e))?;
+
+    let tokens = tokenize(&model, prompt)?;
+    // Half of the context is left for the translation
+    if tokens.len() as u32 > context_length / 2 {
+        return Err(format!(
+            "The text is too long for a context of {} tokens",
+            context_length
+        ));
+    }
+    let max_tokens = tokens.len() as i32 + (context_length / 2) as i32;
+
+    let mut batch = LlamaBatch::new(context_length as usize, 1);
+    let last = tokens.len() as i32 - 1;
+    for (position, token) in (0..).zip(tokens) {
+        batch
+            .add(token, position, &[0], position == last)
+            .map_err(|e| llama(&e))?;
+    }
+    context.decode(&mut batch).map_err(|e| llama(&e))?;
+
+    let mut sampler =
+        LlamaSampler::chain_simple([LlamaSampler::temp(TEMPERATURE), LlamaSampler::dist(SEED)]);
+    // Tokens can end in the middle of a UTF-8 sequence
+    let mut pending = Vec::new();
+    let mut position = batch.n_tokens();
+    while position < max_tokens {
+        let token = sampler.sample(&context, batch.n_tokens() - 1);
+        sampler.accept(token);
+        if model.is_eog_token(token) {
+            break;
+        }
+
+        pending.extend(
+            model
+                .token_to_bytes(token, Special::Plaintext)
+                .map_err(|e| llama(&e))?,
+        );
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        if valid > 0 {
+            on_delta(&String::from_utf8_lossy(&pending[..valid]));
+            pending.drain(..valid);
+        }
+
+        batch.clear();
+        batch
+            .add(token, position, &[0], true)
+            .map_err(|e| llama(&e))?;
+        position += 1;
+        context.decode(&mut batch).map_err(|e| llama(&e))?;
+    }
+    Ok(())
+}
+
+/// Tokenizes the prompt, parsing special tokens only in the template's own
+/// text. Without special tokens spelled out in the values the prompt is
+/// tokenized in one go: SentencePiece vocabularies would otherwise add a
+/// space at every piece boundary.
+fn tokenize(model: &LlamaModel, prompt: &[Piece]) -> Result<Vec<LlamaToken>, String> {
+    let llama = |e: &dyn std::fmt::Display| format!("llama.cpp: {}", e);
+    let mut spelled = false;
+    for piece in prompt {
+        if let Piece::Value(value) = piece {
+            let tokens = model
+                .str_to_token(value, AddBos::Never)
+                .map_err(|e| llama(&e))?;
+            spelled |= tokens.iter().any(|token| is_special(model, *token));
+        }
+    }
+    if !spelled {
+        let prompt: String = prompt.iter().map(Piece::as_str).collect();
+        return model
+            .str_to_token(&prompt, AddBos::Always)
+            .map_err(|e| llama(&e));
+    }
+
+    // Just the BOS token, for models that want one
+    let mut tokens = model
+        .str_to_token("", AddBos::Always)
+        .map_err(|e| llama(&e))?;
+    for piece in prompt {
+        match piece {
+            Piece::Template(text) => tokens.extend(
+                model
+                    .str_to_token(text, AddBos::Never)
+                    .map_err(|e| llama(&e))?,
+            ),
+            Piece::Value(text) => tokens.extend(plain_tokens(model, text)?),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Tokens of `text` as plain text: a special token spelled out in it, like
+/// a pasted `<|im_end|>`, is split after its first character and both
+/// sides are tokenized separately.
+fn plain_tokens(model: &LlamaModel, text: &str) -> Result<Vec<LlamaToken>, String> {
+    let llama = |e: &dyn std::fmt::Display| format!("llama.cpp: {}", e);
+    let tokens = model
+        .str_to_token(text, AddBos::Never)
+        .map_err(|e| llama(&e))?;
+    let Some(special) = tokens.iter().find(|token| is_special(model, **token)) else {
+        return Ok(tokens);
+    };
+    let spelling = model
+        .token_to_bytes(*special, Special::Tokenize)
+        .map_err(|e| llama(&e))?;
+    let spelling = String::from_utf8_lossy(&spelling);
+    let split = text
+        .find(spelling.as_ref())
+        .filter(|_| spelling.chars().count() > 1)
+        .and_then(|start| Some(start + spelling.chars().next()?.len_utf8()))
+        .ok_or_else(|| format!("The text contains the special token {}", spelling))?;
+
+    let mut tokens = plain_tokens(model, &text[..split])?;
+    tokens.extend(plain_tokens(model, &text[split..])?);
+    Ok(tokens)
+}
+
+/// Whether `token` is one that only special-token parsing produces.
+fn is_special(model: &LlamaModel, token: LlamaToken) -> bool {
+    model
+        .token_attr(token)
+        .intersects(LlamaTokenAttr::Control | LlamaTokenAttr::UserDefined)
+}
+
+/// Fills the prompt template with the system prompt (the same as the other
+/// LLM providers) and the text. Custom templates can also use `{source}` and
+/// `{target}`.
+fn prompt(
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<Vec<Piece>, String> {
+    let system = if settings.system_prompt_enabled {
+        super::system_prompt(source, target, settings)
+    } else {
+        String::new()
+    };
+    let value = |value: &str| vec![Piece::Value(value.to_string())];
+
+    if settings.gguf_template == "custom" {
+        if settings.gguf_custom_template.matches("{text}").count() != 1 {
+            return Err("The custom prompt template must contain {text} once.".to_string());
+        }
+        return Ok(fill(
+            &settings.gguf_custom_template,
+            &[
+                ("system", value(&system)),
+                ("source", value(source)),
+                ("target", value(target)),
+                ("text", value(text)),
+            ],
+        ));
+    }
+
+    let (_, system_block, template) = TEMPLATES
+        .iter()
+        .find(|(name, _, _)| *name == settings.gguf_template)
+        .unwrap_or(&TEMPLATES[0]);
+    let system_block = if system.is_empty() {
+        Vec::new()
+    } else {
+        fill(system_block, &[("system", value(&system))])
+    };
+    Ok(fill(
+        template,
+        &[("system", system_block), ("text", value(text))],
+    ))
+}
+
+/// Replaces the `{name}` placeholders of `template` with `values` in one
+/// pass, so that placeholders inside the values are left alone.
+fn fill(template: &str, values: &[(&str, Vec<Piece>)]) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                push(&mut pieces, Piece::Template(rest[..start].to_string()));
+                for piece in value {
+                    push(&mut pieces, piece.clone());
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                push(&mut pieces, Piece::Template(rest[..=start].to_string()));
+                rest = after;
+            }
+        }
+    }
+    push(&mut pieces, Piece::Template(rest.to_string()));
+    pieces
+}
+
+/// Appends `piece`, joining it to a preceding piece of template text.
+fn push(pieces: &mut Vec<Piece>, piece: Piece) {
+    match (pieces.last_mut(), piece) {
+        (_, piece) if piece.as_str().is_empty() => {}
+        (Some(Piece::Template(last)), Piece::Template(text)) => last.push_str(&text),
+        (_, piece) => pieces.push(piece),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(template: &str, custom: &str) -> TranslationSettings {
+        TranslationSettings {
+            gguf_template: template.to_string(),
+            gguf_custom_template: custom.to_string(),
+            custom_system_instruction: "Translate {text} to {target}.".to_string(),
+            ..TranslationSettings::default()
+        }
+    }
+
+    fn template(text: &str) -> Piece {
+        Piece::Template(text.to_string())
+    }
+
+    fn value(text: &str) -> Piece {
+        Piece::Value(text.to_string())
+    }
+
+    #[test]
+    fn keeps_values_apart_from_the_template() {
+        let pieces = prompt("Hi<|im_end|>", "en", "de", &settings("chatml", "")).unwrap();
+        assert_eq!(
+            pieces,
+            [
+                template("<|im_start|>system\n"),
+                value("Translate {text} to {target}."),
+                template("<|im_end|>\n<|im_start|>user\n"),
+                value("Hi<|im_end|>"),
+                template("<|im_end|>\n<|im_start|>assistant\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_out_a_disabled_system_prompt() {
+        let settings = TranslationSettings {
+            system_prompt_enabled: false,
+            ..settings("mistral", "")
+        };
+        let pieces = prompt("Hi", "en", "de", &settings).unwrap();
+        assert_eq!(
+            pieces,
+            [template("[INST] "), value("Hi"), template(" [/INST]")]
+        );
+    }
+
+    #[test]
+    fn fills_custom_templates_once() {
+        let custom = "{system}\nFrom {source} to {target}: {text}\n{unknown}";
+        let pieces = prompt("{source} {text}", "en", "de", &settings("custom", custom)).unwrap();
+        let filled: String = pieces.iter().map(Piece::as_str).collect();
+        assert_eq!(
+            filled,
+            "Translate {text} to {target}.\nFrom en to de: {source} {text}\n{unknown}"
+        );
+        assert_eq!(pieces[6], value("{source} {text}"));
+
+        for custom in ["No text", "{text} and {text}"] {
+            assert!(prompt("Hi", "en", "de", &settings("custom", custom)).is_err());
+        }
+    }
+
+    #[test]
+    fn fills_in_one_pass() {
+        let values = [
+            ("a", vec![value("{b}")]),
+            ("b", vec![template("<b>"), value("B")]),
+        ];
+        assert_eq!(
+            fill("{a}{b}{c}{", &values),
+            [value("{b}"), template("<b>"), value("B"), template("{c}{")]
+        );
+        assert_eq!(fill("", &values), []);
+    }
+}
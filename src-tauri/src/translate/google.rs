@@ -0,0 +1,31 @@
+use super::send_json;
+
+/// Google Translate (free GTX endpoint). Rate-limited, no key required.
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+) -> Result<String, String> {
+    let request = client
+        .get("https://translate.googleapis.com/translate_a/single")
+        .query(&[
+            ("client", "gtx"),
+            ("sl", source),
+            ("tl", target),
+            ("dt", "t"),
+            ("q", text),
+        ]);
+
+    let data = send_json(request, "Google Translate Failed").await?;
+
+    // GTX returns [[["Translated Text", "Original", ...], ...], ...]
+    let segments = data[0]
+        .as_array()
+        .ok_or_else(|| "Google Translate Failed: unexpected response".to_string())?;
+
+    Ok(segments
+        .iter()
+        .filter_map(|segment| segment[0].as_str())
+        .collect())
+}
@@ -0,0 +1,268 @@
+//! Google Cloud Translation (Advanced, v3), authenticated with a service
+//! account key file. The access token minted from the key (a signed JWT
+//! exchanged at the key's `token_uri`) is kept until shortly before it expires.
+
+use std::sync::Mutex;
+
+use base64::Engine;
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use serde_json::json;
+
+use super::{or_default, send_json, sign, TranslationSettings};
+
+const DEFAULT_ENDPOINT: &str = "https://translation.googleapis.com";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const SCOPE: &str = "https://www.googleapis.com/auth/cloud-translation";
+/// Tokens are renewed this many seconds before they expire
+const EXPIRY_MARGIN: u64 = 60;
+
+struct Token {
+    /// `client_email` and `token_uri` of the key it was minted with
+    account: (String, String),
+    access_token: String,
+    expires_at: u64,
+}
+
+static TOKEN: Mutex<Option<Token>> = Mutex::new(None);
+
+/// The parts of a service account key file that are used.
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    project_id: String,
+    token_uri: String,
+}
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let account = service_account(settings)?;
+    let project = or_default(&settings.google_cloud_project_id, &account.project_id);
+    if project.is_empty() {
+        return Err("Google Cloud project ID is required.".to_string());
+    }
+    let location = or_default(&settings.google_cloud_location, "global");
+    let access_token = access_token(client, &account).await?;
+
+    let parent = format!("projects/{}/locations/{}", project, location);
+    let mut body = json!({
+        "contents": [text],
+        "targetLanguageCode": target,
+        "mimeType": "text/plain",
+    });
+    // Detected when left out
+    if source != "auto" {
+        body["sourceLanguageCode"] = json!(source);
+    }
+    // Glossaries need a source language; with auto-detect the text is
+    // translated without one rather than rejected
+    if !settings.google_cloud_glossary.is_empty() && source != "auto" {
+        body["glossaryConfig"] = json!({
+            "glossary": format!("{}/glossaries/{}", parent, settings.google_cloud_glossary),
+        });
+    }
+
+    let endpoint = or_default(&settings.google_cloud_endpoint, DEFAULT_ENDPOINT);
+    let request = client
+        .post(format!(
+            "{}/v3/{}:translateText",
+            endpoint.trim_end_matches('/'),
+            parent
+        ))
+        .bearer_auth(access_token)
+        .json(&body);
+    let data = send_json(request, "Google Cloud Translation Error").await?;
+    Ok(translation(&data))
+}
+
+fn translation(data: &serde_json::Value) -> String {
+    // With a glossary both translations are returned
+    let translation = data["glossaryTranslations"][0]["translatedText"]
+        .as_str()
+        .or_else(|| data["translations"][0]["translatedText"].as_str());
+    translation.unwrap_or("Translation empty.").to_string()
+}
+
+fn service_account(settings: &TranslationSettings) -> Result<ServiceAccount, String> {
+    let path = &settings.google_cloud_credentials_path;
+    if path.is_empty() {
+        return Err("Choose a Google Cloud service account key file in Settings.".to_string());
+    }
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read the service account key {}: {}", path, e))?;
+    let key: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid service account key {}: {}", path, e))?;
+
+    let field = |name: &str| key[name].as_str().unwrap_or_default().to_string();
+    if key["type"].as_str() != Some("service_account") {
+        return Err(format!("{} is not a service account key file", path));
+    }
+    Ok(ServiceAccount {
+        client_email: field("client_email"),
+        private_key: field("private_key"),
+        project_id: field("project_id"),
+        token_uri: or_default(&field("token_uri"), DEFAULT_TOKEN_URI).to_string(),
+    })
+}
+
+/// A valid access token for `account`, minting a new one when needed.
+async fn access_token(
+    client: &reqwest::Client,
+    account: &ServiceAccount,
+) -> Result<String, String> {
+    let key = (account.client_email.clone(), account.token_uri.clone());
+    let now = sign::timestamp();
+    if let Some(token) = TOKEN.lock().unwrap().as_ref() {
+        if token.account == key && token.expires_at > now + EXPIRY_MARGIN {
+            return Ok(token.access_token.clone());
+        }
+    }
+
+    let assertion = jwt(account, now)?;
+    let request = client.post(&account.token_uri).form(&[
+        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+        ("assertion", &assertion),
+    ]);
+    let data = send_json(request, "Google Cloud Auth Error").await?;
+    let access_token = data["access_token"]
+        .as_str()
+        .ok_or("Google Cloud Auth Error: no access token")?
+        .to_string();
+
+    *TOKEN.lock().unwrap() = Some(Token {
+        account: key,
+        access_token: access_token.clone(),
+        expires_at: now + data["expires_in"].as_u64().unwrap_or(3600),
+    });
+    Ok(access_token)
+}
+
+/// The RS256 signed assertion requesting a token for `SCOPE`.
+fn jwt(account: &ServiceAccount, now: u64) -> Result<String, String> {
+    let encode = |data: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data);
+    let header = json!({ "alg": "RS256", "typ": "JWT" });
+    let claims = json!({
+        "iss": account.client_email,
+        "scope": SCOPE,
+        "aud": account.token_uri,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    let message = format!(
+        "{}.{}",
+        encode(header.to_string().as_bytes()),
+        encode(claims.to_string().as_bytes())
+    );
+
+    let key_pair = RsaKeyPair::from_pkcs8(&pem_der(&account.private_key)?)
+        .map_err(|e| format!("Invalid service account private key: {}", e))?;
+    let mut signature = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            message.as_bytes(),
+            &mut signature,
+        )
+        .map_err(|_| "Failed to sign the service account assertion".to_string())?;
+
+    Ok(format!("{}.{}", message, encode(&signature)))
+}
+
+/// The DER bytes of a PEM `PRIVATE KEY` block.
+fn pem_der(pem: &str) -> Result<Vec<u8>, String> {
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .map_err(|e| format!("Invalid service account private key: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+
+    const KEY_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/translate/fixtures/google_cloud_service_account.json"
+    );
+
+    fn test_account() -> ServiceAccount {
+        let settings = TranslationSettings {
+            google_cloud_credentials_path: KEY_FILE.to_string(),
+            ..TranslationSettings::default()
+        };
+        service_account(&settings).unwrap()
+    }
+
+    fn decode(part: &str) -> serde_json::Value {
+        let json = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(part)
+            .unwrap();
+        serde_json::from_slice(&json).unwrap()
+    }
+
+    #[test]
+    fn reads_service_account_key() {
+        let account = test_account();
+        assert_eq!(
+            account.client_email,
+            "translator@light-translator-test.iam.gserviceaccount.com"
+        );
+        assert_eq!(account.project_id, "light-translator-test");
+        assert_eq!(account.token_uri, DEFAULT_TOKEN_URI);
+    }
+
+    #[test]
+    fn jwt_has_rs256_header_and_claims() {
+        let account = test_account();
+        let jwt = jwt(&account, 1_700_000_000).unwrap();
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        assert_eq!(decode(parts[0]), json!({ "alg": "RS256", "typ": "JWT" }));
+        assert_eq!(
+            decode(parts[1]),
+            json!({
+                "iss": "translator@light-translator-test.iam.gserviceaccount.com",
+                "scope": SCOPE,
+                "aud": "https://oauth2.googleapis.com/token",
+                "iat": 1_700_000_000u64,
+                "exp": 1_700_003_600u64,
+            })
+        );
+
+        let key_pair = RsaKeyPair::from_pkcs8(&pem_der(&account.private_key).unwrap()).unwrap();
+        let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(parts[2])
+            .unwrap();
+        UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, key_pair.public().as_ref())
+            .verify(format!("{}.{}", parts[0], parts[1]).as_bytes(), &signature)
+            .expect("valid RS256 signature");
+    }
+
+    #[test]
+    fn parses_translation() {
+        let data = serde_json::from_str(include_str!("fixtures/google_cloud_translate_text.json"))
+            .unwrap();
+        assert_eq!(translation(&data), "Hallo Welt");
+    }
+
+    #[test]
+    fn prefers_glossary_translation() {
+        let data = serde_json::from_str(include_str!(
+            "fixtures/google_cloud_translate_text_glossary.json"
+        ))
+        .unwrap();
+        assert_eq!(translation(&data), "Hallo Welt, sagte LightLamp");
+    }
+}
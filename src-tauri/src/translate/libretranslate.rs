@@ -0,0 +1,145 @@
+//! LibreTranslate (Argos Translate), usually a self-hosted instance.
+
+use serde::Serialize;
+use serde_json::json;
+
+use super::{or_default, send_json, TranslationSettings};
+
+const DEFAULT_BASE_URL: &str = "http://localhost:5000";
+
+/// A language the instance supports, from `/languages`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+    pub targets: Vec<String>,
+}
+
+/// Source language guessed by `/detect`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Detection {
+    pub language: String,
+    /// 0 to 100
+    pub confidence: f64,
+}
+
+/// Translates `text`, returning the translation and up to
+/// `libretranslate_alternatives` other candidates.
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<(String, Vec<String>), String> {
+    let mut body = json!({
+        "q": text,
+        "source": language_code(source),
+        "target": language_code(target),
+        "format": if settings.libretranslate_format == "html" { "html" } else { "text" },
+    });
+    if settings.libretranslate_alternatives > 0 {
+        body["alternatives"] = json!(settings.libretranslate_alternatives);
+    }
+    with_api_key(&mut body, settings);
+
+    let request = client
+        .post(format!("{}/translate", base_url(settings)))
+        .json(&body);
+    let data = send_json(request, "LibreTranslate Error").await?;
+
+    let translation = data["translatedText"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Translation empty.".to_string());
+    let alternatives = data["alternatives"]
+        .as_array()
+        .map(|alternatives| {
+            alternatives
+                .iter()
+                .filter_map(|alternative| alternative.as_str())
+                .map(|alternative| alternative.trim().to_string())
+                .filter(|alternative| !alternative.is_empty() && *alternative != translation)
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((translation, alternatives))
+}
+
+/// Languages of the instance and the targets each can be translated to.
+pub(super) async fn languages(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+) -> Result<Vec<Language>, String> {
+    let request = client.get(format!("{}/languages", base_url(settings)));
+    let data = send_json(request, "LibreTranslate Error").await?;
+
+    Ok(data
+        .as_array()
+        .map(|languages| {
+            languages
+                .iter()
+                .filter_map(|language| {
+                    Some(Language {
+                        code: language["code"].as_str()?.to_string(),
+                        name: language["name"].as_str()?.to_string(),
+                        targets: language["targets"]
+                            .as_array()
+                            .map(|targets| {
+                                targets
+                                    .iter()
+                                    .filter_map(|target| target.as_str().map(str::to_string))
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The most likely language of `text`.
+pub(super) async fn detect(
+    client: &reqwest::Client,
+    text: &str,
+    settings: &TranslationSettings,
+) -> Result<Detection, String> {
+    let mut body = json!({ "q": text });
+    with_api_key(&mut body, settings);
+
+    let request = client
+        .post(format!("{}/detect", base_url(settings)))
+        .json(&body);
+    let data = send_json(request, "LibreTranslate Error").await?;
+
+    let best = &data[0];
+    Ok(Detection {
+        language: best["language"]
+            .as_str()
+            .ok_or("LibreTranslate Error: no language detected")?
+            .to_string(),
+        confidence: best["confidence"].as_f64().unwrap_or(0.0),
+    })
+}
+
+// --- Helpers ---
+
+fn base_url(settings: &TranslationSettings) -> &str {
+    or_default(&settings.libretranslate_base_url, DEFAULT_BASE_URL).trim_end_matches('/')
+}
+
+fn with_api_key(body: &mut serde_json::Value, settings: &TranslationSettings) {
+    if !settings.libretranslate_api_key.is_empty() {
+        body["api_key"] = json!(settings.libretranslate_api_key);
+    }
+}
+
+/// Argos codes differ from the app's for Chinese.
+fn language_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh",
+        "zh-TW" => "zt",
+        code => code,
+    }
+}
@@ -0,0 +1,50 @@
+use serde_json::json;
+
+use super::{or_default, send_json, TranslationSettings};
+
+/// Microsoft Translator (Azure Cognitive Services, API v3).
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.microsoft_subscription_key.is_empty() {
+        return Err("Microsoft Subscription Key is required.".to_string());
+    }
+
+    let mut params = vec![("api-version", "3.0"), ("to", map_lang_code(target))];
+    // Microsoft auto-detects when `from` is omitted
+    if source != "auto" {
+        params.push(("from", map_lang_code(source)));
+    }
+
+    let request = client
+        .post("https://api.cognitive.microsofttranslator.com/translate")
+        .query(&params)
+        .header(
+            "Ocp-Apim-Subscription-Key",
+            &settings.microsoft_subscription_key,
+        )
+        .header(
+            "Ocp-Apim-Subscription-Region",
+            or_default(&settings.microsoft_region, "eastus"),
+        )
+        .json(&json!([{ "Text": text }]));
+
+    let data = send_json(request, "Microsoft Translator Error").await?;
+
+    Ok(data[0]["translations"][0]["text"]
+        .as_str()
+        .unwrap_or("Translation empty.")
+        .to_string())
+}
+
+fn map_lang_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh-Hans",
+        "zh-TW" => "zh-Hant",
+        other => other,
+    }
+}
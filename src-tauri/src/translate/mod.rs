@@ -0,0 +1,492 @@
+//! Backend translation path.
+//!
+//! Mirrors the providers in `services/geminiService.ts` so that entry points
+//! which never go through the webview (D-Bus, ...) can translate with the
+//! user's current settings.
+
+mod amazon;
+mod anthropic;
+mod azure_openai;
+mod baidu;
+mod custom;
+mod deepl;
+mod gemini;
+mod gguf;
+mod google;
+mod google_cloud;
+mod libretranslate;
+mod microsoft;
+mod offline;
+mod ollama;
+mod openai;
+mod plugins;
+mod sign;
+mod tencent;
+mod youdao;
+
+use serde::{Deserialize, Serialize};
+
+pub use custom::{load as load_custom_providers, CustomProvider};
+pub use libretranslate::{Detection, Language};
+pub use ollama::{pull_model as pull_ollama_model, PullProgress};
+pub use plugins::{
+    has_postprocessors, list as list_plugins, load as load_plugins, postprocess, PluginInfo,
+};
+
+/// `(id, name)` of every provider, in the order of `PROVIDERS` in
+/// `constants.ts`.
+pub const PROVIDERS: &[(&str, &str)] = &[
+    ("gemini", "Google Gemini"),
+    ("openai", "OpenAI Compatible"),
+    ("openrouter", "OpenRouter"),
+    ("anthropic", "Anthropic Claude"),
+    ("azure_openai", "Azure OpenAI"),
+    ("ollama", "Ollama"),
+    ("gguf", "Local GGUF (llama.cpp)"),
+    ("deepl", "DeepL Translate"),
+    ("google", "Google Translate"),
+    ("microsoft", "Microsoft Translator"),
+    ("libretranslate", "LibreTranslate"),
+    ("baidu", "Baidu Translate"),
+    ("youdao", "Youdao Translate"),
+    ("tencent", "Tencent Cloud TMT"),
+    ("amazon", "Amazon Translate"),
+    ("google_cloud", "Google Cloud Translation"),
+    ("offline", "Offline (Marian)"),
+];
+
+/// `(code, name)` of the languages in `LANGUAGES` in `constants.ts`.
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("auto", "Auto Detect"),
+    ("zh-CN", "Chinese (Simplified)"),
+    ("zh-TW", "Chinese (Traditional)"),
+    ("en", "English"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("ru", "Russian"),
+];
+
+pub fn language_name(code: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+        .unwrap_or(code)
+}
+
+/// Whether `id` is a built-in provider, a loaded custom provider or an
+/// enabled translator plugin.
+pub fn is_provider(id: &str) -> bool {
+    PROVIDERS.iter().any(|(p, _)| *p == id) || custom::exists(id) || plugins::exists(id)
+}
+
+/// `(id, name)` of the loaded custom providers and enabled translator
+/// plugins.
+pub fn loaded_providers() -> Vec<(String, String)> {
+    let mut providers = custom::providers();
+    providers.extend(plugins::providers());
+    providers
+}
+
+/// Whether `provider` is a translator plugin, whose requests must not
+/// follow redirects past its allowed hosts.
+pub fn is_plugin(provider: &str) -> bool {
+    provider.starts_with(plugins::PREFIX)
+}
+
+// --- Types ---
+
+/// The provider-related subset of [`crate::settings::Settings`], flattened
+/// into it and kept in `AppState` by the settings store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranslationSettings {
+    pub provider: String,
+    // Gemini
+    pub model_id: String,
+    pub custom_system_instruction: String,
+    pub system_prompt_enabled: bool,
+    pub gemini_api_key: String,
+    // OpenAI
+    pub openai_base_url: String,
+    pub openai_api_key: String,
+    pub openai_model: String,
+    // OpenRouter
+    pub openrouter_api_key: String,
+    pub openrouter_model: String,
+    // Anthropic
+    pub anthropic_api_key: String,
+    pub anthropic_base_url: String,
+    pub anthropic_model: String,
+    // Azure OpenAI
+    /// `https://<resource>.openai.azure.com`; the regional endpoint if empty
+    pub azure_openai_endpoint: String,
+    pub azure_openai_region: String,
+    pub azure_openai_deployment: String,
+    pub azure_openai_api_version: String,
+    /// `key` or `entra`
+    pub azure_openai_auth: String,
+    pub azure_openai_api_key: String,
+    /// Entra ID app registration; the Azure CLI login is used without one
+    pub azure_openai_tenant_id: String,
+    pub azure_openai_client_id: String,
+    pub azure_openai_client_secret: String,
+    // Ollama
+    pub ollama_base_url: String,
+    pub ollama_model: String,
+    /// Duration ("5m") or seconds (-1: forever); the daemon's default if empty
+    pub ollama_keep_alive: String,
+    /// Context window in tokens; the model's default if 0
+    pub ollama_num_ctx: u32,
+    // GGUF (llama.cpp)
+    pub gguf_model_path: String,
+    /// `chatml`, `llama3`, `gemma`, `mistral` or `custom`
+    pub gguf_template: String,
+    /// Used with the `custom` template: `{system}`, `{text}`, `{source}`,
+    /// `{target}`
+    pub gguf_custom_template: String,
+    /// CPU threads; all cores if 0
+    pub gguf_threads: u32,
+    pub gguf_context_length: u32,
+    // DeepL
+    pub deepl_api_key: String,
+    /// `formal`, `informal`, or the provider's default if empty (DeepL,
+    /// Amazon Translate)
+    pub formality: String,
+    // Microsoft
+    pub microsoft_subscription_key: String,
+    pub microsoft_region: String,
+    // LibreTranslate
+    pub libretranslate_base_url: String,
+    pub libretranslate_api_key: String,
+    /// `text` or `html`
+    pub libretranslate_format: String,
+    /// Other candidate translations to ask for
+    pub libretranslate_alternatives: u32,
+    // Baidu Fanyi
+    pub baidu_app_id: String,
+    pub baidu_secret_key: String,
+    // Youdao
+    pub youdao_app_key: String,
+    pub youdao_app_secret: String,
+    // Tencent Cloud TMT
+    pub tencent_secret_id: String,
+    pub tencent_secret_key: String,
+    pub tencent_region: String,
+    // Amazon Translate; the shared credentials file is used without a key
+    pub aws_access_key_id: String,
+    pub aws_secret_access_key: String,
+    pub aws_profile: String,
+    pub aws_region: String,
+    /// Custom terminology names, comma-separated
+    pub aws_terminology: String,
+    /// Replaces `https://translate.<region>.amazonaws.com`, e.g. for a mock
+    pub aws_endpoint: String,
+    // Google Cloud Translation v3
+    /// Service account key file (JSON)
+    pub google_cloud_credentials_path: String,
+    /// `project_id` of the key file if empty
+    pub google_cloud_project_id: String,
+    /// `global`, or the region of the glossary
+    pub google_cloud_location: String,
+    /// Glossary ID in the project and location
+    pub google_cloud_glossary: String,
+    /// Replaces `https://translation.googleapis.com`, e.g. for a mock
+    pub google_cloud_endpoint: String,
+    // Offline
+    /// One directory per language pair; `~/.local/share/lighttranslator/models`
+    /// if empty
+    pub offline_models_dir: String,
+    /// CPU threads per model; all cores if 0
+    pub offline_threads: u32,
+    /// Models are unloaded to keep their total size under this
+    pub offline_memory_limit_mb: u32,
+    // Languages used when a caller does not specify any
+    pub quick_source_lang: String,
+    pub quick_target_lang: String,
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            provider: "google".to_string(),
+            model_id: "gemini-3-flash-preview".to_string(),
+            custom_system_instruction: String::new(),
+            system_prompt_enabled: true,
+            gemini_api_key: String::new(),
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            openai_api_key: String::new(),
+            openai_model: "gpt-3.5-turbo".to_string(),
+            openrouter_api_key: String::new(),
+            openrouter_model: "openai/gpt-3.5-turbo".to_string(),
+            anthropic_api_key: String::new(),
+            anthropic_base_url: "https://api.anthropic.com".to_string(),
+            anthropic_model: "claude-haiku-4-5".to_string(),
+            azure_openai_endpoint: String::new(),
+            azure_openai_region: "eastus".to_string(),
+            azure_openai_deployment: String::new(),
+            azure_openai_api_version: "2024-10-21".to_string(),
+            azure_openai_auth: "key".to_string(),
+            azure_openai_api_key: String::new(),
+            azure_openai_tenant_id: String::new(),
+            azure_openai_client_id: String::new(),
+            azure_openai_client_secret: String::new(),
+            ollama_base_url: "http://localhost:11434".to_string(),
+            ollama_model: "llama3.2".to_string(),
+            ollama_keep_alive: "5m".to_string(),
+            ollama_num_ctx: 0,
+            gguf_model_path: String::new(),
+            gguf_template: "chatml".to_string(),
+            gguf_custom_template: String::new(),
+            gguf_threads: 0,
+            gguf_context_length: 4096,
+            deepl_api_key: String::new(),
+            formality: String::new(),
+            microsoft_subscription_key: String::new(),
+            microsoft_region: "eastus".to_string(),
+            libretranslate_base_url: "http://localhost:5000".to_string(),
+            libretranslate_api_key: String::new(),
+            libretranslate_format: "text".to_string(),
+            libretranslate_alternatives: 0,
+            baidu_app_id: String::new(),
+            baidu_secret_key: String::new(),
+            youdao_app_key: String::new(),
+            youdao_app_secret: String::new(),
+            tencent_secret_id: String::new(),
+            tencent_secret_key: String::new(),
+            tencent_region: "ap-guangzhou".to_string(),
+            aws_access_key_id: String::new(),
+            aws_secret_access_key: String::new(),
+            aws_profile: String::new(),
+            aws_region: String::new(),
+            aws_terminology: String::new(),
+            aws_endpoint: String::new(),
+            google_cloud_credentials_path: String::new(),
+            google_cloud_project_id: String::new(),
+            google_cloud_location: "global".to_string(),
+            google_cloud_glossary: String::new(),
+            google_cloud_endpoint: String::new(),
+            offline_models_dir: String::new(),
+            offline_threads: 0,
+            offline_memory_limit_mb: 1024,
+            quick_source_lang: "auto".to_string(),
+            quick_target_lang: "zh-CN".to_string(),
+        }
+    }
+}
+
+/// A model a provider offers, for the model pickers.
+#[derive(Debug, Clone, Serialize)]
+pub struct Model {
+    pub id: String,
+    pub name: String,
+}
+
+// --- Entry Point ---
+
+/// Routes `text` to the provider selected in `settings`.
+pub async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    match settings.provider.as_str() {
+        "openai" => openai::translate(client, text, source, target, settings).await,
+        "openrouter" => openai::translate_openrouter(client, text, source, target, settings).await,
+        "anthropic" => anthropic::translate(client, text, source, target, settings).await,
+        "azure_openai" => azure_openai::translate(client, text, source, target, settings).await,
+        "ollama" => ollama::translate_stream(client, text, source, target, settings, &|_| {}).await,
+        "deepl" => deepl::translate(client, text, source, target, settings).await,
+        "google" => google::translate(client, text, source, target).await,
+        "microsoft" => microsoft::translate(client, text, source, target, settings).await,
+        "libretranslate" => libretranslate::translate(client, text, source, target, settings)
+            .await
+            .map(|(translation, _)| translation),
+        "baidu" => baidu::translate(client, text, source, target, settings).await,
+        "youdao" => youdao::translate(client, text, source, target, settings).await,
+        "tencent" => tencent::translate(client, text, source, target, settings).await,
+        "amazon" => amazon::translate(client, text, source, target, settings).await,
+        "google_cloud" => google_cloud::translate(client, text, source, target, settings).await,
+        "offline" => offline::translate(text, source, target, settings).await,
+        "gguf" => gguf::translate_stream(text, source, target, settings, &|_| {}).await,
+        id if id.starts_with(custom::PREFIX) => {
+            custom::translate(client, id, text, source, target).await
+        }
+        id if id.starts_with(plugins::PREFIX) => {
+            plugins::translate(client, id, text, source, target).await
+        }
+        // Default to Gemini
+        _ => gemini::translate(client, text, source, target, settings).await,
+    }
+}
+
+/// [`translate`], calling `on_delta` with partial output for providers that
+/// stream it (others call it once with the whole translation) and
+/// `on_alternatives` with other candidate translations for providers that
+/// offer them.
+pub async fn translate_stream(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+    on_delta: &(dyn Fn(&str) + Send + Sync),
+    on_alternatives: &(dyn Fn(&[String]) + Send + Sync),
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    match settings.provider.as_str() {
+        "anthropic" => {
+            anthropic::translate_stream(client, text, source, target, settings, on_delta).await
+        }
+        "ollama" => {
+            ollama::translate_stream(client, text, source, target, settings, on_delta).await
+        }
+        "gguf" => gguf::translate_stream(text, source, target, settings, on_delta).await,
+        "libretranslate" => {
+            let (translation, alternatives) =
+                libretranslate::translate(client, text, source, target, settings).await?;
+            on_delta(&translation);
+            if !alternatives.is_empty() {
+                on_alternatives(&alternatives);
+            }
+            Ok(translation)
+        }
+        _ => {
+            let translation = translate(client, text, source, target, settings).await?;
+            on_delta(&translation);
+            Ok(translation)
+        }
+    }
+}
+
+/// Models offered by `provider`, for providers that can list them.
+pub async fn list_models(
+    client: &reqwest::Client,
+    provider: &str,
+    settings: &TranslationSettings,
+) -> Result<Vec<Model>, String> {
+    match provider {
+        "anthropic" => anthropic::list_models(client, settings).await,
+        "azure_openai" => azure_openai::list_models(client, settings).await,
+        "ollama" => ollama::list_models(client, settings).await,
+        "offline" => offline::list_models(settings),
+        _ => Err(format!("Provider '{}' can't list its models", provider)),
+    }
+}
+
+/// Languages `provider` supports, for providers that can list them.
+pub async fn list_languages(
+    client: &reqwest::Client,
+    provider: &str,
+    settings: &TranslationSettings,
+) -> Result<Vec<Language>, String> {
+    match provider {
+        "libretranslate" => libretranslate::languages(client, settings).await,
+        _ => Err(format!("Provider '{}' can't list its languages", provider)),
+    }
+}
+
+/// Guesses the language of `text` with `provider`.
+pub async fn detect_language(
+    client: &reqwest::Client,
+    provider: &str,
+    text: &str,
+    settings: &TranslationSettings,
+) -> Result<Detection, String> {
+    match provider {
+        "libretranslate" => libretranslate::detect(client, text, settings).await,
+        _ => Err(format!("Provider '{}' can't detect languages", provider)),
+    }
+}
+
+// --- Helpers ---
+
+/// System prompt of the LLM providers: the custom instruction, or a plain
+/// translation instruction.
+fn system_prompt(source: &str, target: &str, settings: &TranslationSettings) -> String {
+    if !settings.custom_system_instruction.is_empty() {
+        return settings.custom_system_instruction.clone();
+    }
+    let source = if source == "auto" {
+        "detected language"
+    } else {
+        source
+    };
+    format!("You are a professional translator. Translate the following content from {} to {}. Output ONLY the translation, no explanations.", source, target)
+}
+
+/// Sends `request` and parses the body as JSON, turning non-2xx responses into
+/// `"<label>: <status> - <body>"` errors.
+async fn send_json(
+    request: reqwest::RequestBuilder,
+    label: &str,
+) -> Result<serde_json::Value, String> {
+    let response = crate::policy::send(request)
+        .await
+        .map_err(|e| format!("{}: {}", label, e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(format!("{}: {} - {}", label, status.as_u16(), body));
+    }
+
+    serde_json::from_str(&body).map_err(|e| format!("{}: {}", label, e))
+}
+
+/// Sends `request` and calls `on_line` with every line of the streamed body
+/// (server-sent events, NDJSON) as it arrives.
+async fn send_lines(
+    request: reqwest::RequestBuilder,
+    label: &str,
+    mut on_line: impl FnMut(&str) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut response = crate::policy::send(request)
+        .await
+        .map_err(|e| format!("{}: {}", label, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("{}: {} - {}", label, status.as_u16(), body));
+    }
+
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("{}: {}", label, e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            on_line(String::from_utf8_lossy(&line).trim())?;
+        }
+    }
+    if !buffer.is_empty() {
+        on_line(String::from_utf8_lossy(&buffer).trim())?;
+    }
+    Ok(())
+}
+
+/// Returns `value` unless it is empty, in which case `fallback` is used.
+fn or_default<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.is_empty() {
+        fallback
+    } else {
+        value
+    }
+}
@@ -0,0 +1,468 @@
+//! Offline neural machine translation with Marian models (OPUS-MT and
+//! others) converted to CTranslate2. Inference runs on the CPU inside the
+//! backend, one translator per language pair, loaded on first use and
+//! unloaded when idle or when loading another model would exceed the memory
+//! limit. Translators of different pairs work independently of each other.
+//!
+//! The model directory holds one directory per pair, named after it: `en-zh`,
+//! `enzh` or `opus-mt-en-zh`. Each holds a CTranslate2 model (`model.bin`)
+//! and its tokenizer: `source.spm` and `target.spm`, or `tokenizer.json`.
+//! Pairs without a model are translated through English when both halves
+//! exist.
+
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+
+use ct2rs::tokenizers::auto::Tokenizer;
+use ct2rs::{Config, TranslationOptions, Translator};
+
+use super::{Model, TranslationSettings};
+
+/// Models unused for this long are unloaded
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// How often idle models are looked for
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+/// Language pairs without a model of their own go through this one
+const PIVOT: &str = "en";
+
+/// Model directory of one language pair.
+#[derive(Debug, Clone)]
+struct PairModel {
+    from: String,
+    to: String,
+    dir: PathBuf,
+    /// Size of the files, as an estimate of the memory the model takes
+    size: u64,
+}
+
+/// The translator of one model. Its lock is held while it translates, the
+/// others keep working.
+struct Slot {
+    dir: PathBuf,
+    size: u64,
+    last_used: Mutex<Instant>,
+    /// Loaded on first use
+    translator: Mutex<Option<Translator<Tokenizer>>>,
+}
+
+/// Slots of the loaded models, least recently used first. Dropping a slot
+/// unloads its model once a translation still using it is done.
+static LOADED: Mutex<Vec<Arc<Slot>>> = Mutex::new(Vec::new());
+
+pub(super) async fn translate(
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let (text, source, target) = (text.to_string(), source.to_string(), target.to_string());
+    let settings = settings.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        translate_blocking(&text, &source, &target, &settings)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Installed language pairs, for the settings dialog.
+pub(super) fn list_models(settings: &TranslationSettings) -> Result<Vec<Model>, String> {
+    let loaded: Vec<PathBuf> = LOADED
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|slot| slot.dir.clone())
+        .collect();
+    Ok(discover(settings)?
+        .into_iter()
+        .map(|model| {
+            let mut name = format!(
+                "{} → {} ({} MB)",
+                model.from,
+                model.to,
+                model.size / (1024 * 1024)
+            );
+            if loaded.contains(&model.dir) {
+                name.push_str(", loaded");
+            }
+            Model {
+                id: format!("{}-{}", model.from, model.to),
+                name,
+            }
+        })
+        .collect())
+}
+
+fn translate_blocking(
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    let models = discover(settings)?;
+    let target = model_language(target);
+    let source = if source == "auto" {
+        detect_source(text, &target, &models)
+    } else {
+        model_language(source)
+    };
+    if source == target {
+        return Ok(text.to_string());
+    }
+
+    let route = route(&models, &source, &target).ok_or_else(|| {
+        format!(
+            "No offline model for {} → {} in {}",
+            source,
+            target,
+            models_dir(settings).display()
+        )
+    })?;
+    let mut translation = text.to_string();
+    for model in route {
+        translation = run(model, &translation, settings)?;
+    }
+    Ok(translation)
+}
+
+// --- Models ---
+
+fn models_dir(settings: &TranslationSettings) -> PathBuf {
+    if !settings.offline_models_dir.is_empty() {
+        return PathBuf::from(&settings.offline_models_dir);
+    }
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .unwrap_or_default();
+    data_dir.join("lighttranslator").join("models")
+}
+
+fn discover(settings: &TranslationSettings) -> Result<Vec<PairModel>, String> {
+    let root = models_dir(settings);
+    let entries = std::fs::read_dir(&root)
+        .map_err(|e| format!("Failed to read model directory {}: {}", root.display(), e))?;
+
+    let mut models = Vec::new();
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let Some((from, to)) = parse_pair(&entry.file_name().to_string_lossy()) else {
+            continue;
+        };
+        let size = std::fs::read_dir(&dir)
+            .map(|files| {
+                files
+                    .flatten()
+                    .filter_map(|file| file.metadata().ok())
+                    .filter(|metadata| metadata.is_file())
+                    .map(|metadata| metadata.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        models.push(PairModel {
+            from,
+            to,
+            dir,
+            size,
+        });
+    }
+    models.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    Ok(models)
+}
+
+/// `en-zh`, `en_zh`, `enzh`, `opus-mt-en-zh` → `("en", "zh")`
+fn parse_pair(name: &str) -> Option<(String, String)> {
+    let name = name.to_ascii_lowercase();
+    let name = name.strip_prefix("opus-mt-").unwrap_or(&name);
+    let (from, to) = match name.split_once(['-', '_']) {
+        Some(pair) => pair,
+        None if name.len() == 4 => name.split_at(2),
+        None => return None,
+    };
+    let is_code =
+        |code: &str| (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase());
+    (is_code(from) && is_code(to)).then(|| (from.to_string(), to.to_string()))
+}
+
+/// `zh-CN` → `zh`: models are per language, not per variant.
+fn model_language(code: &str) -> String {
+    code.split('-').next().unwrap_or(code).to_ascii_lowercase()
+}
+
+/// Guesses the source language from the script, falling back to the only
+/// model into `target` or the pivot language.
+fn detect_source(text: &str, target: &str, models: &[PairModel]) -> String {
+    let has = |range: RangeInclusive<char>| text.chars().any(|c| range.contains(&c));
+    let script = if has('\u{3040}'..='\u{30ff}') {
+        Some("ja")
+    } else if has('\u{ac00}'..='\u{d7af}') {
+        Some("ko")
+    } else if has('\u{4e00}'..='\u{9fff}') {
+        Some("zh")
+    } else if has('\u{0400}'..='\u{04ff}') {
+        Some("ru")
+    } else {
+        None
+    };
+    if let Some(language) = script {
+        return language.to_string();
+    }
+
+    let sources: Vec<&PairModel> = models.iter().filter(|model| model.to == target).collect();
+    match sources.as_slice() {
+        [only] => only.from.clone(),
+        _ => PIVOT.to_string(),
+    }
+}
+
+/// Models translating `source` to `target`, directly or through the pivot.
+fn route<'a>(models: &'a [PairModel], source: &str, target: &str) -> Option<Vec<&'a PairModel>> {
+    let find = |from: &str, to: &str| models.iter().find(|m| m.from == from && m.to == to);
+    if let Some(model) = find(source, target) {
+        return Some(vec![model]);
+    }
+    if source == PIVOT || target == PIVOT {
+        return None;
+    }
+    Some(vec![find(source, PIVOT)?, find(PIVOT, target)?])
+}
+
+// --- Translators ---
+
+/// Translates with the translator of `model`, loading it if needed.
+fn run(model: &PairModel, text: &str, settings: &TranslationSettings) -> Result<String, String> {
+    let slot = slot(model, settings);
+    let mut loaded = slot.translator.lock().unwrap();
+    let translator = match loaded.take() {
+        Some(translator) => translator,
+        None => load(model, settings)?,
+    };
+
+    let translation = translate_lines(&translator, text);
+    *loaded = Some(translator);
+    *slot.last_used.lock().unwrap() = Instant::now();
+    translation
+}
+
+/// The slot of `model`, made the most recently used. Creating one unloads
+/// the least recently used models until it fits the memory limit; models
+/// still translating are unloaded once they are done.
+fn slot(model: &PairModel, settings: &TranslationSettings) -> Arc<Slot> {
+    start_reaper();
+    let limit = u64::from(settings.offline_memory_limit_mb) * 1024 * 1024;
+    let mut slots = LOADED.lock().unwrap();
+
+    let slot = match slots.iter().position(|slot| slot.dir == model.dir) {
+        Some(index) => slots.remove(index),
+        None => {
+            while !slots.is_empty()
+                && slots.iter().map(|slot| slot.size).sum::<u64>() + model.size > limit
+            {
+                slots.remove(0);
+            }
+            Arc::new(Slot {
+                dir: model.dir.clone(),
+                size: model.size,
+                last_used: Mutex::new(Instant::now()),
+                translator: Mutex::new(None),
+            })
+        }
+    };
+    *slot.last_used.lock().unwrap() = Instant::now();
+    slots.push(slot.clone());
+    slot
+}
+
+/// Unloads models idle for [`IDLE_TIMEOUT`] in the background, so they don't
+/// stay in memory until the next offline translation.
+fn start_reaper() {
+    static REAPER: Once = Once::new();
+    REAPER.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(REAP_INTERVAL);
+            reap(&mut LOADED.lock().unwrap(), Instant::now());
+        });
+    });
+}
+
+/// Drops the slots last used more than [`IDLE_TIMEOUT`] before `now`.
+fn reap(slots: &mut Vec<Arc<Slot>>, now: Instant) {
+    slots.retain(|slot| {
+        let idle = now.saturating_duration_since(*slot.last_used.lock().unwrap());
+        if idle >= IDLE_TIMEOUT {
+            log::info!("Unloading idle offline model {}", slot.dir.display());
+        }
+        idle < IDLE_TIMEOUT
+    });
+}
+
+fn load(
+    model: &PairModel,
+    settings: &TranslationSettings,
+) -> Result<Translator<Tokenizer>, String> {
+    check_model(&model.dir)?;
+    let threads = match settings.offline_threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n as usize,
+    };
+    log::info!("Loading offline model {}", model.dir.display());
+
+    let config = Config {
+        num_threads_per_replica: threads,
+        ..Config::default()
+    };
+    Translator::new(&model.dir, &config).map_err(|e| {
+        format!(
+            "Failed to load the offline model {}: {}",
+            model.dir.display(),
+            e
+        )
+    })
+}
+
+/// Checks that `dir` holds a CTranslate2 model and a tokenizer, which gives
+/// a clearer error than the loader.
+fn check_model(dir: &Path) -> Result<(), String> {
+    let has = |file: &str| dir.join(file).is_file();
+    if !has("model.bin") {
+        return Err(format!(
+            "No CTranslate2 model in {}. Convert Marian models with ct2-transformers-converter or ct2-opus-mt-converter.",
+            dir.display()
+        ));
+    }
+    let has_tokenizer = has("tokenizer.json") || (has("source.spm") && has("target.spm"));
+    if !has_tokenizer {
+        return Err(format!(
+            "No tokenizer in {}: add source.spm and target.spm, or tokenizer.json",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Translates line by line, as one batch; blank lines are kept as they are.
+fn translate_lines(translator: &Translator<Tokenizer>, text: &str) -> Result<String, String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let sources: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| !line.is_empty())
+        .collect();
+    if sources.is_empty() {
+        return Ok(text.to_string());
+    }
+
+    let options = TranslationOptions::<String, String>::default();
+    let mut translations = translator
+        .translate_batch(&sources, &options, None)
+        .map_err(|e| format!("Offline translation failed: {}", e))?
+        .into_iter()
+        .map(|(translation, _score)| translation);
+    Ok(lines
+        .iter()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                translations
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end()
+                    .to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(from: &str, to: &str) -> PairModel {
+        PairModel {
+            from: from.to_string(),
+            to: to.to_string(),
+            dir: PathBuf::from(format!("{}-{}", from, to)),
+            size: 0,
+        }
+    }
+
+    fn dirs(route: Vec<&PairModel>) -> Vec<PathBuf> {
+        route.into_iter().map(|model| model.dir.clone()).collect()
+    }
+
+    #[test]
+    fn parses_pair_names() {
+        let pair = |from: &str, to: &str| Some((from.to_string(), to.to_string()));
+        assert_eq!(parse_pair("en-zh"), pair("en", "zh"));
+        assert_eq!(parse_pair("en_zh"), pair("en", "zh"));
+        assert_eq!(parse_pair("enzh"), pair("en", "zh"));
+        assert_eq!(parse_pair("opus-mt-de-en"), pair("de", "en"));
+        assert_eq!(parse_pair("OPUS-MT-EN-ZH"), pair("en", "zh"));
+        assert_eq!(parse_pair("en-fiu"), pair("en", "fiu"));
+        assert_eq!(parse_pair("models"), None);
+        assert_eq!(parse_pair("en-zh-tw"), None);
+        assert_eq!(parse_pair("e1-zh"), None);
+    }
+
+    #[test]
+    fn routes_directly_or_through_english() {
+        let models = [model("de", "en"), model("en", "zh"), model("ja", "zh")];
+        assert_eq!(
+            dirs(route(&models, "ja", "zh").unwrap()),
+            [PathBuf::from("ja-zh")]
+        );
+        assert_eq!(
+            dirs(route(&models, "de", "zh").unwrap()),
+            [PathBuf::from("de-en"), PathBuf::from("en-zh")]
+        );
+        // Nothing to pivot through
+        assert!(route(&models, "en", "de").is_none());
+        assert!(route(&models, "zh", "de").is_none());
+        assert!(route(&models, "fr", "zh").is_none());
+    }
+
+    #[test]
+    fn checks_model_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let touch = |file: &str| std::fs::write(dir.path().join(file), b"").unwrap();
+        assert!(check_model(dir.path())
+            .unwrap_err()
+            .starts_with("No CTranslate2 model"));
+
+        touch("model.bin");
+        touch("source.spm");
+        assert!(check_model(dir.path())
+            .unwrap_err()
+            .starts_with("No tokenizer"));
+
+        touch("target.spm");
+        assert!(check_model(dir.path()).is_ok());
+
+        std::fs::remove_file(dir.path().join("source.spm")).unwrap();
+        touch("tokenizer.json");
+        assert!(check_model(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn reaps_idle_models() {
+        let slot = |dir: &str, last_used: Instant| {
+            Arc::new(Slot {
+                dir: PathBuf::from(dir),
+                size: 0,
+                last_used: Mutex::new(last_used),
+                translator: Mutex::new(None),
+            })
+        };
+        let start = Instant::now();
+        let mut slots = vec![slot("idle", start), slot("recent", start + IDLE_TIMEOUT)];
+        reap(&mut slots, start + IDLE_TIMEOUT + Duration::from_secs(1));
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].dir, Path::new("recent"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Local models served by Ollama (`/api/chat`, `/api/tags`, `/api/pull`).
+//!
+//! Ollama streams newline-delimited JSON. Requests to a loopback daemon skip
+//! the configured proxy (see `AppState::http_client`).
+
+use serde::Serialize;
+use serde_json::json;
+
+use super::{or_default, send_json, send_lines, Model, TranslationSettings};
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+const DEFAULT_MODEL: &str = "llama3.2";
+
+/// Progress of `ollama pull`, as reported by the daemon.
+#[derive(Debug, Clone, Serialize)]
+pub struct PullProgress {
+    pub model: String,
+    pub status: String,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+}
+
+pub(super) async fn translate_stream(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+    on_delta: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    let mut messages = Vec::new();
+    if settings.system_prompt_enabled {
+        let system_prompt = super::system_prompt(source, target, settings);
+        messages.push(json!({ "role": "system", "content": system_prompt }));
+    }
+    messages.push(json!({ "role": "user", "content": text }));
+
+    let mut options = json!({ "temperature": 0.3 });
+    if settings.ollama_num_ctx > 0 {
+        options["num_ctx"] = json!(settings.ollama_num_ctx);
+    }
+    let mut body = json!({
+        "model": or_default(&settings.ollama_model, DEFAULT_MODEL),
+        "messages": messages,
+        "stream": true,
+        "options": options
+    });
+    if !settings.ollama_keep_alive.is_empty() {
+        body["keep_alive"] = keep_alive(&settings.ollama_keep_alive);
+    }
+
+    let request = client
+        .post(format!("{}/api/chat", base_url(settings)))
+        .json(&body);
+    let mut translation = String::new();
+    let result = send_lines(request, "Ollama Error", |line| {
+        let Some(event) = parse_line(line)? else {
+            return Ok(());
+        };
+        if let Some(delta) = event["message"]["content"].as_str() {
+            if !delta.is_empty() {
+                translation.push_str(delta);
+                on_delta(delta);
+            }
+        }
+        Ok(())
+    })
+    .await;
+    result.map_err(|e| daemon_error(settings, e))?;
+
+    if translation.trim().is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(translation.trim().to_string())
+}
+
+/// Models pulled into the local daemon.
+pub(super) async fn list_models(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+) -> Result<Vec<Model>, String> {
+    let request = client.get(format!("{}/api/tags", base_url(settings)));
+    let data = send_json(request, "Ollama Error")
+        .await
+        .map_err(|e| daemon_error(settings, e))?;
+
+    Ok(data["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|model| {
+                    let id = model["name"].as_str()?.to_string();
+                    let name = match model["details"]["parameter_size"].as_str() {
+                        Some(size) => format!("{} ({})", id, size),
+                        None => id.clone(),
+                    };
+                    Some(Model { id, name })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Downloads `model` into the daemon, calling `on_progress` as it goes.
+pub async fn pull_model(
+    client: &reqwest::Client,
+    settings: &TranslationSettings,
+    model: &str,
+    on_progress: &(dyn Fn(PullProgress) + Send + Sync),
+) -> Result<(), String> {
+    let request = client
+        .post(format!("{}/api/pull", base_url(settings)))
+        .json(&json!({ "model": model, "stream": true }));
+    let result = send_lines(request, "Ollama Error", |line| {
+        let Some(event) = parse_line(line)? else {
+            return Ok(());
+        };
+        on_progress(PullProgress {
+            model: model.to_string(),
+            status: event["status"].as_str().unwrap_or_default().to_string(),
+            completed: event["completed"].as_u64(),
+            total: event["total"].as_u64(),
+        });
+        Ok(())
+    })
+    .await;
+    result.map_err(|e| daemon_error(settings, e))
+}
+
+// --- Helpers ---
+
+fn base_url(settings: &TranslationSettings) -> &str {
+    or_default(&settings.ollama_base_url, DEFAULT_BASE_URL).trim_end_matches('/')
+}
+
+/// Parses one NDJSON line, turning `{"error": ...}` into an error.
+fn parse_line(line: &str) -> Result<Option<serde_json::Value>, String> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let event: serde_json::Value =
+        serde_json::from_str(line).map_err(|e| format!("Ollama Error: {}", e))?;
+    match event["error"].as_str() {
+        Some(error) => Err(format!("Ollama Error: {}", error)),
+        None => Ok(Some(event)),
+    }
+}
+
+/// `keep_alive` is a duration ("10m") or a number of seconds, `-1` keeping
+/// the model loaded indefinitely.
+fn keep_alive(value: &str) -> serde_json::Value {
+    match value.trim().parse::<i64>() {
+        Ok(seconds) => json!(seconds),
+        Err(_) => json!(value.trim()),
+    }
+}
+
+/// Replaces failures to reach the daemon (reqwest's "error sending request")
+/// with a hint that it is not running.
+fn daemon_error(settings: &TranslationSettings, error: String) -> String {
+    if error.contains("error sending request") {
+        format!(
+            "Ollama is not running at {}. Start it with `ollama serve`.",
+            base_url(settings)
+        )
+    } else {
+        error
+    }
+}
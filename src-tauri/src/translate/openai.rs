@@ -0,0 +1,90 @@
+use serde_json::json;
+
+use super::{or_default, send_json, TranslationSettings};
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.openai_api_key.is_empty() || settings.openai_base_url.is_empty() {
+        return Err("OpenAI API Key and Base URL are required.".to_string());
+    }
+
+    let url = format!(
+        "{}/chat/completions",
+        settings.openai_base_url.trim_end_matches('/')
+    );
+    let body = json!({
+        "model": or_default(&settings.openai_model, "gpt-3.5-turbo"),
+        "messages": messages(text, source, target, settings),
+        "temperature": 0.3
+    });
+
+    let request = client
+        .post(url)
+        .bearer_auth(&settings.openai_api_key)
+        .json(&body);
+
+    chat_completion(request, "OpenAI API Error").await
+}
+
+pub(super) async fn translate_openrouter(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.openrouter_api_key.is_empty() {
+        return Err("OpenRouter API Key is required.".to_string());
+    }
+
+    let body = json!({
+        "model": or_default(&settings.openrouter_model, "openai/gpt-3.5-turbo"),
+        "messages": messages(text, source, target, settings),
+        "temperature": 0.3
+    });
+
+    let request = client
+        .post("https://openrouter.ai/api/v1/chat/completions")
+        .bearer_auth(&settings.openrouter_api_key)
+        .header(
+            "HTTP-Referer",
+            "https://github.com/ArianaProjects/LightTranslator",
+        )
+        .header("X-Title", "LightTranslator")
+        .json(&body);
+
+    chat_completion(request, "OpenRouter API Error").await
+}
+
+// --- Helpers ---
+
+fn messages(
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Vec<serde_json::Value> {
+    let mut messages = Vec::new();
+
+    if settings.system_prompt_enabled {
+        let system_prompt = super::system_prompt(source, target, settings);
+        messages.push(json!({ "role": "system", "content": system_prompt }));
+    }
+
+    messages.push(json!({ "role": "user", "content": text }));
+    messages
+}
+
+async fn chat_completion(request: reqwest::RequestBuilder, label: &str) -> Result<String, String> {
+    let data = send_json(request, label).await?;
+
+    Ok(data["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "Translation empty.".to_string()))
+}
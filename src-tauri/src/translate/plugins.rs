@@ -0,0 +1,480 @@
+//! WebAssembly plugins: components in the `plugins` config directory that
+//! implement the interfaces of `wit/plugin.wit`. A `translator` becomes the
+//! provider `plugin:<file name>`; a `postprocessor` runs on every
+//! translation, in file name order.
+//!
+//! Plugins are sandboxed. They get WASI without preopened directories,
+//! environment variables or sockets, and reach the network only through
+//! `http.fetch`, which sends requests with the app's client to the hosts
+//! listed in the plugin's manifest, a TOML file next to it:
+//!
+//! ```toml
+//! # internal-mt.toml, for internal-mt.wasm
+//! name = "Internal MT"
+//! allowed_hosts = ["mt.example.com", "*.auth.example.com"]
+//! ```
+//!
+//! A plugin only runs once it has been enabled. Each call gets a fresh
+//! instance with limited memory and time.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use wasmtime::component::{Component, ComponentExportIndex, InstancePre, Linker, ResourceTable};
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder};
+use wasmtime_wasi::{IoView, WasiCtx, WasiCtxBuilder, WasiView};
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    world: "host",
+    async: true,
+});
+
+use lighttranslator::plugin::http;
+
+/// Prefix of the provider IDs of translator plugins
+pub const PREFIX: &str = "plugin:";
+
+const TRANSLATOR: &str = "lighttranslator:plugin/translator@1.0.0";
+const POSTPROCESSOR: &str = "lighttranslator:plugin/postprocessor@1.0.0";
+/// Linear memory a plugin may grow to
+const MEMORY_LIMIT: usize = 256 << 20;
+/// Seconds a call may take, waiting for HTTP included
+const CALL_TIMEOUT: u64 = 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Largest response body handed to a plugin
+const RESPONSE_LIMIT: usize = 16 << 20;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Manifest {
+    name: Option<String>,
+    /// Hosts `http.fetch` may reach; `*.example.com` for subdomains
+    allowed_hosts: Vec<String>,
+}
+
+/// A plugin file, as listed to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    pub id: String,
+    pub name: String,
+    pub file: String,
+    /// `translator` and/or `postprocessor`
+    pub kinds: Vec<&'static str>,
+    pub allowed_hosts: Vec<String>,
+    pub enabled: bool,
+    /// Why the plugin failed to load, or its last call failed
+    pub error: Option<String>,
+}
+
+struct Loaded {
+    id: String,
+    name: String,
+    path: PathBuf,
+    /// Modification time of the file the component was compiled from
+    modified: Option<SystemTime>,
+    manifest: Manifest,
+    enabled: bool,
+    pre: Option<InstancePre<PluginState>>,
+    translate: Option<ComponentExportIndex>,
+    postprocess: Option<ComponentExportIndex>,
+    error: Option<String>,
+}
+
+struct Plugins {
+    loaded: Vec<Loaded>,
+}
+
+static PLUGINS: Mutex<Plugins> = Mutex::new(Plugins { loaded: Vec::new() });
+
+/// The engine, with an epoch ticking every second to interrupt plugins
+/// that run for too long.
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config
+            .async_support(true)
+            .wasm_component_model(true)
+            .epoch_interruption(true);
+        let engine = Engine::new(&config).expect("Invalid WebAssembly engine configuration");
+        let ticker = engine.weak();
+        std::thread::spawn(move || {
+            while let Some(engine) = ticker.upgrade() {
+                engine.increment_epoch();
+                drop(engine);
+                std::thread::sleep(Duration::from_secs(1));
+            }
+        });
+        engine
+    })
+}
+
+/// Replaces the loaded plugins with the `.wasm` files in `dir`, enabling
+/// those in `enabled`. Components that didn't change since the last load
+/// aren't compiled again.
+pub fn load(dir: &Path, enabled: &[String]) -> Vec<PluginInfo> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|path| path.extension().is_some_and(|e| e == "wasm"));
+    paths.sort();
+
+    let mut previous = std::mem::take(&mut PLUGINS.lock().unwrap().loaded);
+    let mut loaded = Vec::new();
+    for path in paths {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let id = format!("{}{}", PREFIX, stem);
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let (manifest, manifest_error) = match read_manifest(&path.with_extension("toml")) {
+            Ok(manifest) => (manifest, None),
+            Err(e) => (Manifest::default(), Some(e)),
+        };
+
+        let mut plugin = Loaded {
+            name: manifest.name.clone().unwrap_or_else(|| stem.to_string()),
+            enabled: enabled.contains(&id),
+            id,
+            path,
+            modified,
+            manifest,
+            pre: None,
+            translate: None,
+            postprocess: None,
+            error: manifest_error,
+        };
+        // A plugin with an invalid manifest isn't run at all
+        if plugin.error.is_none() {
+            let unchanged = previous.iter().position(|p| {
+                p.path == plugin.path && p.modified == plugin.modified && p.pre.is_some()
+            });
+            if let Some(index) = unchanged {
+                let old = previous.swap_remove(index);
+                plugin.pre = old.pre;
+                plugin.translate = old.translate;
+                plugin.postprocess = old.postprocess;
+            } else if let Err(e) = compile(&mut plugin) {
+                plugin.error = Some(e);
+            }
+        }
+        if let Some(error) = &plugin.error {
+            log::error!("Plugin {}: {}", plugin.path.display(), error);
+        }
+        loaded.push(plugin);
+    }
+
+    let infos = loaded.iter().map(info).collect();
+    *PLUGINS.lock().unwrap() = Plugins { loaded };
+    infos
+}
+
+/// The loaded plugins, with the errors of their last calls.
+pub fn list() -> Vec<PluginInfo> {
+    PLUGINS.lock().unwrap().loaded.iter().map(info).collect()
+}
+
+/// Whether `id` is an enabled translator plugin.
+pub fn exists(id: &str) -> bool {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .loaded
+        .iter()
+        .any(|p| p.id == id && p.enabled && p.translate.is_some())
+}
+
+/// `(id, name)` of the enabled translator plugins.
+pub fn providers() -> Vec<(String, String)> {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .loaded
+        .iter()
+        .filter(|p| p.enabled && p.translate.is_some())
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .collect()
+}
+
+/// Whether any enabled plugin post-processes translations.
+pub fn has_postprocessors() -> bool {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .loaded
+        .iter()
+        .any(|p| p.enabled && p.postprocess.is_some())
+}
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    id: &str,
+    text: &str,
+    source: &str,
+    target: &str,
+) -> Result<String, String> {
+    let call = Call::new(id, |p| p.translate.as_ref())
+        .ok_or_else(|| format!("Plugin '{}' is not enabled or failed to load", id))?;
+    let result = call
+        .run::<(&str, &str, &str)>(client, (text, source, target))
+        .await;
+    record_result(id, &result);
+    let translation = result?;
+    if translation.trim().is_empty() {
+        return Ok("Translation empty.".to_string());
+    }
+    Ok(translation)
+}
+
+/// Runs `translation` through the enabled post-processors. A failing
+/// post-processor is skipped and its error kept for the plugin list.
+pub async fn postprocess(
+    client: &reqwest::Client,
+    translation: String,
+    source: &str,
+    target: &str,
+    provider: &str,
+) -> String {
+    let ids: Vec<String> = PLUGINS
+        .lock()
+        .unwrap()
+        .loaded
+        .iter()
+        .filter(|p| p.enabled && p.postprocess.is_some())
+        .map(|p| p.id.clone())
+        .collect();
+
+    let mut translation = translation;
+    for id in ids {
+        let Some(call) = Call::new(&id, |p| p.postprocess.as_ref()) else {
+            continue;
+        };
+        let args = (translation.as_str(), source, target, provider);
+        let result = call.run::<(&str, &str, &str, &str)>(client, args).await;
+        record_result(&id, &result);
+        match result {
+            Ok(processed) => translation = processed,
+            Err(e) => log::error!("{}", e),
+        }
+    }
+    translation
+}
+
+// --- Host ---
+
+struct PluginState {
+    wasi: WasiCtx,
+    table: ResourceTable,
+    limits: StoreLimits,
+    client: reqwest::Client,
+    allowed_hosts: Vec<String>,
+}
+
+impl IoView for PluginState {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+impl WasiView for PluginState {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+impl http::Host for PluginState {
+    async fn fetch(&mut self, request: http::Request) -> Result<http::Response, String> {
+        let url = reqwest::Url::parse(&request.url)
+            .map_err(|e| format!("Invalid URL {}: {}", request.url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL {}", url));
+        }
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        if !self.allowed_hosts.iter().any(|p| host_matches(&host, p)) {
+            return Err(format!("{} is not in the plugin's allowed_hosts", host));
+        }
+
+        let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+            .map_err(|_| format!("Invalid method {}", request.method))?;
+        let mut builder = self.client.request(method, url).timeout(REQUEST_TIMEOUT);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body);
+        }
+
+        let mut response = crate::policy::send(builder).await?;
+        if let Some(length) = response.content_length() {
+            if length > RESPONSE_LIMIT as u64 {
+                return Err(format!("Response of {} bytes is too large", length));
+            }
+        }
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        // Without a Content-Length, stop reading as soon as the limit is passed
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if body.len() + chunk.len() > RESPONSE_LIMIT {
+                return Err(format!("Response is larger than {} bytes", RESPONSE_LIMIT));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(http::Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// `pattern` is a host, or `*.` and a domain for its subdomains.
+fn host_matches(host: &str, pattern: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
+/// An export of a plugin, copied out of `PLUGINS` to be called without
+/// holding the lock.
+struct Call {
+    name: String,
+    pre: InstancePre<PluginState>,
+    export: ComponentExportIndex,
+    allowed_hosts: Vec<String>,
+}
+
+impl Call {
+    fn new(id: &str, export: impl Fn(&Loaded) -> Option<&ComponentExportIndex>) -> Option<Call> {
+        let plugins = PLUGINS.lock().unwrap();
+        let plugin = plugins.loaded.iter().find(|p| p.id == id && p.enabled)?;
+        Some(Call {
+            name: plugin.name.clone(),
+            pre: plugin.pre.clone()?,
+            export: *export(plugin)?,
+            allowed_hosts: plugin.manifest.allowed_hosts.clone(),
+        })
+    }
+
+    /// Calls the export with `args` in a new instance.
+    async fn run<Args>(self, client: &reqwest::Client, args: Args) -> Result<String, String>
+    where
+        Args: wasmtime::component::ComponentNamedList + wasmtime::component::Lower + Send + Sync,
+    {
+        let label = format!("{} Error", self.name);
+        let state = PluginState {
+            wasi: WasiCtxBuilder::new().build(),
+            table: ResourceTable::new(),
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+            client: client.clone(),
+            allowed_hosts: self.allowed_hosts,
+        };
+        let mut store = Store::new(engine(), state);
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(CALL_TIMEOUT);
+        store.epoch_deadline_trap();
+
+        let instance = self
+            .pre
+            .instantiate_async(&mut store)
+            .await
+            .map_err(|e| format!("{}: {}", label, e))?;
+        let func = instance
+            .get_typed_func::<Args, (Result<String, String>,)>(&mut store, &self.export)
+            .map_err(|e| format!("{}: {}", label, e))?;
+        let (result,) = func
+            .call_async(&mut store, args)
+            .await
+            .map_err(|e| format!("{}: {}", label, trap_message(&e)))?;
+        result.map_err(|e| format!("{}: {}", label, e))
+    }
+}
+
+fn trap_message(error: &wasmtime::Error) -> String {
+    match error.downcast_ref::<wasmtime::Trap>() {
+        Some(wasmtime::Trap::Interrupt) => format!("timed out after {} s", CALL_TIMEOUT),
+        _ => format!("{:#}", error),
+    }
+}
+
+// --- Helpers ---
+
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e)),
+        // Without a manifest the plugin has no network access
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Manifest::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Compiles and links the component of `plugin` and finds its exports.
+fn compile(plugin: &mut Loaded) -> Result<(), String> {
+    let engine = engine();
+    let component = Component::from_file(engine, &plugin.path).map_err(|e| format!("{:#}", e))?;
+
+    let mut linker = Linker::<PluginState>::new(engine);
+    wasmtime_wasi::add_to_linker_async(&mut linker).map_err(|e| e.to_string())?;
+    http::add_to_linker(&mut linker, |state| state).map_err(|e| e.to_string())?;
+    let pre = linker
+        .instantiate_pre(&component)
+        .map_err(|e| format!("{:#}", e))?;
+
+    let function = |interface: &str, name: &str| {
+        let (_, instance) = component.export_index(None, interface)?;
+        let (_, function) = component.export_index(Some(&instance), name)?;
+        Some(function)
+    };
+    plugin.translate = function(TRANSLATOR, "translate");
+    plugin.postprocess = function(POSTPROCESSOR, "postprocess");
+    if plugin.translate.is_none() && plugin.postprocess.is_none() {
+        return Err("exports neither a translator nor a postprocessor".to_string());
+    }
+    plugin.pre = Some(pre);
+    Ok(())
+}
+
+/// Keeps the error of a call for the plugin list, or clears the previous
+/// one after a call that succeeded.
+fn record_result(id: &str, result: &Result<String, String>) {
+    if let Some(plugin) = PLUGINS
+        .lock()
+        .unwrap()
+        .loaded
+        .iter_mut()
+        .find(|p| p.id == id)
+    {
+        plugin.error = result.as_ref().err().cloned();
+    }
+}
+
+fn info(plugin: &Loaded) -> PluginInfo {
+    let mut kinds = Vec::new();
+    if plugin.translate.is_some() {
+        kinds.push("translator");
+    }
+    if plugin.postprocess.is_some() {
+        kinds.push("postprocessor");
+    }
+    PluginInfo {
+        id: plugin.id.clone(),
+        name: plugin.name.clone(),
+        file: plugin.path.to_string_lossy().into_owned(),
+        kinds,
+        allowed_hosts: plugin.manifest.allowed_hosts.clone(),
+        enabled: plugin.enabled,
+        error: plugin.error.clone(),
+    }
+}
@@ -0,0 +1,176 @@
+//! Tencent Cloud Machine Translation (TMT `TextTranslate`), reachable from
+//! mainland China. Requests are signed with TC3-HMAC-SHA256.
+
+use serde_json::json;
+
+use super::{or_default, send_json, sign, TranslationSettings};
+
+const HOST: &str = "tmt.tencentcloudapi.com";
+const SERVICE: &str = "tmt";
+const VERSION: &str = "2018-03-21";
+const CONTENT_TYPE: &str = "application/json; charset=utf-8";
+const DEFAULT_REGION: &str = "ap-guangzhou";
+const SIGNED_HEADERS: &str = "content-type;host";
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.tencent_secret_id.is_empty() || settings.tencent_secret_key.is_empty() {
+        return Err("Tencent Cloud SecretId and SecretKey are required.".to_string());
+    }
+
+    let payload = json!({
+        "SourceText": text,
+        "Source": map_lang_code(source),
+        "Target": map_lang_code(target),
+        "ProjectId": 0,
+    })
+    .to_string();
+    let timestamp = sign::timestamp();
+    let authorization = authorization(
+        &settings.tencent_secret_id,
+        &settings.tencent_secret_key,
+        HOST,
+        SERVICE,
+        &payload,
+        timestamp,
+    );
+
+    let request = client
+        .post(format!("https://{}", HOST))
+        .header("Authorization", authorization)
+        .header("Content-Type", CONTENT_TYPE)
+        .header("X-TC-Action", "TextTranslate")
+        .header("X-TC-Version", VERSION)
+        .header("X-TC-Timestamp", timestamp.to_string())
+        .header(
+            "X-TC-Region",
+            or_default(&settings.tencent_region, DEFAULT_REGION),
+        )
+        .body(payload);
+
+    let data = send_json(request, "Tencent TMT Error").await?;
+
+    // Failures answer 200 with `Response.Error`
+    let data = &data["Response"];
+    if let Some(error) = data["Error"].as_object() {
+        return Err(format!(
+            "Tencent TMT Error: {} - {}",
+            error["Code"].as_str().unwrap_or_default(),
+            error["Message"].as_str().unwrap_or_default()
+        ));
+    }
+
+    Ok(data["TargetText"]
+        .as_str()
+        .unwrap_or("Translation empty.")
+        .to_string())
+}
+
+/// `Authorization` header of a TC3-HMAC-SHA256 signed POST of `payload` to
+/// `service` at `host`.
+fn authorization(
+    secret_id: &str,
+    secret_key: &str,
+    host: &str,
+    service: &str,
+    payload: &str,
+    timestamp: u64,
+) -> String {
+    let (year, month, day) = sign::utc_date(timestamp);
+    let date = format!("{:04}-{:02}-{:02}", year, month, day);
+    let scope = format!("{}/{}/tc3_request", date, service);
+
+    let canonical_request = canonical_request(host, payload);
+    let string_to_sign = string_to_sign(timestamp, &scope, &canonical_request);
+
+    let secret_date = sign::hmac_sha256(format!("TC3{}", secret_key).as_bytes(), date.as_bytes());
+    let secret_service = sign::hmac_sha256(&secret_date, service.as_bytes());
+    let secret_signing = sign::hmac_sha256(&secret_service, b"tc3_request");
+    let signature = hex::encode(sign::hmac_sha256(
+        &secret_signing,
+        string_to_sign.as_bytes(),
+    ));
+
+    format!(
+        "TC3-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        secret_id, scope, SIGNED_HEADERS, signature
+    )
+}
+
+fn canonical_request(host: &str, payload: &str) -> String {
+    format!(
+        "POST\n/\n\ncontent-type:{}\nhost:{}\n\n{}\n{}",
+        CONTENT_TYPE,
+        host,
+        SIGNED_HEADERS,
+        sign::sha256_hex(payload.as_bytes())
+    )
+}
+
+fn string_to_sign(timestamp: u64, scope: &str, canonical_request: &str) -> String {
+    format!(
+        "TC3-HMAC-SHA256\n{}\n{}\n{}",
+        timestamp,
+        scope,
+        sign::sha256_hex(canonical_request.as_bytes())
+    )
+}
+
+fn map_lang_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Worked example of the TC3-HMAC-SHA256 signing docs (CVM
+    // DescribeInstances), with the credentials exactly as published
+    const SECRET_ID: &str = "AKIDz8krbsJ5yKBZQpn74WFkmLPx3*******";
+    const SECRET_KEY: &str = "Gu5t9xGARNpq86cd98joQYCN3*******";
+    const HOST: &str = "cvm.tencentcloudapi.com";
+    const TIMESTAMP: u64 = 1551113065;
+    const PAYLOAD: &str =
+        r#"{"Limit": 1, "Filters": [{"Values": ["\u672a\u547d\u540d"], "Name": "instance-name"}]}"#;
+
+    #[test]
+    fn canonical_request_matches_docs() {
+        assert_eq!(
+            canonical_request(HOST, PAYLOAD),
+            "POST\n/\n\n\
+             content-type:application/json; charset=utf-8\n\
+             host:cvm.tencentcloudapi.com\n\n\
+             content-type;host\n\
+             35e9c5b0e3ae67532d3c9f17ead6c90222632e5b1ff7f6e89887f1398934f064"
+        );
+    }
+
+    #[test]
+    fn string_to_sign_matches_docs() {
+        let canonical_request = canonical_request(HOST, PAYLOAD);
+        assert_eq!(
+            string_to_sign(TIMESTAMP, "2019-02-25/cvm/tc3_request", &canonical_request),
+            "TC3-HMAC-SHA256\n1551113065\n2019-02-25/cvm/tc3_request\n\
+             5ffe6a04c0664d6b969fab9a13bdab201d63ee709638e2749d62a09ca18d7031"
+        );
+    }
+
+    #[test]
+    fn authorization_matches_docs() {
+        assert_eq!(
+            authorization(SECRET_ID, SECRET_KEY, HOST, "cvm", PAYLOAD, TIMESTAMP),
+            "TC3-HMAC-SHA256 \
+             Credential=AKIDz8krbsJ5yKBZQpn74WFkmLPx3*******/2019-02-25/cvm/tc3_request, \
+             SignedHeaders=content-type;host, \
+             Signature=2230eefd229f582d8b1b891af7107b91597240707d778ab3738f756258d7652c"
+        );
+    }
+}
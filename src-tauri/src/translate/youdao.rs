@@ -0,0 +1,130 @@
+//! Youdao (NetEase) text translation API, reachable from mainland China.
+
+use super::{send_json, sign, TranslationSettings};
+
+const API_URL: &str = "https://openapi.youdao.com/api";
+
+pub(super) async fn translate(
+    client: &reqwest::Client,
+    text: &str,
+    source: &str,
+    target: &str,
+    settings: &TranslationSettings,
+) -> Result<String, String> {
+    if settings.youdao_app_key.is_empty() || settings.youdao_app_secret.is_empty() {
+        return Err("Youdao App Key and App Secret are required.".to_string());
+    }
+
+    let curtime = sign::timestamp().to_string();
+    let salt = sign::nonce();
+    let signature = signature(
+        &settings.youdao_app_key,
+        text,
+        &salt,
+        &curtime,
+        &settings.youdao_app_secret,
+    );
+    let params = [
+        ("q", text),
+        ("from", map_lang_code(source)),
+        ("to", map_lang_code(target)),
+        ("appKey", &settings.youdao_app_key),
+        ("salt", &salt),
+        ("sign", &signature),
+        ("signType", "v3"),
+        ("curtime", &curtime),
+    ];
+
+    let request = client.post(API_URL).form(&params);
+    let data = send_json(request, "Youdao Translate Error").await?;
+
+    // Failures still answer 200, with a non-zero error code
+    let code = data["errorCode"].as_str().unwrap_or("0");
+    if code != "0" {
+        return Err(format!(
+            "Youdao Translate Error: code {} (see the Youdao error code list)",
+            code
+        ));
+    }
+
+    Ok(data["translation"][0]
+        .as_str()
+        .unwrap_or("Translation empty.")
+        .to_string())
+}
+
+/// v3 signature: `SHA256(appKey + input + salt + curtime + appSecret)`,
+/// lowercase hex.
+fn signature(app_key: &str, text: &str, salt: &str, curtime: &str, app_secret: &str) -> String {
+    let data = format!(
+        "{}{}{}{}{}",
+        app_key,
+        truncate(text),
+        salt,
+        curtime,
+        app_secret
+    );
+    sign::sha256_hex(data.as_bytes())
+}
+
+/// The text as it goes into the signature: unchanged up to 20 characters,
+/// otherwise the first 10, the length and the last 10.
+fn truncate(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= 20 {
+        return text.to_string();
+    }
+    let head: String = chars[..10].iter().collect();
+    let tail: String = chars[chars.len() - 10..].iter().collect();
+    format!("{}{}{}", head, chars.len(), tail)
+}
+
+fn map_lang_code(code: &str) -> &str {
+    match code {
+        "zh-CN" => "zh-CHS",
+        "zh-TW" => "zh-CHT",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_keeps_short_text() {
+        assert_eq!(truncate("hello"), "hello");
+        assert_eq!(truncate("exactly twenty chars"), "exactly twenty chars");
+    }
+
+    #[test]
+    fn truncate_shortens_long_text() {
+        assert_eq!(
+            truncate("The quick brown fox jumps over the lazy dog"),
+            "The quick 43e lazy dog"
+        );
+        // Counted in characters, not bytes
+        assert_eq!(
+            truncate("这是一个超过二十个字符的中文句子，用来测试截断是否按字符计算"),
+            "这是一个超过二十个字30试截断是否按字符计算"
+        );
+    }
+
+    #[test]
+    fn signature_hashes_truncated_input() {
+        assert_eq!(
+            signature("app-key", "hello", "1a2b3c", "1700000000", "app-secret"),
+            "af4bd427aa35760daf8f69490cba66246685204e2aba50a2d70e40dbb8fc4c28"
+        );
+        assert_eq!(
+            signature(
+                "app-key",
+                "The quick brown fox jumps over the lazy dog",
+                "1a2b3c",
+                "1700000000",
+                "app-secret"
+            ),
+            "3993c2a2b13c4ba834dd2cea9bcffe59460b6d12c726660374d2940c1d4586d0"
+        );
+    }
+}
@@ -0,0 +1,514 @@
+//! Tray icon and its menu.
+//!
+//! The menu reflects the current state (recent translations, pinned windows,
+//! profile, provider, language pair, pause, incognito) and is rebuilt through [`refresh`]
+//! whenever one of those changes. The icon and tooltip show whether a
+//! background operation is running or has failed, see [`busy`].
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tauri::{
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager, Wry,
+};
+
+use crate::translate::{self, PROVIDERS};
+use crate::AppState;
+
+const TRAY_ID: &str = "main";
+const RECENTS_CAPACITY: usize = 10;
+/// Characters of a translation shown in the Recent submenu.
+const RECENT_LABEL_CHARS: usize = 48;
+
+const TOOLTIP: &str = "LightTranslator";
+/// How long the error icon stays up before returning to idle.
+const ERROR_DISPLAY: Duration = Duration::from_secs(10);
+
+/// `(source, target)` presets offered in the Languages submenu.
+const LANGUAGE_PAIRS: &[(&str, &str)] = &[
+    ("auto", "en"),
+    ("auto", "zh-CN"),
+    ("en", "zh-CN"),
+    ("zh-CN", "en"),
+    ("en", "de"),
+    ("en", "ja"),
+];
+
+#[derive(Clone)]
+struct Recent {
+    /// Menu item id, stays the same while the list changes around it
+    id: u64,
+    text: String,
+    translation: String,
+}
+
+#[derive(Default)]
+pub struct TrayState {
+    /// Newest last
+    recents: Mutex<VecDeque<Recent>>,
+    next_recent_id: AtomicU64,
+    paused: AtomicBool,
+    incognito: AtomicBool,
+    showing_error: AtomicBool,
+    /// Bumped on every status change so a stale error reset is skipped
+    status_generation: AtomicU64,
+}
+
+impl TrayState {
+    /// Whether the global shortcuts and clipboard watch are paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn is_incognito(&self) -> bool {
+        self.incognito.load(Ordering::SeqCst)
+    }
+
+    fn remember(&self, text: &str, translation: &str) {
+        let mut recents = self.recents.lock().unwrap();
+        // Live translation while typing reports every prefix, keep the last
+        if let Some(last) = recents.back() {
+            if text.starts_with(last.text.as_str()) || last.text.starts_with(text) {
+                recents.pop_back();
+            }
+        }
+        recents.retain(|recent| recent.text != text);
+        if recents.len() == RECENTS_CAPACITY {
+            recents.pop_front();
+        }
+        recents.push_back(Recent {
+            id: self.next_recent_id.fetch_add(1, Ordering::SeqCst),
+            text: text.to_string(),
+            translation: translation.to_string(),
+        });
+    }
+}
+
+/// Turns incognito mode on or off; turning it on forgets the recent
+/// translations. It stays on while the system policy disables history.
+pub fn set_incognito(app: &AppHandle, incognito: bool) {
+    let state = app.state::<AppState>();
+    let incognito = incognito || crate::policy::current().history_disabled();
+    state.tray.incognito.store(incognito, Ordering::SeqCst);
+    if incognito {
+        state.tray.recents.lock().unwrap().clear();
+    }
+    refresh(app);
+}
+
+/// Adds a finished translation to the Recent submenu, unless incognito.
+pub fn record_translation(app: &AppHandle, text: &str, translation: &str) {
+    let state = app.state::<AppState>();
+    if state.tray.is_incognito() || text.trim().is_empty() || translation.trim().is_empty() {
+        return;
+    }
+    state.tray.remember(text.trim(), translation.trim());
+    refresh(app);
+}
+
+fn shorten(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("").trim();
+    if line.chars().count() > RECENT_LABEL_CHARS || text.lines().count() > 1 {
+        let short: String = line.chars().take(RECENT_LABEL_CHARS - 1).collect();
+        format!("{}…", short.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+// --- Menu ---
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let state = app.state::<AppState>();
+    let settings = state.translation_settings.lock().unwrap().clone();
+    let clipboard_watch = state.clipboard_watch.settings();
+    let policy = crate::policy::current();
+
+    // Recent translations, newest first
+    let recents = state.tray.recents.lock().unwrap().clone();
+    let mut recent_menu = SubmenuBuilder::new(app, "Recent Translations");
+    if recents.is_empty() {
+        recent_menu = recent_menu.item(
+            &MenuItemBuilder::new("No translations yet")
+                .enabled(false)
+                .build(app)?,
+        );
+    }
+    for recent in recents.iter().rev() {
+        recent_menu = recent_menu.text(
+            format!("recent:{}", recent.id),
+            shorten(&recent.translation),
+        );
+    }
+
+    // Pinned windows, closed by clicking them
+    let pinned = state.pinned.list();
+    let mut pinned_menu =
+        SubmenuBuilder::new(app, "Close Pinned Translation").enabled(!pinned.is_empty());
+    for (label, translation) in &pinned {
+        pinned_menu = pinned_menu.text(format!("unpin:{}", label), shorten(translation));
+    }
+    if pinned.len() > 1 {
+        pinned_menu = pinned_menu.separator().text("unpin-all", "Close All");
+    }
+
+    // Profiles, checked like a radio group
+    let profiles = state.profiles.list();
+    let mut profile_menu =
+        SubmenuBuilder::new(app, "Profile").enabled(!profiles.profiles.is_empty());
+    for profile in &profiles.profiles {
+        profile_menu = profile_menu.item(
+            &CheckMenuItemBuilder::with_id(format!("profile:{}", profile.name), &profile.name)
+                .checked(profiles.active.as_deref() == Some(profile.name.as_str()))
+                .build(app)?,
+        );
+    }
+
+    // Provider switcher, checked like a radio group
+    let mut provider_menu = SubmenuBuilder::new(app, "Provider");
+    let loaded_providers = translate::loaded_providers();
+    let providers = PROVIDERS.iter().map(|(id, name)| (*id, *name)).chain(
+        loaded_providers
+            .iter()
+            .map(|(id, name)| (id.as_str(), name.as_str())),
+    );
+    for (id, name) in providers {
+        if policy.check_provider(id).is_err() {
+            continue;
+        }
+        provider_menu = provider_menu.item(
+            &CheckMenuItemBuilder::with_id(format!("provider:{}", id), name)
+                .checked(settings.provider == id)
+                .build(app)?,
+        );
+    }
+
+    let mut language_menu = SubmenuBuilder::new(app, "Languages");
+    for (source, target) in LANGUAGE_PAIRS {
+        let current =
+            settings.quick_source_lang == *source && settings.quick_target_lang == *target;
+        language_menu = language_menu.item(
+            &CheckMenuItemBuilder::with_id(
+                format!("pair:{}:{}", source, target),
+                format!(
+                    "{} → {}",
+                    translate::language_name(source),
+                    translate::language_name(target)
+                ),
+            )
+            .checked(current)
+            .build(app)?,
+        );
+    }
+    language_menu = language_menu.separator().item(
+        &MenuItemBuilder::with_id(
+            "swap",
+            format!(
+                "Swap ({} → {})",
+                translate::language_name(&settings.quick_target_lang),
+                translate::language_name(&settings.quick_source_lang)
+            ),
+        )
+        // There is no "auto" target
+        .enabled(settings.quick_source_lang != "auto")
+        .build(app)?,
+    );
+
+    let clipboard_watch_item = CheckMenuItemBuilder::with_id("clipboard-watch", "Watch Clipboard")
+        .checked(clipboard_watch.enabled)
+        .build(app)?;
+    let pause_item = CheckMenuItemBuilder::with_id("pause", "Pause Shortcuts and Clipboard Watch")
+        .checked(state.tray.is_paused())
+        .build(app)?;
+    let incognito_item = CheckMenuItemBuilder::with_id("incognito", "Incognito (No History)")
+        .checked(state.tray.is_incognito())
+        .enabled(!policy.history_disabled())
+        .build(app)?;
+
+    MenuBuilder::new(app)
+        .text("toggle", "Show/Hide LightTranslator")
+        .text("settings", "Settings")
+        .text("ocr", "OCR Screenshot")
+        .separator()
+        .item(&recent_menu.build()?)
+        .item(&pinned_menu.build()?)
+        .item(&profile_menu.build()?)
+        .item(&provider_menu.build()?)
+        .item(&language_menu.build()?)
+        .separator()
+        .item(&clipboard_watch_item)
+        .item(&pause_item)
+        .item(&incognito_item)
+        .separator()
+        .text("quit", "Quit")
+        .build()
+}
+
+/// Rebuilds the tray menu from the current state.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::error!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    let state = app.state::<AppState>();
+
+    match id {
+        "toggle" => toggle_main_window(app, false),
+        "settings" => crate::open_settings(app),
+        "ocr" => crate::spawn_ocr_capture(app),
+        "clipboard-watch" => crate::clipboard_watch::toggle(app),
+        "pause" => {
+            state.tray.paused.fetch_xor(true, Ordering::SeqCst);
+            refresh(app);
+        }
+        "incognito" => set_incognito(app, !state.tray.is_incognito()),
+        "swap" => {
+            let (source, target) = {
+                let settings = state.translation_settings.lock().unwrap();
+                (
+                    settings.quick_source_lang.clone(),
+                    settings.quick_target_lang.clone(),
+                )
+            };
+            if let Err(e) = crate::set_quick_languages(app, &target, &source) {
+                log::error!("Failed to swap languages: {}", e);
+            }
+        }
+        "unpin-all" => crate::pinned::close(app, None),
+        "quit" => {
+            // Open pins are never destroyed, keep where they were
+            crate::pinned::save(app);
+            crate::placement::save(app);
+            std::process::exit(0);
+        }
+        _ => {
+            if let Some(name) = id.strip_prefix("profile:") {
+                if let Err(e) = crate::profiles::switch(app, name) {
+                    report_error(app, "Switching profile failed", &e);
+                }
+            } else if let Some(provider) = id.strip_prefix("provider:") {
+                crate::set_provider(app, provider);
+            } else if let Some(pair) = id.strip_prefix("pair:") {
+                if let Some((source, target)) = pair.split_once(':') {
+                    if let Err(e) = crate::set_quick_languages(app, source, target) {
+                        log::error!("Failed to set languages: {}", e);
+                    }
+                }
+            } else if let Some(label) = id.strip_prefix("unpin:") {
+                crate::pinned::close(app, Some(label));
+            } else if let Some(recent_id) = id.strip_prefix("recent:") {
+                let translation = recent_id.parse::<u64>().ok().and_then(|recent_id| {
+                    let recents = state.tray.recents.lock().unwrap();
+                    recents
+                        .iter()
+                        .find(|recent| recent.id == recent_id)
+                        .map(|recent| recent.translation.clone())
+                });
+                if let Some(translation) = translation {
+                    if let Err(e) = crate::copy_to_clipboard(app, &translation) {
+                        log::error!("Failed to copy translation: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// --- Status ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Idle,
+    Busy,
+    Error,
+}
+
+/// Tray icons for each status: the bundled icon, with a blue (busy) or red
+/// (error) badge in the bottom right corner.
+fn status_icon(status: Status) -> tauri::image::Image<'static> {
+    static ICONS: OnceLock<[image::RgbaImage; 3]> = OnceLock::new();
+
+    let icons = ICONS.get_or_init(|| {
+        // Use the same icon as dock (512x512) - let system handle scaling
+        let icon_bytes = include_bytes!("../icons/icon.png");
+        let idle = image::load_from_memory(icon_bytes)
+            .expect("Failed to load tray icon")
+            .into_rgba8();
+        let busy = with_badge(&idle, [10, 132, 255, 255]);
+        let error = with_badge(&idle, [255, 59, 48, 255]);
+        [idle, busy, error]
+    });
+
+    let icon = match status {
+        Status::Idle => &icons[0],
+        Status::Busy => &icons[1],
+        Status::Error => &icons[2],
+    };
+    let (width, height) = icon.dimensions();
+    tauri::image::Image::new_owned(icon.as_raw().clone(), width, height)
+}
+
+fn with_badge(icon: &image::RgbaImage, color: [u8; 4]) -> image::RgbaImage {
+    let mut icon = icon.clone();
+    let (width, height) = icon.dimensions();
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    // White ring around the dot keeps it visible on dark and coloured icons
+    let ring = radius * 0.18;
+
+    for (x, y, pixel) in icon.enumerate_pixels_mut() {
+        let distance = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)).sqrt();
+        if distance <= radius - ring {
+            *pixel = image::Rgba(color);
+        } else if distance <= radius {
+            *pixel = image::Rgba([255, 255, 255, 255]);
+        }
+    }
+    icon
+}
+
+fn set_status(app: &AppHandle, status: Status, tooltip: &str) -> u64 {
+    let generation = app
+        .state::<AppState>()
+        .tray
+        .status_generation
+        .fetch_add(1, Ordering::SeqCst)
+        + 1;
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_icon(Some(status_icon(status)));
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+    generation
+}
+
+/// Shows the busy icon with `operation` as tooltip until the returned guard
+/// is dropped.
+pub fn busy(app: &AppHandle, operation: &str) -> BusyGuard {
+    app.state::<AppState>()
+        .tray
+        .showing_error
+        .store(false, Ordering::SeqCst);
+    set_status(app, Status::Busy, &format!("{} — {}", TOOLTIP, operation));
+    BusyGuard { app: app.clone() }
+}
+
+pub struct BusyGuard {
+    app: AppHandle,
+}
+
+impl BusyGuard {
+    /// Changes the tooltip for the next step of the operation.
+    pub fn step(&self, operation: &str) {
+        if let Some(tray) = self.app.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(format!("{} — {}", TOOLTIP, operation)));
+        }
+    }
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        let state = self.app.state::<AppState>();
+        // An error reported meanwhile stays visible
+        if !state.tray.showing_error.load(Ordering::SeqCst) {
+            set_status(&self.app, Status::Idle, TOOLTIP);
+        }
+    }
+}
+
+/// Shows the error icon and a desktop notification with `reason`, e.g.
+/// "tesseract language pack jpn missing". Returns to idle after a while.
+pub fn report_error(app: &AppHandle, operation: &str, reason: &str) {
+    log::error!("{}: {}", operation, reason);
+
+    let state = app.state::<AppState>();
+    state.tray.showing_error.store(true, Ordering::SeqCst);
+    let generation = set_status(
+        app,
+        Status::Error,
+        &format!("{} — {}: {}", TOOLTIP, operation, reason),
+    );
+
+    let app = app.clone();
+    let (operation, reason) = (operation.to_string(), reason.to_string());
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::notifications::show_error(&operation, &reason).await {
+            log::warn!("{}", e);
+        }
+
+        tokio::time::sleep(ERROR_DISPLAY).await;
+        let state = app.state::<AppState>();
+        if state.tray.status_generation.load(Ordering::SeqCst) == generation {
+            state.tray.showing_error.store(false, Ordering::SeqCst);
+            set_status(&app, Status::Idle, TOOLTIP);
+        }
+    });
+}
+
+/// Hides the main window if it is visible, otherwise shows it. Opening the
+/// tray menu takes the focus, so only a click checks `require_focus`.
+fn toggle_main_window(app: &AppHandle, require_focus: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let focused = !require_focus || window.is_focused().unwrap_or(false);
+        if window.is_visible().unwrap_or(false) && focused {
+            let _ = window.hide();
+        } else {
+            crate::show_main_window(app);
+        }
+    }
+}
+
+pub fn setup(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app)?;
+
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(status_icon(Status::Idle))
+        .tooltip(TOOLTIP)
+        .menu(&menu)
+        // Left click toggles the main window, the menu is on right click.
+        // Linux reports no clicks, the menu's Show/Hide item covers it.
+        .show_menu_on_left_click(false)
+        // Handled off the main thread, settings updates wait for it
+        .on_menu_event(|app, event| {
+            let (app, id) = (app.clone(), event.id.as_ref().to_string());
+            std::thread::spawn(move || handle_menu_event(&app, &id));
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                toggle_main_window(tray.app_handle(), true);
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+// --- Commands ---
+
+/// Called by the windows after each finished translation.
+#[tauri::command]
+pub async fn add_recent_translation(
+    app: AppHandle,
+    text: String,
+    translation: String,
+) -> Result<(), String> {
+    record_translation(&app, &text, &translation);
+    Ok(())
+}
@@ -6,6 +6,8 @@
  * fallbacks for web environments where applicable.
  */
 
+import type { AppSettings } from '../../types';
+
 // Type definitions for the platform API
 export interface ProxyRequestOptions {
   method?: string;
@@ -13,6 +15,12 @@ export interface ProxyRequestOptions {
   body?: string;
 }
 
+/** Broadcast after every settings change; `origin` is the label of the window that made it */
+export interface SettingsChangedEvent {
+  settings: AppSettings;
+  origin: string | null;
+}
+
 export interface ProxyResponse {
   ok: boolean;
   statusCode?: number;
@@ -20,13 +28,125 @@ export interface ProxyResponse {
   error?: string;
 }
 
-export interface ProxySettings {
-  enabled: boolean;
-  protocol: string;
+/** Captured text plus the overrides of a matching application rule */
+export interface QuickTranslateRequest {
+  text: string;
+  provider?: string;
+  sourceLang?: string;
+  targetLang?: string;
+  systemPrompt?: string;
+  formality?: 'formal' | 'informal';
+}
+
+/** Per-request changes to the settings used by `translateText` */
+export interface TranslationOverrides {
+  provider?: string;
+  sourceLang?: string;
+  targetLang?: string;
+  systemPrompt?: string;
+  formality?: 'formal' | 'informal';
+}
+
+export interface ProviderModel {
+  id: string;
+  name: string;
+}
+
+/** A language a provider supports, with the targets it translates to */
+export interface ProviderLanguage {
+  code: string;
+  name: string;
+  targets: string[];
+}
+
+/** A provider declared in a file of the providers config directory */
+export interface CustomProvider {
+  id: `custom:${string}`;
+  name: string;
   host: string;
-  port: number;
-  username?: string;
-  password?: string;
+}
+
+export interface CustomProviders {
+  dir: string;
+  providers: CustomProvider[];
+  /** Files that were skipped, with the reason */
+  errors: string[];
+}
+
+/** A WebAssembly plugin of the plugins config directory */
+export interface PluginInfo {
+  id: `plugin:${string}`;
+  name: string;
+  file: string;
+  kinds: ('translator' | 'postprocessor')[];
+  /** Hosts the plugin may send requests to */
+  allowedHosts: string[];
+  enabled: boolean;
+  /** Why it failed to load, or its last call failed */
+  error: string | null;
+}
+
+export interface PluginList {
+  dir: string;
+  plugins: PluginInfo[];
+}
+
+export interface LanguageDetection {
+  language: string;
+  /** 0 to 100 */
+  confidence: number;
+}
+
+/** Progress of an Ollama model download */
+export interface OllamaPullProgress {
+  model: string;
+  status: string;
+  completed: number | null;
+  total: number | null;
+}
+
+export interface AppRule {
+  app: string;
+  neverCapture: boolean;
+  provider?: string;
+  sourceLang?: string;
+  targetLang?: string;
+  systemPrompt?: string;
+  formality?: 'formal' | 'informal';
+}
+
+/** Named configuration profile; secrets are references, never values */
+export interface Profile {
+  name: string;
+  settings: Partial<AppSettings>;
+  secrets?: Record<string, string>;
+  incognito: boolean;
+}
+
+export interface ProfileList {
+  active: string | null;
+  profiles: Profile[];
+}
+
+/** System policy (/etc/lighttranslator/policy.toml) set by an administrator */
+export interface Policy {
+  locked: Partial<AppSettings>;
+  allowedProviders: string[] | null;
+  allowedHosts: string[] | null;
+  blockedHosts: string[];
+  disableHistory: boolean;
+}
+
+export interface ReplacePreview {
+  text: string;
+  translation: string;
+}
+
+export interface PinnedContent {
+  text: string;
+  translation: string;
+  provider: string;
+  targetLang: string;
 }
 
 export interface WindowDimensions {
@@ -61,7 +181,7 @@ export const isTauri = (): boolean => {
 
 // Platform-specific imports for Tauri (lazy loaded)
 let tauriInvoke: ((cmd: string, args?: Record<string, unknown>) => Promise<unknown>) | null = null;
-let tauriWindow: { getCurrentWindow: () => { minimize: () => Promise<void>; toggleMaximize: () => Promise<void>; close: () => Promise<void>; hide: () => Promise<void>; onFocusChanged: (handler: (event: { payload: boolean }) => void) => Promise<() => void> } } | null = null;
+let tauriWindow: { getCurrentWindow: () => { label: string; minimize: () => Promise<void>; toggleMaximize: () => Promise<void>; close: () => Promise<void>; hide: () => Promise<void>; onFocusChanged: (handler: (event: { payload: boolean }) => void) => Promise<() => void> } } | null = null;
 let tauriEvent: { listen: (event: string, handler: (event: { payload: unknown }) => void) => Promise<() => void> } | null = null;
 
 // Initialize Tauri APIs if available
@@ -80,6 +200,18 @@ const initTauri = async () => {
   }
 };
 
+// Ids of backend translations, unique across windows since every window
+// receives the delta events
+let nextTranslationId = Date.now() * 1000;
+
+// Listeners the main window registers for actions sent by the backend;
+// `sendMainReady` waits for them before the command line action is run
+const pendingListeners: Promise<unknown>[] = [];
+let mainReadySent = false;
+const trackListener = (registered: Promise<unknown>) => {
+  if (!mainReadySent) pendingListeners.push(registered);
+};
+
 // Initialize on module load if Tauri is detected
 if (isTauri()) {
   initTauri();
@@ -143,15 +275,276 @@ export const platform = {
     }
   },
 
+  /**
+   * Settings, owned by the backend
+   */
+  async getSettings(): Promise<AppSettings | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('get_settings') as Promise<AppSettings>;
+    }
+    return null;
+  },
+
+  async updateSettings(patch: Partial<AppSettings>): Promise<AppSettings | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('update_settings', { patch }) as Promise<AppSettings>;
+    }
+    return null;
+  },
+
+  /** One-time import of the settings formerly kept in localStorage */
+  async importFrontendSettings(store: string): Promise<AppSettings | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('import_frontend_settings', { store }) as Promise<AppSettings>;
+    }
+    return null;
+  },
+
+  onSettingsChanged(callback: (event: SettingsChangedEvent) => void): () => void {
+    let unlisten: (() => void) | null = null;
+    initTauri().then(() => {
+      if (tauriEvent) {
+        tauriEvent.listen('settings-changed', (event) => {
+          callback(event.payload as SettingsChangedEvent);
+        }).then((fn) => {
+          unlisten = fn;
+        });
+      }
+    });
+    return () => unlisten?.();
+  },
+
+  async getWindowLabel(): Promise<string | null> {
+    await initTauri();
+    return tauriWindow ? tauriWindow.getCurrentWindow().label : null;
+  },
+
+  /**
+   * Translates in the backend with the current settings. `onDelta` receives
+   * the text as streaming providers produce it, `onAlternatives` other
+   * candidate translations from providers that offer them.
+   */
+  async translateText(
+    text: string,
+    source: string,
+    target: string,
+    overrides: TranslationOverrides = {},
+    onDelta?: (text: string) => void,
+    onAlternatives?: (alternatives: string[]) => void
+  ): Promise<string> {
+    await initTauri();
+    if (!tauriInvoke) {
+      throw new Error('This provider is only available in the desktop app.');
+    }
+    const requestId = nextTranslationId++;
+    const unlisteners: (() => void)[] = [];
+    if (onDelta && tauriEvent) {
+      unlisteners.push(await tauriEvent.listen('translation-delta', (event) => {
+        const delta = event.payload as { id: number; text: string };
+        if (delta.id === requestId) onDelta(delta.text);
+      }));
+    }
+    if (onAlternatives && tauriEvent) {
+      unlisteners.push(await tauriEvent.listen('translation-alternatives', (event) => {
+        const payload = event.payload as { id: number; alternatives: string[] };
+        if (payload.id === requestId) onAlternatives(payload.alternatives);
+      }));
+    }
+    try {
+      return await (tauriInvoke('translate_text', { requestId, text, source, target, overrides }) as Promise<string>);
+    } finally {
+      unlisteners.forEach((unlisten) => unlisten());
+    }
+  },
+
+  async listProviderModels(provider: string): Promise<ProviderModel[]> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('list_provider_models', { provider }) as Promise<ProviderModel[]>;
+    }
+    return [];
+  },
+
+  async listProviderLanguages(provider: string): Promise<ProviderLanguage[]> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('list_provider_languages', { provider }) as Promise<ProviderLanguage[]>;
+    }
+    return [];
+  },
+
+  async detectLanguage(text: string): Promise<LanguageDetection | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('detect_language', { text }) as Promise<LanguageDetection>;
+    }
+    return null;
+  },
+
+  async pullOllamaModel(model: string): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('pull_ollama_model', { model });
+    }
+  },
+
+  async pickGgufModel(): Promise<string | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('pick_gguf_model') as Promise<string | null>;
+    }
+    return null;
+  },
+
+  /** Reloads the custom provider definitions */
+  async listCustomProviders(): Promise<CustomProviders> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('list_custom_providers') as Promise<CustomProviders>;
+    }
+    return { dir: '', providers: [], errors: [] };
+  },
+
+  async listPlugins(): Promise<PluginList> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('list_plugins') as Promise<PluginList>;
+    }
+    return { dir: '', plugins: [] };
+  },
+
+  /** Reloads the plugins, picking up changed files */
+  async reloadPlugins(): Promise<PluginList> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('reload_plugins') as Promise<PluginList>;
+    }
+    return { dir: '', plugins: [] };
+  },
+
+  async setPluginEnabled(id: string, enabled: boolean): Promise<PluginList> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('set_plugin_enabled', { id, enabled }) as Promise<PluginList>;
+    }
+    return { dir: '', plugins: [] };
+  },
+
+  /** Runs a translation made in the webview through the post-processor plugins */
+  async postprocessTranslation(text: string, source: string, target: string, provider: string): Promise<string> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('postprocess_translation', { text, source, target, provider }) as Promise<string>;
+    }
+    return text;
+  },
+
+  async pickServiceAccountKey(): Promise<string | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('pick_service_account_key') as Promise<string | null>;
+    }
+    return null;
+  },
+
+  onOllamaPullProgress(callback: (progress: OllamaPullProgress) => void): () => void {
+    let unlisten: (() => void) | null = null;
+    initTauri().then(() => {
+      if (tauriEvent) {
+        tauriEvent.listen('ollama-pull-progress', (event) => {
+          callback(event.payload as OllamaPullProgress);
+        }).then((fn) => {
+          unlisten = fn;
+        });
+      }
+    });
+    return () => unlisten?.();
+  },
+
+  async getPolicy(): Promise<Policy | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('get_policy') as Promise<Policy>;
+    }
+    return null;
+  },
+
+  /**
+   * Configuration profiles (also switchable from the tray and --profile)
+   */
+  async listProfiles(): Promise<ProfileList> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('list_profiles') as Promise<ProfileList>;
+    }
+    return { active: null, profiles: [] };
+  },
+
+  async saveProfile(name: string, incognito: boolean): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('save_profile', { name, incognito });
+    }
+  },
+
+  async deleteProfile(name: string): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('delete_profile', { name });
+    }
+  },
+
+  async switchProfile(name: string): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('switch_profile', { name });
+    }
+  },
+
+  /** Returns false if the file dialog was cancelled */
+  async exportProfiles(): Promise<boolean> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('export_profiles') as Promise<boolean>;
+    }
+    return false;
+  },
+
+  /** Returns the number of imported profiles */
+  async importProfiles(): Promise<number> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('import_profiles') as Promise<number>;
+    }
+    return 0;
+  },
+
+  onProfilesChanged(callback: (list: ProfileList) => void): () => void {
+    let unlisten: (() => void) | null = null;
+    initTauri().then(() => {
+      if (tauriEvent) {
+        tauriEvent.listen('profiles-changed', (event) => {
+          callback(event.payload as ProfileList);
+        }).then((fn) => {
+          unlisten = fn;
+        });
+      }
+    });
+    return () => unlisten?.();
+  },
+
   /**
    * Quick Translate window
    */
-  onQuickTranslate(callback: (text: string) => void): () => void {
+  onQuickTranslate(callback: (request: QuickTranslateRequest) => void): () => void {
     let unlisten: (() => void) | null = null;
     initTauri().then(() => {
       if (tauriEvent) {
         tauriEvent.listen('quick-translate-text', (event) => {
-          callback(event.payload as string);
+          callback(event.payload as QuickTranslateRequest);
         }).then((fn) => {
           unlisten = fn;
         });
@@ -160,6 +553,74 @@ export const platform = {
     return () => unlisten?.();
   },
 
+  /**
+   * Tray menu: recent translations
+   */
+  async addRecentTranslation(text: string, translation: string): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('add_recent_translation', { text, translation });
+    }
+  },
+
+  /**
+   * Per-application rules (provider, languages, prompt, never capture)
+   */
+  async getAppRules(): Promise<AppRule[]> {
+    await initTauri();
+    if (tauriInvoke) {
+      return tauriInvoke('get_app_rules') as Promise<AppRule[]>;
+    }
+    return [];
+  },
+
+  async setAppRules(rules: AppRule[]): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('set_app_rules', { rules });
+    }
+  },
+
+  /**
+   * Replace-selection preview in the quick window
+   */
+  onReplacePreview(callback: (preview: ReplacePreview) => void): () => void {
+    let unlisten: (() => void) | null = null;
+    initTauri().then(() => {
+      if (tauriEvent) {
+        tauriEvent.listen('replace-preview', (event) => {
+          callback(event.payload as ReplacePreview);
+        }).then((fn) => {
+          unlisten = fn;
+        });
+      }
+    });
+    return () => unlisten?.();
+  },
+
+  async confirmReplace(translation: string): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('confirm_replace', { translation });
+    }
+  },
+
+  async cancelReplace(): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('cancel_replace');
+    }
+  },
+
+  /** Tells the backend the main window listens, so the startup action can run */
+  sendMainReady(): void {
+    if (mainReadySent) return;
+    mainReadySent = true;
+    Promise.allSettled(pendingListeners.splice(0))
+      .then(() => initTauri())
+      .then(() => tauriInvoke?.('main_window_ready'));
+  },
+
   sendQuickReady(): void {
     initTauri().then(() => {
       if (tauriInvoke) {
@@ -168,6 +629,32 @@ export const platform = {
     });
   },
 
+  /**
+   * Pinned translations: detached copies of the quick window's result
+   */
+  async pinQuickWindow(content: PinnedContent): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('pin_quick_window', { content });
+    }
+  },
+
+  async getPinnedContent(): Promise<PinnedContent | null> {
+    await initTauri();
+    if (tauriInvoke) {
+      return await tauriInvoke('get_pinned_content') as PinnedContent;
+    }
+    return null;
+  },
+
+  closePinnedWindow(): void {
+    initTauri().then(() => {
+      if (tauriInvoke) {
+        tauriInvoke('close_pinned_window');
+      }
+    });
+  },
+
   closeQuickWindow(): void {
     initTauri().then(() => {
       if (tauriInvoke) {
@@ -202,6 +689,16 @@ export const platform = {
     }
   },
 
+  /**
+   * Gives the main window back the size it had before resizeMainWindow
+   */
+  async restoreMainWindowSize(): Promise<void> {
+    await initTauri();
+    if (tauriInvoke) {
+      await tauriInvoke('restore_main_window_size');
+    }
+  },
+
   async resizeMainWindow(dimensions: WindowDimensions): Promise<void> {
     await initTauri();
     if (tauriInvoke) {
@@ -214,15 +711,15 @@ export const platform = {
    */
   onOpenSettings(callback: () => void): () => void {
     let unlisten: (() => void) | null = null;
-    initTauri().then(() => {
+    trackListener(initTauri().then(() => {
       if (tauriEvent) {
-        tauriEvent.listen('open-settings', () => {
+        return tauriEvent.listen('open-settings', () => {
           callback();
         }).then((fn) => {
           unlisten = fn;
         });
       }
-    });
+    }));
     return () => unlisten?.();
   },
 
@@ -253,55 +750,52 @@ export const platform = {
    */
   onOcrResult(callback: (text: string) => void): () => void {
     let unlisten: (() => void) | null = null;
-    initTauri().then(() => {
+    trackListener(initTauri().then(() => {
       if (tauriEvent) {
-        tauriEvent.listen('ocr-result', (event) => {
+        return tauriEvent.listen('ocr-result', (event) => {
           callback(event.payload as string);
         }).then((fn) => {
           unlisten = fn;
         });
       }
-    });
+    }));
     return () => unlisten?.();
   },
 
   /**
-   * Proxy settings
-   */
-  async setProxy(settings: ProxySettings): Promise<void> {
-    await initTauri();
-    if (tauriInvoke) {
-      await tauriInvoke('set_proxy', { settings });
-    }
-  },
-
-  /**
-   * Keyboard shortcut settings
+   * A finished translation to show as is (from a notification)
    */
-  async updateShortcut(shortcut: string): Promise<boolean> {
-    await initTauri();
-    if (tauriInvoke) {
-      return tauriInvoke('update_shortcut', { shortcut }) as Promise<boolean>;
-    }
-    return false;
+  onShowTranslation(callback: (shown: { text: string; translation: string }) => void): () => void {
+    let unlisten: (() => void) | null = null;
+    trackListener(initTauri().then(() => {
+      if (tauriEvent) {
+        return tauriEvent.listen('show-translation', (event) => {
+          callback(event.payload as { text: string; translation: string });
+        }).then((fn) => {
+          unlisten = fn;
+        });
+      }
+    }));
+    return () => unlisten?.();
   },
 
   /**
-   * Auto-launch settings
+   * Browser extension native messaging host manifests (Firefox, Chromium)
    */
-  async setAutoLaunch(enabled: boolean): Promise<void> {
+  async installNativeMessagingHost(): Promise<string[]> {
     await initTauri();
     if (tauriInvoke) {
-      await tauriInvoke('set_auto_launch', { enabled });
+      return tauriInvoke('install_native_messaging_host') as Promise<string[]>;
     }
+    return [];
   },
 
-  async getAutoLaunch(): Promise<boolean> {
+  async uninstallNativeMessagingHost(): Promise<string[]> {
     await initTauri();
     if (tauriInvoke) {
-      return tauriInvoke('get_auto_launch') as Promise<boolean>;
+      return tauriInvoke('uninstall_native_messaging_host') as Promise<string[]>;
     }
-    return false;
+    return [];
   },
 
   /**
@@ -2,6 +2,27 @@ import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { AppSettings, LanguageCode, TranslationProviderId, ModelVerificationState } from '../types';
 import { DEFAULT_SETTINGS } from '../constants';
+import { platform } from '../src/lib/platform';
+
+/** Only settings keys are sent to the backend, UI state stays local */
+const pickSettings = (values: Partial<AppState>): Partial<AppSettings> =>
+  Object.fromEntries(
+    Object.entries(values).filter(([key]) => key in DEFAULT_SETTINGS)
+  ) as Partial<AppSettings>;
+
+/**
+ * Sends changed settings to the backend, which owns them. On rejection the
+ * store is reset to the backend's copy.
+ */
+const pushSettings = (values: Partial<AppState>) => {
+  const patch = pickSettings(values);
+  if (!platform.isAvailable() || Object.keys(patch).length === 0) return;
+  platform.updateSettings(patch).catch(async (error) => {
+    console.error('Failed to update settings:', error);
+    const settings = await platform.getSettings();
+    if (settings) useAppStore.getState().applyBackendSettings(settings);
+  });
+};
 
 // OCR dependency status
 interface OcrStatus {
@@ -32,6 +53,8 @@ interface AppState extends AppSettings {
   setProvider: (id: TranslationProviderId) => void;
   toggleAutoTranslate: () => void;
   updateSettings: (settings: Partial<AppSettings>) => void;
+  /** Takes over settings from the backend without sending them back */
+  applyBackendSettings: (settings: AppSettings) => void;
 
   // UI State (not persisted)
   inputText: string;
@@ -55,61 +78,70 @@ interface AppState extends AppSettings {
 
 export const useAppStore = create<AppState>()(
   persist(
-    (set) => ({
-      ...DEFAULT_SETTINGS,
-
-      // UI State Defaults
-      inputText: '',
-      translatedText: '',
-      isTranslating: false,
-      errorMessage: null,
+    (set, get) => {
+      // Settings are applied locally right away and then sent to the backend
+      const change = (values: Partial<AppSettings>) => {
+        set(values);
+        pushSettings(values);
+      };
 
-      // OCR State Defaults (not persisted - checked at runtime)
-      ocrStatus: {
-        available: true, // Assume available until checked
-        checked: false,
-        checking: false,
-        installing: false,
-        message: null,
-        details: null,
-      },
+      return {
+        ...DEFAULT_SETTINGS,
 
-      // Model Verification State Defaults (not persisted - verified at runtime)
-      modelVerification: {
-        isVerifying: false,
-        verifiedIdentity: null,
-        lastVerifiedAt: null,
-        error: null,
-      },
+        // UI State Defaults
+        inputText: '',
+        translatedText: '',
+        isTranslating: false,
+        errorMessage: null,
 
-      // Actions
-      setSourceLang: (lang) => set({ sourceLang: lang }),
-      setTargetLang: (lang) => set({ targetLang: lang }),
-      setQuickSourceLang: (lang) => set({ quickSourceLang: lang }),
-      setQuickTargetLang: (lang) => set({ quickTargetLang: lang }),
-      setProvider: (id) => set({ provider: id }),
-      toggleAutoTranslate: () => set((state) => ({ autoTranslate: !state.autoTranslate })),
-      updateSettings: (newSettings) => set((state) => ({ ...state, ...newSettings })),
+        // OCR State Defaults (not persisted - checked at runtime)
+        ocrStatus: {
+          available: true, // Assume available until checked
+          checked: false,
+          checking: false,
+          installing: false,
+          message: null,
+          details: null,
+        },
 
-      setInputText: (text) => set({ inputText: text }),
-      setTranslatedText: (text) => set({ translatedText: text }),
-      setIsTranslating: (loading) => set({ isTranslating: loading }),
-      setErrorMessage: (msg) => set({ errorMessage: msg }),
-      setOcrStatus: (status) => set((state) => ({
-        ocrStatus: { ...state.ocrStatus, ...status }
-      })),
-      setModelVerification: (verificationState) => set((state) => ({
-        modelVerification: { ...state.modelVerification, ...verificationState }
-      })),
-      clearModelVerification: () => set({
+        // Model Verification State Defaults (not persisted - verified at runtime)
         modelVerification: {
           isVerifying: false,
           verifiedIdentity: null,
           lastVerifiedAt: null,
           error: null,
-        }
-      }),
-    }),
+        },
+
+        // Actions
+        setSourceLang: (lang) => change({ sourceLang: lang }),
+        setTargetLang: (lang) => change({ targetLang: lang }),
+        setQuickSourceLang: (lang) => change({ quickSourceLang: lang }),
+        setQuickTargetLang: (lang) => change({ quickTargetLang: lang }),
+        setProvider: (id) => change({ provider: id }),
+        toggleAutoTranslate: () => change({ autoTranslate: !get().autoTranslate }),
+        updateSettings: (newSettings) => change(newSettings),
+        applyBackendSettings: (settings) => set(pickSettings(settings)),
+
+        setInputText: (text) => set({ inputText: text }),
+        setTranslatedText: (text) => set({ translatedText: text }),
+        setIsTranslating: (loading) => set({ isTranslating: loading }),
+        setErrorMessage: (msg) => set({ errorMessage: msg }),
+        setOcrStatus: (status) => set((state) => ({
+          ocrStatus: { ...state.ocrStatus, ...status }
+        })),
+        setModelVerification: (verificationState) => set((state) => ({
+          modelVerification: { ...state.modelVerification, ...verificationState }
+        })),
+        clearModelVerification: () => set({
+          modelVerification: {
+            isVerifying: false,
+            verifiedIdentity: null,
+            lastVerifiedAt: null,
+            error: null,
+          }
+        }),
+      };
+    },
     {
       name: 'light-translator-storage',
       partialize: (state) => ({
@@ -132,11 +164,61 @@ export const useAppStore = create<AppState>()(
         // OpenRouter
         openrouterApiKey: state.openrouterApiKey,
         openrouterModel: state.openrouterModel,
+        anthropicApiKey: state.anthropicApiKey,
+        anthropicBaseUrl: state.anthropicBaseUrl,
+        anthropicModel: state.anthropicModel,
+        azureOpenaiEndpoint: state.azureOpenaiEndpoint,
+        azureOpenaiRegion: state.azureOpenaiRegion,
+        azureOpenaiDeployment: state.azureOpenaiDeployment,
+        azureOpenaiApiVersion: state.azureOpenaiApiVersion,
+        azureOpenaiAuth: state.azureOpenaiAuth,
+        azureOpenaiApiKey: state.azureOpenaiApiKey,
+        azureOpenaiTenantId: state.azureOpenaiTenantId,
+        azureOpenaiClientId: state.azureOpenaiClientId,
+        azureOpenaiClientSecret: state.azureOpenaiClientSecret,
+        ollamaBaseUrl: state.ollamaBaseUrl,
+        ollamaModel: state.ollamaModel,
+        ollamaKeepAlive: state.ollamaKeepAlive,
+        ollamaNumCtx: state.ollamaNumCtx,
+        ggufModelPath: state.ggufModelPath,
+        ggufTemplate: state.ggufTemplate,
+        ggufCustomTemplate: state.ggufCustomTemplate,
+        ggufThreads: state.ggufThreads,
+        ggufContextLength: state.ggufContextLength,
         // DeepL
         deeplApiKey: state.deeplApiKey,
+        formality: state.formality,
         // Microsoft
         microsoftSubscriptionKey: state.microsoftSubscriptionKey,
         microsoftRegion: state.microsoftRegion,
+        // LibreTranslate
+        libretranslateBaseUrl: state.libretranslateBaseUrl,
+        libretranslateApiKey: state.libretranslateApiKey,
+        libretranslateFormat: state.libretranslateFormat,
+        libretranslateAlternatives: state.libretranslateAlternatives,
+        // Baidu, Youdao, Tencent
+        baiduAppId: state.baiduAppId,
+        baiduSecretKey: state.baiduSecretKey,
+        youdaoAppKey: state.youdaoAppKey,
+        youdaoAppSecret: state.youdaoAppSecret,
+        tencentSecretId: state.tencentSecretId,
+        tencentSecretKey: state.tencentSecretKey,
+        tencentRegion: state.tencentRegion,
+        // Amazon, Google Cloud
+        awsAccessKeyId: state.awsAccessKeyId,
+        awsSecretAccessKey: state.awsSecretAccessKey,
+        awsProfile: state.awsProfile,
+        awsRegion: state.awsRegion,
+        awsTerminology: state.awsTerminology,
+        awsEndpoint: state.awsEndpoint,
+        googleCloudCredentialsPath: state.googleCloudCredentialsPath,
+        googleCloudProjectId: state.googleCloudProjectId,
+        googleCloudLocation: state.googleCloudLocation,
+        googleCloudGlossary: state.googleCloudGlossary,
+        googleCloudEndpoint: state.googleCloudEndpoint,
+        offlineModelsDir: state.offlineModelsDir,
+        offlineThreads: state.offlineThreads,
+        offlineMemoryLimitMb: state.offlineMemoryLimitMb,
         // Proxy
         proxyEnabled: state.proxyEnabled,
         proxyProtocol: state.proxyProtocol,
@@ -144,16 +226,27 @@ export const useAppStore = create<AppState>()(
         proxyPort: state.proxyPort,
         proxyUsername: state.proxyUsername,
         proxyPassword: state.proxyPassword,
+        proxyBypass: state.proxyBypass,
         // Shortcut
         selectionShortcut: state.selectionShortcut,
+        // Result Output
+        quickTranslateOutput: state.quickTranslateOutput,
+        ocrOutput: state.ocrOutput,
+        // Replace Selection
+        replaceShortcut: state.replaceShortcut,
+        writingLang: state.writingLang,
+        replacePreview: state.replacePreview,
         // Startup
         launchAtStartup: state.launchAtStartup,
         // Quick Window Appearance
         quickWindowOpacity: state.quickWindowOpacity,
         quickWindowBorderOpacity: state.quickWindowBorderOpacity,
+        quickWindowPlacement: state.quickWindowPlacement,
         // Quick Window Language
         quickSourceLang: state.quickSourceLang,
         quickTargetLang: state.quickTargetLang,
+        // Clipboard Watch
+        clipboardWatch: state.clipboardWatch,
       }),
     }
   )
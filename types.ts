@@ -1,3 +1,7 @@
+export type OutputMode = 'window' | 'notification';
+
+export type QuickPlacement = 'cursor' | 'selection' | 'topLeft' | 'topRight' | 'bottomLeft' | 'bottomRight' | 'center';
+
 export type LanguageCode = 'auto' | 'en' | 'zh-CN' | 'zh-TW' | 'ja' | 'ko' | 'fr' | 'de' | 'es' | 'ru';
 
 export interface Language {
@@ -5,7 +9,9 @@ export interface Language {
   name: string;
 }
 
-export type TranslationProviderId = 'gemini' | 'openai' | 'openrouter' | 'deepl' | 'google' | 'microsoft';
+export type GgufTemplate = 'chatml' | 'llama3' | 'gemma' | 'mistral' | 'custom';
+
+export type TranslationProviderId = 'gemini' | 'openai' | 'openrouter' | 'anthropic' | 'azure_openai' | 'ollama' | 'gguf' | 'deepl' | 'google' | 'microsoft' | 'libretranslate' | 'baidu' | 'youdao' | 'tencent' | 'amazon' | 'google_cloud' | 'offline' | `custom:${string}` | `plugin:${string}`;
 
 export type ProviderCategory = 'llm' | 'cloud';
 
@@ -41,13 +47,83 @@ export interface AppSettings {
   openrouterApiKey: string;
   openrouterModel: string;
 
+  // Anthropic Specific
+  anthropicApiKey: string;
+  anthropicBaseUrl: string;
+  anthropicModel: string;
+
+  // Azure OpenAI Specific
+  azureOpenaiEndpoint: string;
+  azureOpenaiRegion: string;
+  azureOpenaiDeployment: string;
+  azureOpenaiApiVersion: string;
+  azureOpenaiAuth: 'key' | 'entra';
+  azureOpenaiApiKey: string;
+  azureOpenaiTenantId: string;
+  azureOpenaiClientId: string;
+  azureOpenaiClientSecret: string;
+
+  // Ollama Specific
+  ollamaBaseUrl: string;
+  ollamaModel: string;
+  ollamaKeepAlive: string;
+  ollamaNumCtx: number;
+
+  // Local GGUF (llama.cpp) Specific
+  ggufModelPath: string;
+  ggufTemplate: GgufTemplate;
+  ggufCustomTemplate: string;
+  ggufThreads: number;
+  ggufContextLength: number;
+
   // DeepL Specific
   deeplApiKey: string;
+  /** Also used by Amazon Translate; empty for the provider's default */
+  formality: '' | 'formal' | 'informal';
 
   // Microsoft Translator Specific
   microsoftSubscriptionKey: string;
   microsoftRegion: string;
 
+  // LibreTranslate Specific
+  libretranslateBaseUrl: string;
+  libretranslateApiKey: string;
+  libretranslateFormat: 'text' | 'html';
+  libretranslateAlternatives: number;
+
+  // Baidu Translate Specific
+  baiduAppId: string;
+  baiduSecretKey: string;
+
+  // Youdao Translate Specific
+  youdaoAppKey: string;
+  youdaoAppSecret: string;
+
+  // Tencent Cloud TMT Specific
+  tencentSecretId: string;
+  tencentSecretKey: string;
+  tencentRegion: string;
+
+  // Amazon Translate Specific
+  awsAccessKeyId: string;
+  awsSecretAccessKey: string;
+  awsProfile: string;
+  awsRegion: string;
+  awsTerminology: string;
+  awsEndpoint: string;
+
+  // Google Cloud Translation Specific
+  googleCloudCredentialsPath: string;
+  googleCloudProjectId: string;
+  googleCloudLocation: string;
+  googleCloudGlossary: string;
+  googleCloudEndpoint: string;
+
+  // Offline (Marian) Specific
+  offlineModelsDir: string;
+  offlineThreads: number;
+  offlineMemoryLimitMb: number;
+
   // Proxy Settings
   proxyEnabled: boolean;
   proxyProtocol: 'http' | 'https' | 'socks5';
@@ -55,20 +131,43 @@ export interface AppSettings {
   proxyPort: number;
   proxyUsername: string;
   proxyPassword: string;
+  proxyBypass: string;
 
   // Shortcut Settings
   selectionShortcut: string;
 
+  // Result Output Settings
+  quickTranslateOutput: OutputMode;
+  ocrOutput: OutputMode;
+
+  // Replace-Selection Settings
+  replaceShortcut: string;
+  writingLang: LanguageCode;
+  replacePreview: boolean;
+
   // Startup Settings
   launchAtStartup: boolean;
 
   // Quick Window Appearance
   quickWindowOpacity: number; // 0.5-1.0
   quickWindowBorderOpacity: number; // 0-1.0
+  quickWindowPlacement: QuickPlacement;
 
   // Quick Window Language Settings (independent from main panel)
   quickSourceLang: LanguageCode;
   quickTargetLang: LanguageCode;
+
+  // Clipboard Watch (also toggled from the tray)
+  clipboardWatch: ClipboardWatchSettings;
+}
+
+export interface ClipboardWatchSettings {
+  enabled: boolean;
+  watchPrimary: boolean;
+  minLength: number;
+  output: 'quick' | 'notification';
+  skipTargetLanguage: boolean;
+  denylist: string[];
 }
 
 export interface ProxySettings {